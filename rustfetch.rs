@@ -5,6 +5,7 @@ use std::{
     process::Command,
     thread,
     collections::HashMap,
+    sync::atomic::{AtomicU8, Ordering},
     time::{SystemTime, UNIX_EPOCH},
     io::Write,
 };
@@ -14,12 +15,80 @@ use std::{
 // ============================================================================
 
 const LOG_FILE: &str = "/tmp/rustfetch_log";
-const LOG_ENABLED: bool = true;
 
-/// Logs a message to the rustfetch log file with timestamp and severity level.
+/// Where log entries go. Off by default; set with `--log` or `RUSTFETCH_LOG`.
+#[derive(Clone, Copy, PartialEq)]
+enum LogSink {
+    Off = 0,
+    File = 1,
+    Stderr = 2,
+}
+
+static LOG_SINK: AtomicU8 = AtomicU8::new(LogSink::Off as u8);
+
+/// Severity, least to most verbose, so `level <= threshold` means "keep".
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum LogLevel {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+}
+
+impl LogLevel {
+    fn tag(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARNING",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+        }
+    }
+}
+
+/// Entries more verbose than this are dropped; `RUSTFETCH_LOG_LEVEL` sets it.
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Debug as u8);
+
+fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+fn parse_log_level(value: &str) -> Result<LogLevel, String> {
+    match value.trim().to_lowercase().as_str() {
+        "error" => Ok(LogLevel::Error),
+        "warn" | "warning" => Ok(LogLevel::Warn),
+        "info" => Ok(LogLevel::Info),
+        "debug" => Ok(LogLevel::Debug),
+        _ => Err(format!("unknown log level '{}' (expected error, warn, info or debug)", value)),
+    }
+}
+
+fn set_log_sink(sink: LogSink) {
+    LOG_SINK.store(sink as u8, Ordering::Relaxed);
+}
+
+fn log_sink() -> LogSink {
+    match LOG_SINK.load(Ordering::Relaxed) {
+        1 => LogSink::File,
+        2 => LogSink::Stderr,
+        _ => LogSink::Off,
+    }
+}
+
+fn parse_log_sink(value: &str) -> Result<LogSink, String> {
+    match value.trim().to_lowercase().as_str() {
+        "off" | "" => Ok(LogSink::Off),
+        "file" => Ok(LogSink::File),
+        "stderr" => Ok(LogSink::Stderr),
+        _ => Err(format!("unknown log target '{}' (expected off, file or stderr)", value)),
+    }
+}
+
+/// Logs a message to the configured sink with timestamp and severity level.
 /// This function provides detailed, human-readable logging for debugging and monitoring.
-fn log_message(level: &str, category: &str, message: &str) {
-    if !LOG_ENABLED {
+fn log_message(level: LogLevel, category: &str, message: &str) {
+    let sink = log_sink();
+    if sink == LogSink::Off || level as u8 > LOG_LEVEL.load(Ordering::Relaxed) {
         return;
     }
     
@@ -42,9 +111,14 @@ fn log_message(level: &str, category: &str, message: &str) {
     
     let log_entry = format!(
         "[{}] [{:7}] [{}] {}\n",
-        timestamp, level, category, message
+        timestamp, level.tag(), category, message
     );
     
+    if sink == LogSink::Stderr {
+        let _ = std::io::stderr().write_all(log_entry.as_bytes());
+        return;
+    }
+    
     // Try to append to log file, create if it doesn't exist
     if let Ok(mut file) = fs::OpenOptions::new()
         .create(true)
@@ -57,22 +131,22 @@ fn log_message(level: &str, category: &str, message: &str) {
 
 /// Logs an informational message - routine operations and status updates
 fn log_info(category: &str, message: &str) {
-    log_message("INFO", category, message);
+    log_message(LogLevel::Info, category, message);
 }
 
 /// Logs a warning message - unexpected but non-critical issues
 fn log_warn(category: &str, message: &str) {
-    log_message("WARNING", category, message);
+    log_message(LogLevel::Warn, category, message);
 }
 
 /// Logs an error message - critical failures that prevent normal operation
 fn log_error(category: &str, message: &str) {
-    log_message("ERROR", category, message);
+    log_message(LogLevel::Error, category, message);
 }
 
 /// Logs a debug message - detailed information for troubleshooting
 fn log_debug(category: &str, message: &str) {
-    log_message("DEBUG", category, message);
+    log_message(LogLevel::Debug, category, message);
 }
 
 // ============================================================================
@@ -80,6 +154,9 @@ fn log_debug(category: &str, message: &str) {
 // ============================================================================
 
 const VERSION: &str = "0.2.0";
+/// Shape of the `--json` / cache output. Bump whenever a field is renamed,
+/// removed or changes type so consumers (and the cache loader) can tell.
+const JSON_SCHEMA_VERSION: u32 = 3;
 const PROGRAM_NAME: &str = "rustfetch";
 
 macro_rules! module {
@@ -99,102 +176,255 @@ macro_rules! module {
 #[derive(Clone)]
 struct Config {
     use_color: bool,
+    /// `--strip-ansi`: remove every escape from the rendered output, whatever the color setting
+    strip_ansi: bool,
     color_scheme: String,
     json_output: bool,
-    cache_enabled: bool,
+    /// `--json-pretty`: indent the one-shot JSON output (watch mode stays one line per object)
+    json_pretty: bool,
+    prometheus_output: bool,
+    /// Use cached results (package count and bootloader, public IP)
+    cache_read: bool,
+    /// Write the caches after collecting; off when another process owns them
+    cache_write: bool,
+    /// `--warm-cache`: collect and write the cache, print nothing
+    warm_cache: bool,
+    /// WSL major version when running under Windows Subsystem for Linux
+    wsl: Option<u8>,
+    /// `--preview-theme`: print this theme's colors and exit
+    preview_theme: Option<String>,
     cache_ttl: u64,
+    public_ip_ttl: u64,
     fast_mode: bool,
     benchmark: bool,
+    image_path: Option<String>,
+    width: Option<usize>,
+    use_glyphs: bool,
+    glyph_overrides: HashMap<String, String>,
+    custom_theme: HashMap<String, (u8, u8, u8)>,
+    custom_modules: Vec<CustomModule>,
+    bar_style: BarStyle,
+    mem_unit: MemUnit,
+    logo_size: LogoSize,
+    logo_position: LogoPosition,
+    ascii_distro: Option<String>,
+    palette: usize,
+    color_depth: ColorDepth,
+    utc: bool,
+    compact: bool,
+    /// `--group`: system / hardware / network / desktop sections with a rule between them
+    group_sections: bool,
+    watch: Option<u64>,
+    /// `--field <PATH>`: print just this JSON value
+    field: Option<String>,
+    /// `--output <FILE>`: write the text, JSON or Prometheus output here instead of stdout
+    output: Option<String>,
+    bar_warn_pct: Option<u8>,
+    bar_crit_pct: Option<u8>,
     show_os: bool,
     show_kernel: bool,
+    show_kernel_arch: bool,
+    show_kernel_check: bool,
     show_uptime: bool,
     show_boot_time: bool,
+    show_boot_duration: bool,
     show_bootloader: bool,
     show_packages: bool,
+    show_updates: bool,
+    packages_format: PackagesFormat,
+    os_format: OsFormat,
+    uptime_precision: UptimePrecision,
     show_shell: bool,
+    show_shell_version: bool,
     show_de: bool,
+    show_de_version: bool,
     show_wm: bool,
     show_init: bool,
+    show_init_version: bool,
     show_terminal: bool,
+    show_terminal_font: bool,
+    show_term_size: bool,
+    /// `--detect-terminal-bg`: ask the terminal for its background (OSC 11)
+    detect_terminal_bg: bool,
+    /// "dark" or "light" once detected
+    terminal_bg: Option<String>,
     show_cpu: bool,
     show_cpu_temp: bool,
     show_gpu: bool,
     show_memory: bool,
     show_swap: bool,
+    show_swap_detail: bool,
+    show_memory_detail: bool,
+    show_memory_pressure: bool,
+    /// Add CPU and IO pressure lines next to memory pressure
+    show_pressure_all: bool,
     show_partitions: bool,
+    show_disk_fstype: bool,
     show_network: bool,
+    show_gateway: bool,
+    show_dns: bool,
+    show_vpn: bool,
     show_network_ping: bool,
+    net_ifaces: Vec<String>,
+    all_net: bool,
+    ping_host: String,
+    ping_count: u32,
+    ping_timeout: u32,
     show_display: bool,
     show_battery: bool,
+    show_battery_power: bool,
+    show_power: bool,
     show_colors: bool,
     show_model: bool,
+    show_chassis: bool,
     show_motherboard: bool,
+    show_mobo_temp: bool,
+    show_fan: bool,
     show_bios: bool,
     show_theme: bool,
     show_icons: bool,
     show_font: bool,
     show_processes: bool,
+    show_processes_detail: bool,
     show_cpu_freq: bool,
     show_locale: bool,
+    show_locale_detail: bool,
+    show_audio: bool,
     show_public_ip: bool,
     show_cpu_cores: bool,
     show_cpu_cache: bool,
+    show_cpu_features: bool,
+    show_cpu_uarch: bool,
+    show_governor: bool,
     show_gpu_vram: bool,
     show_resolution: bool,
     show_entropy: bool,
     show_users: bool,
     show_failed_units: bool,
+    show_failed_detail: bool,
+    show_container: bool,
+    show_virtualization: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             use_color: true,
+            strip_ansi: false,
             color_scheme: "classic".to_string(),
             json_output: false,
-            cache_enabled: true,
+            json_pretty: false,
+            prometheus_output: false,
+            cache_read: true,
+            cache_write: true,
+            warm_cache: false,
+            wsl: None,
+            preview_theme: None,
             cache_ttl: 60,
+            public_ip_ttl: 3600,
             fast_mode: false,
             benchmark: false,
+            image_path: None,
+            width: None,
+            use_glyphs: false,
+            glyph_overrides: HashMap::new(),
+            custom_theme: HashMap::new(),
+            custom_modules: Vec::new(),
+            bar_style: BarStyle::Blocks,
+            mem_unit: MemUnit::Gib,
+            logo_size: LogoSize::Normal,
+            logo_position: LogoPosition::Left,
+            ascii_distro: None,
+            palette: 6,
+            color_depth: ColorDepth::Auto,
+            utc: false,
+            compact: false,
+            group_sections: false,
+            watch: None,
+            output: None,
+            field: None,
+            bar_warn_pct: Some(70),
+            bar_crit_pct: Some(90),
             show_os: true,
             show_kernel: true,
+            show_kernel_arch: false,
+            show_kernel_check: false,
             show_uptime: true,
             show_boot_time: true,
+            show_boot_duration: false,
             show_bootloader: true,
             show_packages: true,
+            show_updates: false,
+            packages_format: PackagesFormat::Full,
+            os_format: OsFormat::Full,
+            uptime_precision: UptimePrecision::Auto,
             show_shell: true,
+            show_shell_version: false,
+            show_de_version: false,
+            show_init_version: false,
             show_de: true,
             show_wm: true,
             show_init: true,
             show_terminal: true,
+            show_terminal_font: false,
+            show_term_size: false,
+            detect_terminal_bg: false,
+            terminal_bg: None,
             show_cpu: true,
             show_cpu_temp: true,
             show_gpu: true,
             show_memory: true,
             show_swap: true,
+            show_swap_detail: true,
+            show_memory_detail: false,
+            show_memory_pressure: false,
+            show_pressure_all: false,
             show_partitions: true,
+            show_disk_fstype: false,
             show_network: true,
+            show_gateway: true,
+            show_dns: true,
+            show_vpn: true,
             show_network_ping: false,
+            net_ifaces: Vec::new(),
+            all_net: false,
+            ping_host: "1.1.1.1".to_string(),
+            ping_count: 2,
+            ping_timeout: 1,
             show_display: true,
             show_battery: true,
+            show_battery_power: false,
+            show_power: false,
             show_colors: true,
             show_model: true,
+            show_chassis: true,
             show_motherboard: true,
+            show_mobo_temp: true,
+            show_fan: true,
             show_bios: true,
             show_theme: true,
             show_icons: true,
             show_font: true,
             show_processes: true,
+            show_processes_detail: false,
             show_cpu_freq: true,
             show_locale: true,
+            show_locale_detail: false,
+            show_audio: true,
             show_public_ip: false,
             show_cpu_cores: true,
             show_cpu_cache: true,
+            show_cpu_features: false,
+            show_cpu_uarch: false,
+            show_governor: false,
             show_gpu_vram: true,
             show_resolution: true,
             show_entropy: true,
             show_users: true,
             show_failed_units: true,
+            show_failed_detail: false,
+            show_container: true,
+            show_virtualization: true,
         }
     }
 }
@@ -208,23 +438,106 @@ USAGE:
 
 OPTIONS:
     -h, --help          Show this help message
+    -V, --version       Print the version (as JSON with --json)
     -j, --json          Output system info as JSON
-    -n, --no-color      Disable colored output
-    -t, --theme <NAME>  Set color theme (classic, pastel, gruvbox, nord, dracula)
-    --no-cache          Disable caching
-    --cache-ttl <SEC>   Set cache TTL in seconds (default: 60)
+    --json-pretty       JSON indented for reading (implies --json)
+    --prometheus        Output numeric metrics in Prometheus text format
+    --list-modules      List module names, default state and labels
+    --field <PATH>      Print one value by its JSON path (memory.used, network.0.ipv4)
+                        and nothing else; exits 1 when it is missing
+    --output <FILE>     Write the output to FILE (parent directories are created);
+                        color is off unless --color is given
+    -n, --no-color      Disable colored output (also NO_COLOR)
+    --color             Force colored output even when piped (also FORCE_COLOR)
+                        Precedence: --color/--no-color (last wins) > NO_COLOR >
+                        FORCE_COLOR > auto-detect (color only on a terminal)
+    --strip-ansi        Remove all escape sequences from the final output; unlike
+                        --no-color the layout is unchanged (an --image logo becomes ASCII)
+    --color-depth <D>   Colors to emit: truecolor, 256, 16 or auto (default)
+    -t, --theme <NAME>  Set color theme (classic, pastel, gruvbox, nord, dracula,
+                        solarized-dark, solarized-light, catppuccin-mocha,
+                        tokyonight, custom = the [theme] section of the config file)
+    --preview-theme <NAME>
+                        Print every color role of a theme as a swatch with its hex value
+    --no-cache          Disable caching (both of the below)
+    --no-cache-read     Don't use cached results
+    --no-cache-write    Don't write the caches (e.g. when a --warm-cache job owns them)
+    --warm-cache        Collect info and write the cache without printing anything
+                        (e.g. from a shell prompt hook, in the background)
+    --cache-ttl <SEC>   How long the package count and bootloader are cached
+                        (default: 60, 0 = never expire)
+    --public-ip-ttl <SEC>
+                        How long the public IP is cached (default: 3600, 0 = never expire)
     --fast              Fast mode - skip expensive operations (temps, ping)
     --benchmark         Show timing for each operation
     --network-ping      Enable network ping tests (slower)
+    --ping-host <HOST>  Host to ping for --network-ping (default: 1.1.1.1)
+    --ping-count <N>    Pings per interface (default: 2)
+    --ping-timeout <SEC>
+                        Seconds to wait for each reply (default: 1)
+    --net-iface <LIST>  Only show these interfaces (comma-separated)
+    --all-net           Also show virtual interfaces (veth, docker, br-, virbr, tun, tap)
+    --image <PATH>      Show a PNG as the logo (kitty/sixel terminals)
+    --width <N>         Override the detected terminal width
+    --icons-glyphs      Prefix each line with a Nerd Font icon
+    --bar-style <STYLE> Usage bar glyphs (blocks, ascii, braille)
+    --mem-unit <UNIT>   Memory, swap and disk amounts in gib (default), gb, mib or percent
+    --logo-size <SIZE>  Logo size (normal, small, none)
+    --packages-format <FMT>
+                        Packages line: full (per manager, default), total, or native
+                        (only the distro's own manager)
+    --os-format <FMT>   OS line: full (PRETTY_NAME, default), short (no codename), or id
+    --uptime-precision <P>
+                        Uptime line: auto ("3d 4h 12m", default), seconds ("3d 4h 12m 7s"),
+                        or compact (largest unit only, "3d")
+    --logo-position <POS>
+                        Logo placement: left (default), right, or top (above the info)
+    --ascii-distro <NAME>
+                        Use this distro's logo instead of the detected one
+    --compact           Drop the user@host underline and the color swatch
+    --group             Sort lines into system, hardware, network and desktop sections
+                        divided by a faint rule (none with --compact)
+    --detect-terminal-bg
+                        Ask the terminal whether its background is dark or light (OSC 11);
+                        a light one picks solarized-light unless --theme is given
+    --palette <N>       Color swatch size: 6, 8 or 16 (two rows)
+    --utc               Show times in UTC instead of the local timezone
+    --watch <SEC>       Refresh every SEC seconds; with --json, print one JSON
+                        object per line (JSON Lines) with a "timestamp" field
+                        (not available with --prometheus or --field)
+    --processes-detail  Break processes down by state (reads every /proc/<pid>/stat)
+    --memory-detail     Show cached, buffers and shared memory on the Memory line
+    --power             Show whether the system runs on AC or battery (also without a battery)
+    --battery-power     Show the battery's charge (+) / discharge (-) rate in watts
+    --locale-detail     Show the effective locale and any LC_ALL / LC_* overrides of $LANG
+    --failed-detail     Name the failed systemd units on the Failed Units line
+    --memory-pressure   Show memory stall time from /proc/pressure (kernel PSI)
+    --pressure-all      Also show CPU and IO pressure
+    --disk-fstype       Show the filesystem type on Disk lines (read-only mounts are always flagged [ro])
+    --kernel-arch       Append the machine architecture to the Kernel line
+    --kernel-check      Flag the Kernel line when a newer kernel is installed than the one running
+    --updates           Count pending updates (checkupdates, apt, dnf; may hit the network,
+                        each check gives up after {}s; skipped with --fast)
+    --boot-duration     Show kernel / userspace boot time from `systemd-analyze` (skipped with --fast)
+    --cpu-features      Show notable CPU extensions (avx2, avx512, aes, sve, ...)
+    --cpu-uarch         Add the microarchitecture (Zen 4, Raptor Lake, ...) to the CPU line
+    --governor          Show the cpufreq scaling governor and energy-performance preference
+    --shell-version     Show the shell version (may run `<shell> --version`)
+    --de-version        Show the GNOME / KDE Plasma version (may run
+                        `gnome-shell --version` or `plasmashell --version`)
+    --init-version      Show the init system's version (may run `systemctl --version`)
+    --log <TARGET>      Debug log: off (default), file ({}), stderr
+                        Also settable with RUSTFETCH_LOG; filter with
+                        RUSTFETCH_LOG_LEVEL=error|warn|info|debug
 
 MODULES:
     --os / --kernel / --uptime / --boot / --packages
     --cpu / --gpu / --memory / --swap / --disk
-    --shell / --terminal / --de / --wm / --init
-    --model / --mobo / --bios / --locale / --public-ip
+    --shell / --terminal / --terminal-font / --term-size / --de / --wm / --init
+    --model / --chassis / --mobo / --mobo-temp / --fan / --bios / --locale / --audio / --public-ip
     --desktop-theme / --icons / --font / --resolution / --entropy
-    --network / --battery / --users / --failed
-    (Most modules enabled by default)
+    --network / --gateway / --dns / --vpn / --battery / --users / --failed / --container / --virt
+    (Most modules enabled by default; add your own with [[custom]] in the config file)
 
 EXAMPLES:
     {}              Show system info with default settings
@@ -232,7 +545,7 @@ EXAMPLES:
     {} --benchmark  Show performance timing
     {} -t gruvbox   Use gruvbox color theme
     {} --network-ping   Enable network latency tests"#,
-        PROGRAM_NAME, VERSION, PROGRAM_NAME, PROGRAM_NAME, PROGRAM_NAME, PROGRAM_NAME, PROGRAM_NAME, PROGRAM_NAME
+        PROGRAM_NAME, VERSION, PROGRAM_NAME, UPDATES_TIMEOUT_SECS, LOG_FILE, PROGRAM_NAME, PROGRAM_NAME, PROGRAM_NAME, PROGRAM_NAME, PROGRAM_NAME
     );
 }
 
@@ -240,8 +553,62 @@ fn parse_args() -> Option<Config> {
     let args: Vec<String> = env::args().collect();
     let mut config = Config::default();
     
+    // Logging is set up before anything else so config file problems get logged too
+    if let Ok(value) = env::var("RUSTFETCH_LOG") {
+        match parse_log_sink(&value) {
+            Ok(sink) => set_log_sink(sink),
+            Err(e) => eprintln!("Warning: RUSTFETCH_LOG: {}", e),
+        }
+    }
+    if let Ok(value) = env::var("RUSTFETCH_LOG_LEVEL") {
+        match parse_log_level(&value) {
+            Ok(level) => set_log_level(level),
+            Err(e) => eprintln!("Warning: RUSTFETCH_LOG_LEVEL: {}", e),
+        }
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--log") {
+        match args.get(pos + 1).map(|v| parse_log_sink(v)) {
+            Some(Ok(sink)) => set_log_sink(sink),
+            Some(Err(e)) => {
+                eprintln!("Error: {}", e);
+                return None;
+            }
+            None => {
+                eprintln!("Error: --log requires one of off, file, stderr");
+                return None;
+            }
+        }
+    }
+    
+    // WSL has no battery, sensors, DMI tables or bootloader of its own; the
+    // config file and flags below can still turn those modules back on
+    config.wsl = detect_wsl();
+    if let Some(version) = config.wsl {
+        log_info("WSL", &format!("Running under WSL{}, hiding hardware-only modules", version));
+        config.show_battery = false;
+        config.show_cpu_temp = false;
+        config.show_mobo_temp = false;
+        config.show_fan = false;
+        config.show_bootloader = false;
+        config.show_model = false;
+        config.show_chassis = false;
+        config.show_motherboard = false;
+        config.show_bios = false;
+    }
+
+    // defaults -> system-wide file -> user file -> flags; each layer only sets the keys it has
+    load_config_file(&mut config, SYSTEM_CONFIG_FILE);
+    if let Some(path) = config_file_path() {
+        load_config_file(&mut config, &path);
+    }
+    
+    // Explicit color choice; None means "color if stdout is a terminal"
+    let mut force_color: Option<bool> = None;
+    let mut theme_chosen = false;
     if env::var("NO_COLOR").is_ok() {
-        config.use_color = false;
+        force_color = Some(false);
+    } else if env::var("FORCE_COLOR").is_ok_and(|v| v != "0") {
+        force_color = Some(true);
     }
     
     let mut i = 1;
@@ -251,27 +618,84 @@ fn parse_args() -> Option<Config> {
                 print_help();
                 return None;
             }
+            "-V" | "--version" => {
+                // --json may come before or after
+                if args.iter().any(|a| a == "-j" || a == "--json") {
+                    println!("{{\"name\":{},\"version\":{}}}", PROGRAM_NAME.to_string().to_json(), VERSION.to_string().to_json());
+                } else {
+                    println!("{} {}", PROGRAM_NAME, VERSION);
+                }
+                return None;
+            }
+            "--list-modules" => {
+                print_module_list(&config);
+                return None;
+            }
             "-j" | "--json" => {
                 config.json_output = true;
-                config.use_color = false;
+            }
+            "--json-pretty" => {
+                config.json_output = true;
+                config.json_pretty = true;
+            }
+            "--prometheus" => {
+                config.prometheus_output = true;
             }
             "-n" | "--no-color" => {
-                config.use_color = false;
+                force_color = Some(false);
+            }
+            "--color" => {
+                force_color = Some(true);
+            }
+            "--strip-ansi" => {
+                config.strip_ansi = true;
             }
             "--no-cache" => {
-                config.cache_enabled = false;
+                config.cache_read = false;
+                config.cache_write = false;
+            }
+            "--no-cache-read" => config.cache_read = false,
+            "--no-cache-write" => config.cache_write = false,
+            "--warm-cache" => {
+                config.warm_cache = true;
             }
             "--cache-ttl" => {
                 i += 1;
                 if i < args.len() {
-                    config.cache_ttl = args[i].parse().unwrap_or(60);
+                    match parse_cache_ttl(&args[i]) {
+                        Ok(ttl) => config.cache_ttl = ttl,
+                        Err(e) => {
+                            log_error("CONFIG", &e);
+                            eprintln!("Error: {}", e);
+                            return None;
+                        }
+                    }
+                } else {
+                    eprintln!("Error: --cache-ttl requires a number of seconds");
+                    return None;
+                }
+            }
+            "--public-ip-ttl" => {
+                i += 1;
+                match args.get(i).map(|v| parse_public_ip_ttl(v)) {
+                    Some(Ok(ttl)) => config.public_ip_ttl = ttl,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --public-ip-ttl requires a number of seconds");
+                        return None;
+                    }
                 }
             }
             "--fast" => {
                 config.fast_mode = true;
                 config.show_cpu_temp = false;
+                config.show_mobo_temp = false;
                 config.show_network_ping = false;
                 config.show_public_ip = false;
+                config.show_updates = false;
             }
             "--benchmark" => {
                 config.benchmark = true;
@@ -279,40 +703,335 @@ fn parse_args() -> Option<Config> {
             "--network-ping" => {
                 config.show_network_ping = true;
             }
+            "--ping-host" => {
+                i += 1;
+                match args.get(i).map(|v| parse_ping_host(v)) {
+                    Some(Ok(v)) => config.ping_host = v,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --ping-host requires a host name or address");
+                        return None;
+                    }
+                }
+            }
+            "--ping-count" => {
+                i += 1;
+                match args.get(i).map(|v| parse_ping_count(v)) {
+                    Some(Ok(v)) => config.ping_count = v,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --ping-count requires a number of pings");
+                        return None;
+                    }
+                }
+            }
+            "--ping-timeout" => {
+                i += 1;
+                match args.get(i).map(|v| parse_ping_timeout(v)) {
+                    Some(Ok(v)) => config.ping_timeout = v,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --ping-timeout requires a number of seconds");
+                        return None;
+                    }
+                }
+            }
+            "--net-iface" => {
+                i += 1;
+                match args.get(i).map(|v| parse_iface_list(v)) {
+                    Some(Ok(list)) => config.net_ifaces = list,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --net-iface requires a comma-separated list of interfaces");
+                        return None;
+                    }
+                }
+            }
+            "--group" => config.group_sections = true,
+            "--no-group" => config.group_sections = false,
+            "--compact" => {
+                config.compact = true;
+            }
+            "--all-net" => {
+                config.all_net = true;
+            }
+            "--log" => {
+                // Already applied before the config file was read
+                i += 1;
+            }
+            "--color-depth" => {
+                i += 1;
+                match args.get(i).map(|v| parse_color_depth(v)) {
+                    Some(Ok(depth)) => config.color_depth = depth,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --color-depth requires one of truecolor, 256, 16, auto");
+                        return None;
+                    }
+                }
+            }
+            "--utc" => {
+                config.utc = true;
+            }
+            "--icons-glyphs" => {
+                config.use_glyphs = true;
+            }
+            "--field" => {
+                i += 1;
+                match args.get(i).filter(|v| !v.trim().is_empty()) {
+                    Some(path) => config.field = Some(path.trim().to_string()),
+                    None => {
+                        eprintln!("Error: --field requires a dotted JSON path, e.g. memory.used");
+                        return None;
+                    }
+                }
+            }
+            "--output" => {
+                i += 1;
+                match args.get(i) {
+                    Some(path) if !path.is_empty() => config.output = Some(path.clone()),
+                    _ => {
+                        eprintln!("Error: --output requires a file path");
+                        return None;
+                    }
+                }
+            }
+            "--watch" => {
+                i += 1;
+                match args.get(i).map(|v| parse_watch_interval(v)) {
+                    Some(Ok(secs)) => config.watch = Some(secs),
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --watch requires an interval in seconds");
+                        return None;
+                    }
+                }
+            }
+            "--width" => {
+                i += 1;
+                match args.get(i).map(|v| parse_width(v)) {
+                    Some(Ok(w)) => config.width = Some(w),
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --width requires a number of columns");
+                        return None;
+                    }
+                }
+            }
+            "--bar-style" => {
+                i += 1;
+                match args.get(i).map(|v| parse_bar_style(v)) {
+                    Some(Ok(style)) => config.bar_style = style,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --bar-style requires one of blocks, ascii, braille");
+                        return None;
+                    }
+                }
+            }
+            "--mem-unit" => {
+                i += 1;
+                match args.get(i).map(|v| parse_mem_unit(v)) {
+                    Some(Ok(unit)) => config.mem_unit = unit,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --mem-unit requires one of gib, gb, mib, percent");
+                        return None;
+                    }
+                }
+            }
+            "--logo-size" => {
+                i += 1;
+                match args.get(i).map(|v| parse_logo_size(v)) {
+                    Some(Ok(size)) => config.logo_size = size,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --logo-size requires one of normal, small, none");
+                        return None;
+                    }
+                }
+            }
+            "--packages-format" => {
+                i += 1;
+                match args.get(i).map(|v| parse_packages_format(v)) {
+                    Some(Ok(format)) => config.packages_format = format,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --packages-format requires one of full, total, native");
+                        return None;
+                    }
+                }
+            }
+            "--os-format" => {
+                i += 1;
+                match args.get(i).map(|v| parse_os_format(v)) {
+                    Some(Ok(format)) => config.os_format = format,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --os-format requires one of full, short, id");
+                        return None;
+                    }
+                }
+            }
+            "--uptime-precision" => {
+                i += 1;
+                match args.get(i).map(|v| parse_uptime_precision(v)) {
+                    Some(Ok(precision)) => config.uptime_precision = precision,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --uptime-precision requires one of auto, seconds, compact");
+                        return None;
+                    }
+                }
+            }
+            "--logo-position" => {
+                i += 1;
+                match args.get(i).map(|v| parse_logo_position(v)) {
+                    Some(Ok(position)) => config.logo_position = position,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --logo-position requires one of left, right, top");
+                        return None;
+                    }
+                }
+            }
+            "--ascii-distro" => {
+                i += 1;
+                match args.get(i).map(|v| parse_ascii_distro(v)) {
+                    Some(Ok(name)) => config.ascii_distro = Some(name),
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --ascii-distro requires one of {}", LOGO_NAMES.join(", "));
+                        return None;
+                    }
+                }
+            }
+            "--palette" => {
+                i += 1;
+                match args.get(i).map(|v| parse_palette(v)) {
+                    Some(Ok(n)) => config.palette = n,
+                    Some(Err(e)) => {
+                        eprintln!("Error: {}", e);
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --palette requires 6, 8 or 16");
+                        return None;
+                    }
+                }
+            }
+            "--image" => {
+                i += 1;
+                if i < args.len() {
+                    config.image_path = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --image requires a path to a PNG file");
+                    return None;
+                }
+            }
             "-t" | "--theme" => {
                 i += 1;
                 if i < args.len() {
                     let theme = args[i].to_lowercase();
-                    match theme.as_str() {
-                        "classic" | "pastel" | "gruvbox" | "nord" | "dracula" => {
-                            config.color_scheme = theme;
-                        }
-                        _ => {
-                            eprintln!("Unknown theme '{}'. Available: classic, pastel, gruvbox, nord, dracula", args[i]);
-                            return None;
-                        }
+                    if THEMES.contains(&theme.as_str()) {
+                        config.color_scheme = theme;
+                        theme_chosen = true;
+                    } else {
+                        eprintln!("Unknown theme '{}'. Available: {}", args[i], THEMES.join(", "));
+                        return None;
                     }
                 } else {
                     eprintln!("Error: --theme requires a theme name");
                     return None;
                 }
             }
+            "--preview-theme" => {
+                i += 1;
+                match args.get(i).map(|t| t.to_lowercase()) {
+                    Some(theme) if THEMES.contains(&theme.as_str()) => config.preview_theme = Some(theme),
+                    Some(_) => {
+                        eprintln!("Unknown theme '{}'. Available: {}", args[i], THEMES.join(", "));
+                        return None;
+                    }
+                    None => {
+                        eprintln!("Error: --preview-theme requires a theme name");
+                        return None;
+                    }
+                }
+            }
             "--os" => config.show_os = true,
             "--no-os" => config.show_os = false,
             "--kernel" => config.show_kernel = true,
             "--no-kernel" => config.show_kernel = false,
+            "--kernel-arch" => config.show_kernel_arch = true,
+            "--no-kernel-arch" => config.show_kernel_arch = false,
+            "--kernel-check" => config.show_kernel_check = true,
+            "--no-kernel-check" => config.show_kernel_check = false,
             "--uptime" => config.show_uptime = true,
             "--no-uptime" => config.show_uptime = false,
             "--boot-time" => config.show_boot_time = true,
             "--no-boot-time" => config.show_boot_time = false,
+            "--boot-duration" => config.show_boot_duration = true,
+            "--no-boot-duration" => config.show_boot_duration = false,
             "--bootloader" => config.show_bootloader = true,
             "--no-bootloader" => config.show_bootloader = false,
             "--packages" => config.show_packages = true,
             "--no-packages" => config.show_packages = false,
             "--shell" => config.show_shell = true,
             "--no-shell" => config.show_shell = false,
+            "--shell-version" => config.show_shell_version = true,
+            "--no-shell-version" => config.show_shell_version = false,
             "--de" => config.show_de = true,
             "--no-de" => config.show_de = false,
+            "--de-version" => config.show_de_version = true,
+            "--no-de-version" => config.show_de_version = false,
+            "--init-version" => config.show_init_version = true,
+            "--no-init-version" => config.show_init_version = false,
             "--wm" => config.show_wm = true,
             "--no-wm" => config.show_wm = false,
             "--init" => config.show_init = true,
@@ -329,36 +1048,85 @@ fn parse_args() -> Option<Config> {
             "--no-memory" => config.show_memory = false,
             "--swap" => config.show_swap = true,
             "--no-swap" => config.show_swap = false,
+            "--swap-detail" => config.show_swap_detail = true,
+            "--no-swap-detail" => config.show_swap_detail = false,
+            "--memory-detail" => config.show_memory_detail = true,
+            "--no-memory-detail" => config.show_memory_detail = false,
+            "--memory-pressure" => config.show_memory_pressure = true,
+            "--no-memory-pressure" => config.show_memory_pressure = false,
+            "--pressure-all" => {
+                config.show_memory_pressure = true;
+                config.show_pressure_all = true;
+            }
+            "--no-pressure-all" => config.show_pressure_all = false,
             "--disk" | "--partitions" => config.show_partitions = true,
             "--no-disk" | "--no-partitions" => config.show_partitions = false,
+            "--disk-fstype" => config.show_disk_fstype = true,
+            "--no-disk-fstype" => config.show_disk_fstype = false,
             "--network" => config.show_network = true,
             "--no-network" => config.show_network = false,
+            "--gateway" => config.show_gateway = true,
+            "--no-gateway" => config.show_gateway = false,
+            "--dns" => config.show_dns = true,
+            "--no-dns" => config.show_dns = false,
+            "--vpn" => config.show_vpn = true,
+            "--no-vpn" => config.show_vpn = false,
             "--display" => config.show_display = true,
             "--no-display" => config.show_display = false,
             "--battery" => config.show_battery = true,
             "--no-battery" => config.show_battery = false,
+            "--battery-power" => config.show_battery_power = true,
+            "--no-battery-power" => config.show_battery_power = false,
+            "--power" => config.show_power = true,
+            "--no-power" => config.show_power = false,
             "--colors" => config.show_colors = true,
             "--no-colors" => config.show_colors = false,
             "--model" => config.show_model = true,
+            "--chassis" => config.show_chassis = true,
+            "--no-chassis" => config.show_chassis = false,
             "--no-model" => config.show_model = false,
             "--mobo" | "--motherboard" => config.show_motherboard = true,
             "--no-mobo" | "--no-motherboard" => config.show_motherboard = false,
+            "--mobo-temp" => config.show_mobo_temp = true,
+            "--no-mobo-temp" => config.show_mobo_temp = false,
+            "--fan" => config.show_fan = true,
+            "--no-fan" => config.show_fan = false,
             "--bios" => config.show_bios = true,
             "--no-bios" => config.show_bios = false,
             "--desktop-theme" => config.show_theme = true,
             "--no-desktop-theme" => config.show_theme = false,
             "--icons" => config.show_icons = true,
             "--no-icons" => config.show_icons = false,
+            "--term-size" => config.show_term_size = true,
+            "--no-term-size" => config.show_term_size = false,
+            "--detect-terminal-bg" => config.detect_terminal_bg = true,
+            "--no-detect-terminal-bg" => config.detect_terminal_bg = false,
+            "--terminal-font" => config.show_terminal_font = true,
+            "--no-terminal-font" => config.show_terminal_font = false,
             "--font" => config.show_font = true,
             "--no-font" => config.show_font = false,
             "--processes" => config.show_processes = true,
             "--no-processes" => config.show_processes = false,
+            "--processes-detail" => config.show_processes_detail = true,
+            "--no-processes-detail" => config.show_processes_detail = false,
             "--cpu-freq" => config.show_cpu_freq = true,
             "--no-cpu-freq" => config.show_cpu_freq = false,
             "--locale" => config.show_locale = true,
             "--no-locale" => config.show_locale = false,
+            "--locale-detail" => config.show_locale_detail = true,
+            "--no-locale-detail" => config.show_locale_detail = false,
+            "--audio" => config.show_audio = true,
+            "--no-audio" => config.show_audio = false,
             "--public-ip" => config.show_public_ip = true,
             "--no-public-ip" => config.show_public_ip = false,
+            "--updates" => config.show_updates = true,
+            "--no-updates" => config.show_updates = false,
+            "--cpu-features" => config.show_cpu_features = true,
+            "--no-cpu-features" => config.show_cpu_features = false,
+            "--cpu-uarch" => config.show_cpu_uarch = true,
+            "--no-cpu-uarch" => config.show_cpu_uarch = false,
+            "--governor" => config.show_governor = true,
+            "--no-governor" => config.show_governor = false,
             "--cores" => config.show_cpu_cores = true,
             "--no-cores" => config.show_cpu_cores = false,
             "--cache" => config.show_cpu_cache = true,
@@ -373,6 +1141,12 @@ fn parse_args() -> Option<Config> {
             "--no-users" => config.show_users = false,
             "--failed" => config.show_failed_units = true,
             "--no-failed" => config.show_failed_units = false,
+            "--failed-detail" => config.show_failed_detail = true,
+            "--no-failed-detail" => config.show_failed_detail = false,
+            "--container" => config.show_container = true,
+            "--no-container" => config.show_container = false,
+            "--virt" => config.show_virtualization = true,
+            "--no-virt" => config.show_virtualization = false,
             
             arg if arg.starts_with('-') => {
                 eprintln!("Unknown option: {}", arg);
@@ -384,50 +1158,473 @@ fn parse_args() -> Option<Config> {
         i += 1;
     }
     
+    if config.output.is_some() && config.watch.is_some() {
+        eprintln!("Error: --output can't be combined with --watch");
+        return None;
+    }
+    // a scrape target serves one snapshot per request; there is no stream format
+    if config.prometheus_output && config.watch.is_some() {
+        eprintln!("Error: --prometheus can't be combined with --watch");
+        return None;
+    }
+    if config.field.is_some() && config.watch.is_some() {
+        eprintln!("Error: --field can't be combined with --watch");
+        return None;
+    }
+    
+    match force_color {
+        Some(on) => config.use_color = on,
+        None => {
+            if config.output.is_some() {
+                log_debug("CONFIG", "Writing to a file, disabling color");
+                config.use_color = false;
+            } else if !stdout_is_tty() {
+                log_debug("CONFIG", "stdout is not a terminal, disabling color");
+                config.use_color = false;
+            }
+        }
+    }
+    // --json implies no color unless color was explicitly forced on
+    if config.prometheus_output || (config.json_output && force_color != Some(true)) {
+        config.use_color = false;
+    }
+    
+    // Before anything is printed: the reply arrives on the same terminal
+    if config.detect_terminal_bg {
+        config.terminal_bg = query_terminal_background();
+        if config.terminal_bg.as_deref() == Some("light") && !theme_chosen {
+            log_info("CONFIG", "Light terminal background, switching to the solarized-light theme");
+            config.color_scheme = "solarized-light".to_string();
+        }
+    }
+    
     Some(config)
 }
 
-// ============================================================================
-// CONSTANTS
-// ============================================================================
+fn parse_width(value: &str) -> Result<usize, String> {
+    match value.trim().parse::<usize>() {
+        Ok(w) if w > 0 => Ok(w),
+        _ => Err(format!("width expects a positive number of columns (got '{}')", value)),
+    }
+}
 
-const CACHE_FILE: &str = "/tmp/rustfetch_cache";
-const KB_TO_GIB: f64 = 1024.0 * 1024.0;
-const MIN_TEMP_MILLIDEGREES: i32 = 1000;
-const MAX_TEMP_MILLIDEGREES: i32 = 150_000;
-const FILLED_CHAR: char = '█';
-const EMPTY_CHAR: char = '░';
+fn parse_watch_interval(value: &str) -> Result<u64, String> {
+    match value.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Ok(secs),
+        _ => Err(format!("--watch expects a positive number of seconds (got '{}')", value)),
+    }
+}
+
+fn parse_bar_style(value: &str) -> Result<BarStyle, String> {
+    match value.trim().to_lowercase().as_str() {
+        "blocks" => Ok(BarStyle::Blocks),
+        "ascii" => Ok(BarStyle::Ascii),
+        "braille" => Ok(BarStyle::Braille),
+        _ => Err(format!("unknown bar style '{}' (expected blocks, ascii or braille)", value)),
+    }
+}
+
+fn parse_mem_unit(value: &str) -> Result<MemUnit, String> {
+    match value.trim().to_lowercase().as_str() {
+        "gib" => Ok(MemUnit::Gib),
+        "gb" => Ok(MemUnit::Gb),
+        "mib" => Ok(MemUnit::Mib),
+        "percent" | "%" => Ok(MemUnit::Percent),
+        _ => Err(format!("unknown memory unit '{}' (expected gib, gb, mib or percent)", value)),
+    }
+}
+
+fn parse_ascii_distro(value: &str) -> Result<String, String> {
+    let name = value.trim().to_lowercase();
+    if LOGO_NAMES.contains(&name.as_str()) {
+        Ok(name)
+    } else {
+        Err(format!("unknown logo '{}' (expected one of {})", value, LOGO_NAMES.join(", ")))
+    }
+}
+
+fn parse_logo_position(value: &str) -> Result<LogoPosition, String> {
+    match value.trim().to_lowercase().as_str() {
+        "left" => Ok(LogoPosition::Left),
+        "right" => Ok(LogoPosition::Right),
+        "top" => Ok(LogoPosition::Top),
+        _ => Err(format!("unknown logo position '{}' (expected left, right or top)", value)),
+    }
+}
+
+fn parse_logo_size(value: &str) -> Result<LogoSize, String> {
+    match value.trim().to_lowercase().as_str() {
+        "normal" => Ok(LogoSize::Normal),
+        "small" => Ok(LogoSize::Small),
+        "none" => Ok(LogoSize::None),
+        _ => Err(format!("unknown logo size '{}' (expected normal, small or none)", value)),
+    }
+}
+
+fn parse_palette(value: &str) -> Result<usize, String> {
+    match value.trim() {
+        "6" => Ok(6),
+        "8" => Ok(8),
+        "16" => Ok(16),
+        _ => Err(format!("palette must be 6, 8 or 16 (got '{}')", value)),
+    }
+}
+
+fn parse_public_ip_ttl(value: &str) -> Result<u64, String> {
+    value.trim().parse::<u64>()
+        .map_err(|_| format!("public IP TTL expects a whole number of seconds (got '{}')", value))
+}
+
+/// Parses a `--cache-ttl` value. `0` means the cache never expires.
+fn parse_cache_ttl(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    if value.starts_with('-') {
+        return Err(format!("--cache-ttl must not be negative (got '{}')", value));
+    }
+    value.parse::<u64>()
+        .map_err(|_| format!("--cache-ttl expects a whole number of seconds (got '{}')", value))
+}
 
 // ============================================================================
-// RGB COLOR SCHEMES
+// CONFIG FILE
 // ============================================================================
 
-struct ColorScheme {
-    reset: &'static str,
-    bold: &'static str,
-    primary: String,
-    secondary: String,
-    warning: String,
-    error: String,
-    muted: String,
-    color1: String,
-    color2: String,
-    color3: String,
-    color4: String,
-    color5: String,
-    color6: String,
+/// Distro-provided defaults, read before (and overridden by) the user's file.
+const SYSTEM_CONFIG_FILE: &str = "/etc/rustfetch/config.toml";
+
+/// `$XDG_CONFIG_HOME/rustfetch/config.toml`, falling back to `~/.config/rustfetch/config.toml`.
+fn config_file_path() -> Option<String> {
+    Some(format!("{}/rustfetch/config.toml", xdg_config_dir()?))
 }
 
-impl ColorScheme {
-    fn new(config: &Config) -> Self {
-        if !config.use_color {
-            return ColorScheme {
-                reset: "",
-                bold: "",
-                primary: String::new(),
-                secondary: String::new(),
-                warning: String::new(),
-                error: String::new(),
+/// `$XDG_CONFIG_HOME`, or `~/.config` when it's unset.
+fn xdg_config_dir() -> Option<String> {
+    env::var("XDG_CONFIG_HOME").ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| env::var("HOME").ok().map(|h| format!("{}/.config", h)))
+}
+
+/// Loads a small TOML subset: `[section]` headers, `key = value` pairs and `#` comments.
+/// Bad keys or values are reported and skipped; a missing file is not an error.
+fn load_config_file(config: &mut Config, path: &str) {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => {
+            log_debug("CONFIG", &format!("No config file at {}", path));
+            return;
+        }
+    };
+    log_info("CONFIG", &format!("Loading config file {}", path));
+
+    let mut section = String::new();
+    for (lineno, raw) in content.lines().enumerate() {
+        let line = strip_toml_comment(raw).trim();
+        if line.is_empty() { continue; }
+
+        // [[name]] opens a new entry of an array of tables, e.g. [[custom]]
+        if line.starts_with("[[") && line.ends_with("]]") {
+            section = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            if let Err(e) = start_config_table(config, &section) {
+                config_warning(path, lineno + 1, &e);
+            }
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((k, v)) => (k.trim(), unquote_toml_value(v.trim())),
+            None => {
+                config_warning(path, lineno + 1, &format!("expected 'key = value', got '{}'", line));
+                continue;
+            }
+        };
+
+        if let Err(e) = apply_config_value(config, &section, key, &value) {
+            config_warning(path, lineno + 1, &e);
+        }
+    }
+}
+
+/// Rejects empty hosts and anything `ping` would read as an option.
+fn parse_ping_host(value: &str) -> Result<String, String> {
+    let host = value.trim();
+    if host.is_empty() || host.starts_with('-') || host.contains(char::is_whitespace) {
+        return Err(format!("ping host must be a host name or address (got '{}')", value));
+    }
+    Ok(host.to_string())
+}
+
+fn parse_ping_count(value: &str) -> Result<u32, String> {
+    match value.trim().parse::<u32>() {
+        Ok(n) if (1..=20).contains(&n) => Ok(n),
+        _ => Err(format!("ping count expects a number from 1 to 20 (got '{}')", value)),
+    }
+}
+
+fn parse_ping_timeout(value: &str) -> Result<u32, String> {
+    match value.trim().parse::<u32>() {
+        Ok(n) if (1..=10).contains(&n) => Ok(n),
+        _ => Err(format!("ping timeout expects 1 to 10 seconds (got '{}')", value)),
+    }
+}
+
+fn parse_iface_list(value: &str) -> Result<Vec<String>, String> {
+    let list: Vec<String> = value.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect();
+    if list.is_empty() {
+        return Err(format!("expected a comma-separated list of interfaces (got '{}')", value));
+    }
+    Ok(list)
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected true or false, got '{}'", value)),
+    }
+}
+
+/// Bar color threshold in percent; `0` turns the threshold off.
+fn parse_threshold_pct(value: &str) -> Result<Option<u8>, String> {
+    match value.parse::<u8>() {
+        Ok(0) => Ok(None),
+        Ok(p) if p <= 100 => Ok(Some(p)),
+        _ => Err(format!("expected a percentage between 0 and 100, got '{}'", value)),
+    }
+}
+
+/// `#83a598`, `83a598` or `131,165,152`
+fn parse_color_value(value: &str) -> Result<(u8, u8, u8), String> {
+    let value = value.trim();
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+        return Ok((channel(0), channel(2), channel(4)));
+    }
+    let parts: Vec<Option<u8>> = value.split(',').map(|p| p.trim().parse::<u8>().ok()).collect();
+    match parts.as_slice() {
+        [Some(r), Some(g), Some(b)] => Ok((*r, *g, *b)),
+        _ => Err(format!("expected a color like \"#83a598\" or \"131,165,152\", got '{}'", value)),
+    }
+}
+
+fn config_warning(path: &str, line: usize, message: &str) {
+    log_warn("CONFIG", &format!("{}:{}: {}", path, line, message));
+    eprintln!("Warning: {}:{}: {}", path, line, message);
+}
+
+fn strip_toml_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote_toml_value(value: &str) -> String {
+    if value.len() >= 2 && ((value.starts_with('"') && value.ends_with('"'))
+        || (value.starts_with('\'') && value.ends_with('\''))) {
+        value[1..value.len() - 1].to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// A `[[custom]]` line: `label: <first line of the command's output>`.
+#[derive(Clone, Default)]
+struct CustomModule {
+    label: String,
+    command: String,
+    /// Run through `sh -c` instead of splitting on whitespace
+    shell: bool,
+}
+
+fn start_config_table(config: &mut Config, name: &str) -> Result<(), String> {
+    match name {
+        "custom" => config.custom_modules.push(CustomModule::default()),
+        _ => return Err(format!("unknown table [[{}]]", name)),
+    }
+    Ok(())
+}
+
+fn apply_config_value(config: &mut Config, section: &str, key: &str, value: &str) -> Result<(), String> {
+    match (section, key) {
+        ("custom", key) => {
+            // labels key the JSON "custom" object, so they must be unique
+            if key == "label" && config.custom_modules.iter().rev().skip(1).any(|c| c.label == value) {
+                return Err(format!("duplicate [[custom]] label '{}'", value));
+            }
+            let entry = config.custom_modules.last_mut()
+                .ok_or("custom modules are declared with [[custom]], not [custom]")?;
+            match key {
+                "label" => entry.label = value.to_string(),
+                "command" => entry.command = value.to_string(),
+                "shell" => entry.shell = parse_bool(value)?,
+                _ => return Err(format!("unknown key '{}' in [[custom]] (expected label, command, shell)", key)),
+            }
+        }
+        ("", "width") => config.width = Some(parse_width(value)?),
+        ("", "use_glyphs") => config.use_glyphs = parse_bool(value)?,
+        ("", "bar_style") => config.bar_style = parse_bar_style(value)?,
+        ("", "mem_unit") => config.mem_unit = parse_mem_unit(value)?,
+        ("", "logo_size") => config.logo_size = parse_logo_size(value)?,
+        ("", "packages_format") => config.packages_format = parse_packages_format(value)?,
+        ("", "os_format") => config.os_format = parse_os_format(value)?,
+        ("", "uptime_precision") => config.uptime_precision = parse_uptime_precision(value)?,
+        ("", "logo_position") => config.logo_position = parse_logo_position(value)?,
+        ("", "ascii_distro") => config.ascii_distro = Some(parse_ascii_distro(value)?),
+        ("", "palette") => config.palette = parse_palette(value)?,
+        ("", "color_depth") => config.color_depth = parse_color_depth(value)?,
+        ("", "public_ip_ttl") => config.public_ip_ttl = parse_public_ip_ttl(value)?,
+        ("", "ping_host") => config.ping_host = parse_ping_host(value)?,
+        ("", "ping_count") => config.ping_count = parse_ping_count(value)?,
+        ("", "ping_timeout") => config.ping_timeout = parse_ping_timeout(value)?,
+        ("", "net_iface") => config.net_ifaces = parse_iface_list(value)?,
+        ("", "all_net") => config.all_net = parse_bool(value)?,
+        ("", "compact") => config.compact = parse_bool(value)?,
+        ("", "bar_warn_pct") => config.bar_warn_pct = parse_threshold_pct(value)?,
+        ("", "bar_crit_pct") => config.bar_crit_pct = parse_threshold_pct(value)?,
+        ("theme", role) => {
+            if !CUSTOM_THEME_ROLES.contains(&role) {
+                return Err(format!("unknown color '{}' in [theme] (expected one of {})", role, CUSTOM_THEME_ROLES.join(", ")));
+            }
+            config.custom_theme.insert(role.to_string(), parse_color_value(value)?);
+        }
+        ("glyphs", module) => {
+            if default_glyph(module).is_none() {
+                return Err(format!("unknown module '{}' in [glyphs]", module));
+            }
+            config.glyph_overrides.insert(module.to_string(), value.to_string());
+        }
+        _ => return Err(format!("unknown key '{}'", if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) })),
+    }
+    Ok(())
+}
+
+// ============================================================================
+// CONSTANTS
+// ============================================================================
+
+const CACHE_FILE: &str = "/tmp/rustfetch_cache";
+const PUBLIC_IP_CACHE_FILE: &str = "/tmp/rustfetch_public_ip";
+const KIB_PER_GIB: f64 = 1024.0 * 1024.0;
+const BYTES_PER_GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+/// Per-manager limit for `--updates` checks, which may refresh metadata over the network.
+const UPDATES_TIMEOUT_SECS: u64 = 15;
+/// Limit for each `[[custom]]` command; the fetch waits for every module.
+const CUSTOM_TIMEOUT_SECS: u64 = 3;
+/// Length of the `--group` rule when there's no user@host header to match.
+const GROUP_RULE_WIDTH: usize = 20;
+/// Failed unit names listed by `--failed-detail` before "+N more".
+const FAILED_UNITS_SHOWN: usize = 3;
+const MIN_TEMP_MILLIDEGREES: i32 = 1000;
+const MAX_TEMP_MILLIDEGREES: i32 = 150_000;
+
+/// Glyph set used by `create_bar()`.
+#[derive(Clone, Copy, PartialEq)]
+enum BarStyle {
+    Blocks,
+    Ascii,
+    Braille,
+}
+
+/// Braille cells filling up one dot at a time, used for the partial cell.
+const BRAILLE_RAMP: [char; 8] = ['⡀', '⡄', '⡆', '⡇', '⣇', '⣧', '⣷', '⣿'];
+
+impl BarStyle {
+    /// (filled, empty) glyphs
+    fn chars(self) -> (char, char) {
+        match self {
+            BarStyle::Blocks => ('█', '░'),
+            BarStyle::Ascii => ('#', '-'),
+            BarStyle::Braille => ('⣿', '⣀'),
+        }
+    }
+}
+
+/// Unit for the memory, swap and disk usage lines.
+#[derive(Clone, Copy, PartialEq)]
+enum MemUnit {
+    Gib,
+    Gb,
+    Mib,
+    Percent,
+}
+
+/// One amount in `unit`; `total_kib` is only needed for `Percent`.
+fn format_size(kib: f64, total_kib: f64, unit: MemUnit) -> String {
+    match unit {
+        MemUnit::Gib => format!("{:.1}GiB", kib / KIB_PER_GIB),
+        MemUnit::Gb => format!("{:.1}GB", kib * 1024.0 / 1e9),
+        MemUnit::Mib => format!("{:.0}MiB", kib / 1024.0),
+        MemUnit::Percent => {
+            let pct = if total_kib > 0.0 { kib / total_kib * 100.0 } else { 0.0 };
+            format!("{:.0}%", pct)
+        }
+    }
+}
+
+/// "used / total", or just the share of total for `Percent`.
+fn format_usage(used_kib: f64, total_kib: f64, unit: MemUnit) -> String {
+    match unit {
+        MemUnit::Percent => format_size(used_kib, total_kib, unit),
+        _ => format!("{} / {}", format_size(used_kib, total_kib, unit), format_size(total_kib, total_kib, unit)),
+    }
+}
+
+// ============================================================================
+// RGB COLOR SCHEMES
+// ============================================================================
+
+/// Names accepted by `--theme`.
+const THEMES: &[&str] = &[
+    "classic", "pastel", "gruvbox", "nord", "dracula", "solarized-dark",
+    "solarized-light", "catppuccin-mocha", "tokyonight", "custom",
+];
+
+/// Color roles settable from the `[theme]` config section.
+const CUSTOM_THEME_ROLES: &[&str] = &[
+    "primary", "secondary", "warning", "error", "muted",
+    "color0", "color1", "color2", "color3", "color4", "color5", "color6", "color7",
+];
+
+struct ColorScheme {
+    reset: &'static str,
+    bold: &'static str,
+    primary: String,
+    secondary: String,
+    warning: String,
+    error: String,
+    muted: String,
+    color1: String,
+    color2: String,
+    color3: String,
+    color4: String,
+    color5: String,
+    color6: String,
+    color0: String,
+    color7: String,
+    /// Bright variants of color0..color7, for the 16-color swatch
+    bright: [String; 8],
+}
+
+impl ColorScheme {
+    fn new(config: &Config) -> Self {
+        if !config.use_color {
+            return ColorScheme {
+                reset: "",
+                bold: "",
+                primary: String::new(),
+                secondary: String::new(),
+                warning: String::new(),
+                error: String::new(),
                 muted: String::new(),
                 color1: String::new(),
                 color2: String::new(),
@@ -435,106 +1632,459 @@ impl ColorScheme {
                 color4: String::new(),
                 color5: String::new(),
                 color6: String::new(),
+                color0: String::new(),
+                color7: String::new(),
+                bright: Default::default(),
             };
         }
 
+        let depth = resolve_color_depth(config.color_depth);
+        let rgb = |r: u8, g: u8, b: u8| format_rgb(depth, r, g, b);
+
         match config.color_scheme.as_str() {
             "classic" => ColorScheme {
                 reset: "\x1b[0m",
                 bold: "\x1b[1m",
-                primary: format_rgb(70, 170, 200),
-                secondary: format_rgb(120, 190, 80),
-                warning: format_rgb(220, 180, 70),
-                error: format_rgb(220, 80, 90),
-                muted: format_rgb(150, 150, 150),
-                color1: format_rgb(220, 80, 90),
-                color2: format_rgb(120, 190, 80),
-                color3: format_rgb(220, 180, 70),
-                color4: format_rgb(70, 140, 220),
-                color5: format_rgb(140, 120, 200),
-                color6: format_rgb(70, 170, 200),
+                primary: rgb(70, 170, 200),
+                secondary: rgb(120, 190, 80),
+                warning: rgb(220, 180, 70),
+                error: rgb(220, 80, 90),
+                muted: rgb(150, 150, 150),
+                color1: rgb(220, 80, 90),
+                color2: rgb(120, 190, 80),
+                color3: rgb(220, 180, 70),
+                color4: rgb(70, 140, 220),
+                color5: rgb(140, 120, 200),
+                color6: rgb(70, 170, 200),
+                color0: rgb(40, 40, 48),
+                color7: rgb(220, 220, 220),
+                bright: [
+                    rgb(94, 94, 100),
+                    rgb(232, 141, 148),
+                    rgb(167, 213, 141),
+                    rgb(232, 206, 135),
+                    rgb(135, 180, 232),
+                    rgb(180, 167, 219),
+                    rgb(135, 200, 219),
+                    rgb(255, 255, 255),
+                ],
             },
             "pastel" => ColorScheme {
                 reset: "\x1b[0m",
                 bold: "\x1b[1m",
-                primary: format_rgb(100, 180, 200),
-                secondary: format_rgb(150, 200, 130),
-                warning: format_rgb(230, 200, 120),
-                error: format_rgb(230, 130, 130),
-                muted: format_rgb(170, 170, 180),
-                color1: format_rgb(230, 130, 130),
-                color2: format_rgb(150, 200, 130),
-                color3: format_rgb(230, 200, 120),
-                color4: format_rgb(130, 170, 230),
-                color5: format_rgb(180, 160, 210),
-                color6: format_rgb(130, 200, 210),
+                primary: rgb(100, 180, 200),
+                secondary: rgb(150, 200, 130),
+                warning: rgb(230, 200, 120),
+                error: rgb(230, 130, 130),
+                muted: rgb(170, 170, 180),
+                color1: rgb(230, 130, 130),
+                color2: rgb(150, 200, 130),
+                color3: rgb(230, 200, 120),
+                color4: rgb(130, 170, 230),
+                color5: rgb(180, 160, 210),
+                color6: rgb(130, 200, 210),
+                color0: rgb(80, 80, 90),
+                color7: rgb(235, 235, 240),
+                bright: [
+                    rgb(124, 124, 131),
+                    rgb(239, 174, 174),
+                    rgb(187, 219, 174),
+                    rgb(239, 219, 167),
+                    rgb(174, 200, 239),
+                    rgb(206, 193, 226),
+                    rgb(174, 219, 226),
+                    rgb(255, 255, 255),
+                ],
             },
             "gruvbox" => ColorScheme {
                 reset: "\x1b[0m",
                 bold: "\x1b[1m",
-                primary: format_rgb(131, 165, 152),
-                secondary: format_rgb(184, 187, 38),
-                warning: format_rgb(250, 189, 47),
-                error: format_rgb(251, 73, 52),
-                muted: format_rgb(168, 153, 132),
-                color1: format_rgb(251, 73, 52),
-                color2: format_rgb(184, 187, 38),
-                color3: format_rgb(250, 189, 47),
-                color4: format_rgb(131, 165, 152),
-                color5: format_rgb(211, 134, 155),
-                color6: format_rgb(254, 128, 25),
+                primary: rgb(131, 165, 152),
+                secondary: rgb(184, 187, 38),
+                warning: rgb(250, 189, 47),
+                error: rgb(251, 73, 52),
+                muted: rgb(168, 153, 132),
+                color1: rgb(251, 73, 52),
+                color2: rgb(184, 187, 38),
+                color3: rgb(250, 189, 47),
+                color4: rgb(131, 165, 152),
+                color5: rgb(211, 134, 155),
+                color6: rgb(254, 128, 25),
+                color0: rgb(40, 40, 40),
+                color7: rgb(168, 153, 132),
+                bright: [
+                    rgb(94, 94, 94),
+                    rgb(252, 137, 123),
+                    rgb(209, 211, 114),
+                    rgb(252, 212, 120),
+                    rgb(174, 196, 188),
+                    rgb(226, 176, 190),
+                    rgb(254, 172, 106),
+                    rgb(255, 255, 255),
+                ],
             },
             "nord" => ColorScheme {
                 reset: "\x1b[0m",
                 bold: "\x1b[1m",
-                primary: format_rgb(136, 192, 208),
-                secondary: format_rgb(163, 190, 140),
-                warning: format_rgb(235, 203, 139),
-                error: format_rgb(191, 97, 106),
-                muted: format_rgb(216, 222, 233),
-                color1: format_rgb(191, 97, 106),
-                color2: format_rgb(163, 190, 140),
-                color3: format_rgb(235, 203, 139),
-                color4: format_rgb(129, 161, 193),
-                color5: format_rgb(180, 142, 173),
-                color6: format_rgb(136, 192, 208),
+                primary: rgb(136, 192, 208),
+                secondary: rgb(163, 190, 140),
+                warning: rgb(235, 203, 139),
+                error: rgb(191, 97, 106),
+                muted: rgb(216, 222, 233),
+                color1: rgb(191, 97, 106),
+                color2: rgb(163, 190, 140),
+                color3: rgb(235, 203, 139),
+                color4: rgb(129, 161, 193),
+                color5: rgb(180, 142, 173),
+                color6: rgb(136, 192, 208),
+                color0: rgb(59, 66, 82),
+                color7: rgb(229, 233, 240),
+                bright: [
+                    rgb(108, 113, 125),
+                    rgb(213, 152, 158),
+                    rgb(195, 213, 180),
+                    rgb(242, 221, 180),
+                    rgb(173, 194, 215),
+                    rgb(206, 182, 202),
+                    rgb(178, 214, 224),
+                    rgb(255, 255, 255),
+                ],
             },
             "dracula" => ColorScheme {
                 reset: "\x1b[0m",
                 bold: "\x1b[1m",
-                primary: format_rgb(139, 233, 253),
-                secondary: format_rgb(80, 250, 123),
-                warning: format_rgb(241, 250, 140),
-                error: format_rgb(255, 85, 85),
-                muted: format_rgb(98, 114, 164),
-                color1: format_rgb(255, 85, 85),
-                color2: format_rgb(80, 250, 123),
-                color3: format_rgb(241, 250, 140),
-                color4: format_rgb(98, 114, 164),
-                color5: format_rgb(189, 147, 249),
-                color6: format_rgb(255, 121, 198),
+                primary: rgb(139, 233, 253),
+                secondary: rgb(80, 250, 123),
+                warning: rgb(241, 250, 140),
+                error: rgb(255, 85, 85),
+                muted: rgb(98, 114, 164),
+                color1: rgb(255, 85, 85),
+                color2: rgb(80, 250, 123),
+                color3: rgb(241, 250, 140),
+                color4: rgb(98, 114, 164),
+                color5: rgb(189, 147, 249),
+                color6: rgb(255, 121, 198),
+                color0: rgb(33, 34, 44),
+                color7: rgb(248, 248, 242),
+                bright: [
+                    rgb(88, 89, 97),
+                    rgb(255, 144, 144),
+                    rgb(141, 252, 169),
+                    rgb(246, 252, 180),
+                    rgb(153, 163, 196),
+                    rgb(212, 185, 251),
+                    rgb(255, 168, 218),
+                    rgb(255, 255, 255),
+                ],
+            },
+            "solarized-dark" => ColorScheme {
+                reset: "\x1b[0m",
+                bold: "\x1b[1m",
+                primary: rgb(38, 139, 210),
+                secondary: rgb(133, 153, 0),
+                warning: rgb(181, 137, 0),
+                error: rgb(220, 50, 47),
+                muted: rgb(88, 110, 117),
+                color1: rgb(220, 50, 47),
+                color2: rgb(133, 153, 0),
+                color3: rgb(181, 137, 0),
+                color4: rgb(38, 139, 210),
+                color5: rgb(211, 54, 130),
+                color6: rgb(42, 161, 152),
+                color0: rgb(7, 54, 66),
+                color7: rgb(238, 232, 213),
+                bright: [
+                    rgb(0, 43, 54),
+                    rgb(203, 75, 22),
+                    rgb(88, 110, 117),
+                    rgb(101, 123, 131),
+                    rgb(131, 148, 150),
+                    rgb(108, 113, 196),
+                    rgb(147, 161, 161),
+                    rgb(253, 246, 227),
+                ],
+            },
+            "solarized-light" => ColorScheme {
+                reset: "\x1b[0m",
+                bold: "\x1b[1m",
+                primary: rgb(38, 139, 210),
+                secondary: rgb(133, 153, 0),
+                warning: rgb(181, 137, 0),
+                error: rgb(220, 50, 47),
+                muted: rgb(147, 161, 161),
+                color1: rgb(220, 50, 47),
+                color2: rgb(133, 153, 0),
+                color3: rgb(181, 137, 0),
+                color4: rgb(38, 139, 210),
+                color5: rgb(211, 54, 130),
+                color6: rgb(42, 161, 152),
+                color0: rgb(7, 54, 66),
+                color7: rgb(238, 232, 213),
+                bright: [
+                    rgb(0, 43, 54),
+                    rgb(203, 75, 22),
+                    rgb(88, 110, 117),
+                    rgb(101, 123, 131),
+                    rgb(131, 148, 150),
+                    rgb(108, 113, 196),
+                    rgb(147, 161, 161),
+                    rgb(253, 246, 227),
+                ],
+            },
+            "catppuccin-mocha" => ColorScheme {
+                reset: "\x1b[0m",
+                bold: "\x1b[1m",
+                primary: rgb(137, 180, 250),
+                secondary: rgb(166, 227, 161),
+                warning: rgb(249, 226, 175),
+                error: rgb(243, 139, 168),
+                muted: rgb(108, 112, 134),
+                color1: rgb(243, 139, 168),
+                color2: rgb(166, 227, 161),
+                color3: rgb(249, 226, 175),
+                color4: rgb(137, 180, 250),
+                color5: rgb(245, 194, 231),
+                color6: rgb(148, 226, 213),
+                color0: rgb(69, 71, 90),
+                color7: rgb(186, 194, 222),
+                bright: [
+                    rgb(88, 91, 112),
+                    rgb(243, 139, 168),
+                    rgb(166, 227, 161),
+                    rgb(249, 226, 175),
+                    rgb(137, 180, 250),
+                    rgb(245, 194, 231),
+                    rgb(148, 226, 213),
+                    rgb(166, 173, 200),
+                ],
             },
+            "tokyonight" => ColorScheme {
+                reset: "\x1b[0m",
+                bold: "\x1b[1m",
+                primary: rgb(122, 162, 247),
+                secondary: rgb(158, 206, 106),
+                warning: rgb(224, 175, 104),
+                error: rgb(247, 118, 142),
+                muted: rgb(86, 95, 137),
+                color1: rgb(247, 118, 142),
+                color2: rgb(158, 206, 106),
+                color3: rgb(224, 175, 104),
+                color4: rgb(122, 162, 247),
+                color5: rgb(187, 154, 247),
+                color6: rgb(125, 207, 255),
+                color0: rgb(21, 22, 30),
+                color7: rgb(169, 177, 214),
+                bright: [
+                    rgb(65, 72, 104),
+                    rgb(247, 118, 142),
+                    rgb(158, 206, 106),
+                    rgb(224, 175, 104),
+                    rgb(122, 162, 247),
+                    rgb(187, 154, 247),
+                    rgb(125, 207, 255),
+                    rgb(192, 202, 245),
+                ],
+            },
+            "custom" => {
+                // Unset roles keep the classic colors
+                let mut base = config.clone();
+                base.color_scheme = "classic".to_string();
+                let mut scheme = ColorScheme::new(&base);
+                if config.custom_theme.is_empty() {
+                    log_warn("THEME", "--theme custom selected but the config file has no [theme] colors");
+                }
+                for (role, &(r, g, b)) in &config.custom_theme {
+                    let color = rgb(r, g, b);
+                    match role.as_str() {
+                        "primary" => scheme.primary = color,
+                        "secondary" => scheme.secondary = color,
+                        "warning" => scheme.warning = color,
+                        "error" => scheme.error = color,
+                        "muted" => scheme.muted = color,
+                        "color0" => scheme.color0 = color,
+                        "color1" => scheme.color1 = color,
+                        "color2" => scheme.color2 = color,
+                        "color3" => scheme.color3 = color,
+                        "color4" => scheme.color4 = color,
+                        "color5" => scheme.color5 = color,
+                        "color6" => scheme.color6 = color,
+                        "color7" => scheme.color7 = color,
+                        _ => {}
+                    }
+                }
+                scheme
+            }
             _ => ColorScheme {
                 reset: "\x1b[0m",
                 bold: "\x1b[1m",
-                primary: format_rgb(80, 160, 200),
-                secondary: format_rgb(100, 180, 100),
-                warning: format_rgb(220, 180, 80),
-                error: format_rgb(220, 80, 80),
-                muted: format_rgb(140, 140, 160),
-                color1: format_rgb(220, 80, 80),
-                color2: format_rgb(100, 180, 100),
-                color3: format_rgb(220, 180, 80),
-                color4: format_rgb(80, 120, 200),
-                color5: format_rgb(160, 120, 200),
-                color6: format_rgb(80, 160, 200),
+                primary: rgb(80, 160, 200),
+                secondary: rgb(100, 180, 100),
+                warning: rgb(220, 180, 80),
+                error: rgb(220, 80, 80),
+                muted: rgb(140, 140, 160),
+                color1: rgb(220, 80, 80),
+                color2: rgb(100, 180, 100),
+                color3: rgb(220, 180, 80),
+                color4: rgb(80, 120, 200),
+                color5: rgb(160, 120, 200),
+                color6: rgb(80, 160, 200),
+                color0: rgb(40, 40, 50),
+                color7: rgb(210, 210, 210),
+                bright: [
+                    rgb(94, 94, 101),
+                    rgb(232, 141, 141),
+                    rgb(154, 206, 154),
+                    rgb(232, 206, 141),
+                    rgb(141, 167, 219),
+                    rgb(193, 167, 219),
+                    rgb(141, 193, 219),
+                    rgb(255, 255, 255),
+                ],
             },
         }
     }
 }
 
-fn format_rgb(r: u8, g: u8, b: u8) -> String {
-    format!("\x1b[38;2;{};{};{}m", r, g, b)
+impl ColorScheme {
+    /// Escape for a `CUSTOM_THEME_ROLES` name.
+    fn role(&self, role: &str) -> Option<&str> {
+        Some(match role {
+            "primary" => &self.primary,
+            "secondary" => &self.secondary,
+            "warning" => &self.warning,
+            "error" => &self.error,
+            "muted" => &self.muted,
+            "color0" => &self.color0,
+            "color1" => &self.color1,
+            "color2" => &self.color2,
+            "color3" => &self.color3,
+            "color4" => &self.color4,
+            "color5" => &self.color5,
+            "color6" => &self.color6,
+            "color7" => &self.color7,
+            _ => return None,
+        })
+    }
+}
+
+/// `--preview-theme`: one `role  ██ #RRGGBB` line per color role. The scheme is
+/// built in truecolor to read the exact values back; the swatch uses the real depth.
+fn print_theme_preview(config: &Config, theme: &str) {
+    let mut exact = config.clone();
+    exact.color_scheme = theme.to_string();
+    exact.use_color = true;
+    exact.color_depth = ColorDepth::TrueColor;
+    let scheme = ColorScheme::new(&exact);
+    let depth = resolve_color_depth(config.color_depth);
+
+    println!("{}", theme);
+    for role in CUSTOM_THEME_ROLES {
+        let Some((r, g, b)) = scheme.role(role).and_then(parse_truecolor_escape) else { continue };
+        let swatch = if config.use_color {
+            format!("{}██\x1b[0m", format_rgb(depth, r, g, b))
+        } else {
+            "██".to_string()
+        };
+        println!("  {:<10} {} #{:02X}{:02X}{:02X}", role, swatch, r, g, b);
+    }
+}
+
+/// (r, g, b) from a `\x1b[38;2;R;G;Bm` foreground escape.
+fn parse_truecolor_escape(escape: &str) -> Option<(u8, u8, u8)> {
+    let params = escape.strip_prefix("\x1b[38;2;")?.strip_suffix('m')?;
+    let mut parts = params.split(';').map(|p| p.parse::<u8>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ColorDepth {
+    Auto,
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+fn parse_color_depth(value: &str) -> Result<ColorDepth, String> {
+    match value.trim().to_lowercase().as_str() {
+        "auto" => Ok(ColorDepth::Auto),
+        "truecolor" | "24bit" => Ok(ColorDepth::TrueColor),
+        "256" => Ok(ColorDepth::Ansi256),
+        "16" => Ok(ColorDepth::Ansi16),
+        _ => Err(format!("unknown color depth '{}' (expected truecolor, 256, 16 or auto)", value)),
+    }
+}
+
+/// `auto` trusts $COLORTERM for 24-bit, then guesses from $TERM.
+fn resolve_color_depth(depth: ColorDepth) -> ColorDepth {
+    if depth != ColorDepth::Auto {
+        return depth;
+    }
+    let colorterm = env::var("COLORTERM").unwrap_or_default().to_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorDepth::TrueColor;
+    }
+    let term = env::var("TERM").unwrap_or_default().to_lowercase();
+    let resolved = if ["kitty", "alacritty", "wezterm", "foot", "direct"].iter().any(|t| term.contains(t)) {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else if term.is_empty() || ["linux", "vt100", "vt220", "ansi", "dumb", "cons25"].contains(&term.as_str()) {
+        ColorDepth::Ansi16
+    } else {
+        ColorDepth::Ansi256
+    };
+    log_debug("COLOR", &format!("TERM='{}' COLORTERM='{}', using {} colors", term, colorterm,
+        match resolved { ColorDepth::TrueColor => "24-bit", ColorDepth::Ansi16 => "16", _ => "256" }));
+    resolved
+}
+
+/// Foreground escape for an RGB color, downsampled to what the terminal can show.
+fn format_rgb(depth: ColorDepth, r: u8, g: u8, b: u8) -> String {
+    match depth {
+        ColorDepth::TrueColor | ColorDepth::Auto => format!("\x1b[38;2;{};{};{}m", r, g, b),
+        ColorDepth::Ansi256 => format!("\x1b[38;5;{}m", rgb_to_ansi256(r, g, b)),
+        ColorDepth::Ansi16 => {
+            let idx = rgb_to_ansi16(r, g, b);
+            format!("\x1b[{}m", if idx < 8 { 30 + idx } else { 90 + idx - 8 })
+        }
+    }
+}
+
+fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Nearest entry of the xterm 6x6x6 cube or its 24-step gray ramp.
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let nearest = |v: u8| (0..6).min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs()).unwrap_or(0);
+    let (ri, gi, bi) = (nearest(r), nearest(g), nearest(b));
+    let cube = (LEVELS[ri], LEVELS[gi], LEVELS[bi]);
+    let cube_idx = 16 + 36 * ri + 6 * gi + bi;
+
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_i = (avg.saturating_sub(8) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_i;
+
+    if color_distance((gray, gray, gray), (r, g, b)) < color_distance(cube, (r, g, b)) {
+        232 + gray_i
+    } else {
+        cube_idx as u8
+    }
+}
+
+/// 16-color index (0-7 normal, 8-15 bright): each channel rounds to on/off,
+/// and brightness picks the bright half. Plain nearest-distance would turn
+/// most muted theme colors gray.
+fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> u8 {
+    let value = (r.max(g).max(b) as u32 * 2 + 127) / 255;
+    if value == 0 {
+        return 0;
+    }
+    let on = |c: u8| (c >= 128) as u8;
+    let idx = (on(b) << 2) | (on(g) << 1) | on(r);
+    if value == 2 { idx + 8 } else { idx }
 }
 
 // ============================================================================
@@ -547,7 +2097,24 @@ trait ToJson {
 
 impl ToJson for String {
     fn to_json(&self) -> String {
-        format!("\"{}\"", self.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+        let mut out = String::with_capacity(self.len() + 2);
+        out.push('"');
+        for c in self.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                '\u{8}' => out.push_str("\\b"),
+                '\u{c}' => out.push_str("\\f"),
+                // Remaining control characters are invalid raw in a JSON string
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
     }
 }
 
@@ -591,10 +2158,207 @@ impl<T: ToJson> ToJson for Vec<T> {
     }
 }
 
+/// Parsed JSON, for reading back our own output (`--field`, `--json-pretty`). Numbers keep
+/// their source text so nothing is lost to float formatting.
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    fn parse(text: &str) -> Option<JsonValue> {
+        let mut chars = text.chars().peekable();
+        let value = parse_json_value(&mut chars)?;
+        skip_json_whitespace(&mut chars);
+        if chars.next().is_some() { None } else { Some(value) }
+    }
+
+    /// Dotted lookup: object keys by name, array elements by index ("network.0.ipv4").
+    fn get_path(&self, path: &str) -> Option<&JsonValue> {
+        path.split('.').try_fold(self, |value, segment| match value {
+            JsonValue::Object(fields) => fields.iter().find(|(k, _)| k == segment).map(|(_, v)| v),
+            JsonValue::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+}
+
+impl JsonValue {
+    /// Two-space indented form, one member per line; `depth` is the current nesting.
+    fn to_json_pretty(&self, depth: usize) -> String {
+        let pad = "  ".repeat(depth + 1);
+        let close = "  ".repeat(depth);
+        match self {
+            JsonValue::Array(items) if !items.is_empty() => {
+                let items: Vec<String> = items.iter()
+                    .map(|v| format!("{}{}", pad, v.to_json_pretty(depth + 1)))
+                    .collect();
+                format!("[\n{}\n{}]", items.join(",\n"), close)
+            }
+            JsonValue::Object(fields) if !fields.is_empty() => {
+                let fields: Vec<String> = fields.iter()
+                    .map(|(k, v)| format!("{}{}: {}", pad, k.to_json(), v.to_json_pretty(depth + 1)))
+                    .collect();
+                format!("{{\n{}\n{}}}", fields.join(",\n"), close)
+            }
+            _ => self.to_json(),
+        }
+    }
+}
+
+impl ToJson for JsonValue {
+    fn to_json(&self) -> String {
+        match self {
+            JsonValue::Null => "null".to_string(),
+            JsonValue::Bool(b) => b.to_string(),
+            JsonValue::Number(n) => n.clone(),
+            JsonValue::Str(s) => s.to_json(),
+            JsonValue::Array(items) => {
+                let items: Vec<String> = items.iter().map(|v| v.to_json()).collect();
+                format!("[{}]", items.join(","))
+            }
+            JsonValue::Object(fields) => {
+                let fields: Vec<String> = fields.iter().map(|(k, v)| format!("{}:{}", k.to_json(), v.to_json())).collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        }
+    }
+}
+
+type JsonChars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_json_whitespace(chars: &mut JsonChars) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_json_value(chars: &mut JsonChars) -> Option<JsonValue> {
+    skip_json_whitespace(chars);
+    match *chars.peek()? {
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_json_whitespace(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Some(JsonValue::Object(fields));
+            }
+            loop {
+                skip_json_whitespace(chars);
+                let key = parse_json_string(chars)?;
+                skip_json_whitespace(chars);
+                if chars.next()? != ':' { return None; }
+                fields.push((key, parse_json_value(chars)?));
+                skip_json_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    '}' => return Some(JsonValue::Object(fields)),
+                    _ => return None,
+                }
+            }
+        }
+        '[' => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_json_whitespace(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Some(JsonValue::Array(items));
+            }
+            loop {
+                items.push(parse_json_value(chars)?);
+                skip_json_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    ']' => return Some(JsonValue::Array(items)),
+                    _ => return None,
+                }
+            }
+        }
+        '"' => parse_json_string(chars).map(JsonValue::Str),
+        c if c == '-' || c.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_digit() || "+-.eE".contains(c)) { break; }
+                number.push(c);
+                chars.next();
+            }
+            number.parse::<f64>().ok().map(|_| JsonValue::Number(number))
+        }
+        _ => {
+            let word: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphabetic())).collect();
+            match word.as_str() {
+                "null" => Some(JsonValue::Null),
+                "true" => Some(JsonValue::Bool(true)),
+                "false" => Some(JsonValue::Bool(false)),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn parse_json_string(chars: &mut JsonChars) -> Option<String> {
+    if chars.next()? != '"' { return None; }
+    let mut out = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                'b' => out.push('\u{8}'),
+                'f' => out.push('\u{c}'),
+                'u' => {
+                    let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                    out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?).unwrap_or('\u{fffd}'));
+                }
+                c => out.push(c),
+            },
+            c => out.push(c),
+        }
+    }
+}
+
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
 
+/// A connected, active output.
+#[derive(Clone)]
+struct Monitor {
+    name: String,
+    width: u32,
+    height: u32,
+    /// Hz of the active mode; DRM sysfs doesn't expose it
+    refresh: Option<f64>,
+    primary: bool,
+}
+
+impl Monitor {
+    fn resolution(&self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+
+    /// "2560x1440@144Hz"; 59.95 and 143.91 style rates round to the advertised figure.
+    fn mode(&self) -> String {
+        match self.refresh {
+            Some(hz) => format!("{}@{}Hz", self.resolution(), hz.round()),
+            None => self.resolution(),
+        }
+    }
+}
+
+impl ToJson for Monitor {
+    fn to_json(&self) -> String {
+        format!("{{\"name\":{},\"resolution\":{},\"refresh_hz\":{},\"primary\":{}}}",
+            self.name.to_json(), self.resolution().to_json(), self.refresh.to_json(), self.primary)
+    }
+}
+
 #[derive(Default, Clone)]
 struct NetworkInfo {
     interface: String,
@@ -638,6 +2402,17 @@ struct CpuInfo {
     cores: Option<usize>,
     cache: Option<String>,
     freq: Option<String>,
+    /// "Intel" / "AMD" from `vendor_id`
+    vendor: Option<String>,
+    /// Codename looked up from `cpu family` / `model`
+    uarch: Option<String>,
+}
+
+/// zram-backed swap: the swap devices found in /proc/swaps and their compression ratio.
+#[derive(Clone)]
+struct ZramSwap {
+    devices: Vec<String>,
+    ratio: Option<f64>,
 }
 
 #[derive(Default, Clone)]
@@ -645,48 +2420,104 @@ struct Info {
     user: Option<String>,
     hostname: Option<String>,
     os: Option<String>,
+    os_id: Option<String>,
+    os_id_like: Option<String>,
+    os_version_id: Option<String>,
+    os_build_id: Option<String>,
+    /// PRETTY_NAME without parentheticals or the codename, for `--os-format short`
+    os_short: Option<String>,
     kernel: Option<String>,
-    public_ip: Option<String>,
+    arch: Option<String>,
+    /// Newest installed kernel when it isn't the running one (`--kernel-check`)
+    kernel_installed: Option<String>,
+    public_ipv4: Option<String>,
+    public_ipv6: Option<String>,
     cpu_cores: Option<(usize, usize)>,
     cpu_cache: Option<String>,
+    cpu_vendor: Option<String>,
+    cpu_uarch: Option<String>,
     gpu_vram: Option<Vec<String>>,
-    resolution: Option<String>,
+    gpu_vram_used: Option<Vec<Option<(f64, f64)>>>,
+    monitors: Option<Vec<Monitor>>,
     entropy: Option<String>,
     users: Option<usize>,
-    failed_units: Option<usize>,
+    /// Names of failed systemd units
+    failed_units: Option<Vec<String>>,
+    container: Option<String>,
+    /// "Ubuntu (WSL2)"
+    wsl: Option<String>,
+    virtualization: Option<String>,
     uptime: Option<String>,
+    uptime_seconds: Option<u64>,
     boot_time: Option<String>,
+    /// Same instant for JSON, where an unambiguous timestamp matters more than looks
+    boot_time_rfc3339: Option<String>,
+    /// (kernel, userspace) seconds from `systemd-analyze`
+    boot_duration: Option<(f64, f64)>,
     bootloader: Option<String>,
-    packages: Option<String>,
+    /// (manager, count) in `PACKAGE_COUNTERS` order
+    packages: Option<Vec<(String, usize)>>,
+    /// Pending updates summed over the managers that could be checked
+    updates: Option<usize>,
     shell: Option<String>,
     de: Option<String>,
     wm: Option<String>,
     init: Option<String>,
     terminal: Option<String>,
+    terminal_font: Option<String>,
+    term_size: Option<String>,
+    terminal_bg: Option<String>,
     cpu: Option<String>,
     cpu_temp: Option<String>,
+    cpu_features: Option<String>,
+    cpu_governor: Option<String>,
     gpu: Option<Vec<String>>,
     gpu_temps: Option<Vec<Option<String>>>,
-    memory: Option<(f64, f64)>,
+    /// Index into `gpu` of the GPU in use when there are several
+    gpu_active: Option<usize>,
+    /// Index into `gpu` of the GPU the Wayland compositor renders on
+    gpu_compositor: Option<usize>,
+    memory: Option<MemoryInfo>,
     swap: Option<(f64, f64)>,
-    partitions: Option<Vec<(String, String, f64, f64)>>,
+    zram: Option<ZramSwap>,
+    /// PSI "some avg10" percentages
+    memory_pressure: Option<f64>,
+    cpu_pressure: Option<f64>,
+    io_pressure: Option<f64>,
+    partitions: Option<Vec<Partition>>,
     network: Option<Vec<NetworkInfo>>,
+    gateway: Option<String>,
+    dns: Option<String>,
+    vpn: Option<String>,
     display: Option<String>,
-    battery: Option<(u8, String)>,
+    battery: Option<Battery>,
+    power_source: Option<String>,
     model: Option<String>,
+    chassis: Option<String>,
     motherboard: Option<String>,
+    mobo_temp: Option<String>,
+    /// (label, RPM) per fan sensor
+    fans: Option<Vec<(String, u32)>>,
     bios: Option<String>,
     theme: Option<String>,
     icons: Option<String>,
     font: Option<String>,
     processes: Option<usize>,
+    process_states: Option<ProcessStates>,
     cpu_freq: Option<String>,
     locale: Option<String>,
+    audio: Option<String>,
+    /// (index into `config.custom_modules`, label, output), in config order once collected
+    custom: Vec<(usize, String, String)>,
+    /// Slow fields came from the main cache; it isn't rewritten so the TTL can run out
+    from_cache: bool,
 }
 
 impl ToJson for Info {
     fn to_json(&self) -> String {
         let mut parts = Vec::with_capacity(40);
+        parts.push(format!("\"schema_version\":{}", JSON_SCHEMA_VERSION));
+        parts.push(format!("\"rustfetch_version\":\"{}\"", VERSION));
         
         if let Some(ref v) = self.user {
             parts.push(format!("\"user\":{}", v.to_json()));
@@ -697,21 +2528,36 @@ impl ToJson for Info {
         if let Some(ref v) = self.os {
             parts.push(format!("\"os\":{}", v.to_json()));
         }
+        if let Some(ref v) = self.os_id { parts.push(format!("\"os_id\":{}", v.to_json())); }
+        if let Some(ref v) = self.os_id_like { parts.push(format!("\"os_id_like\":{}", v.to_json())); }
+        if let Some(ref v) = self.os_version_id { parts.push(format!("\"os_version_id\":{}", v.to_json())); }
+        if let Some(ref v) = self.os_build_id { parts.push(format!("\"os_build_id\":{}", v.to_json())); }
         if let Some(ref v) = self.kernel {
             parts.push(format!("\"kernel\":{}", v.to_json()));
         }
+        if let Some(ref v) = self.arch { parts.push(format!("\"arch\":{}", v.to_json())); }
+        if let Some(ref v) = self.kernel_installed { parts.push(format!("\"kernel_installed\":{}", v.to_json())); }
         if let Some(ref v) = self.uptime {
             parts.push(format!("\"uptime\":{}", v.to_json()));
         }
-        if let Some(ref v) = self.boot_time {
+        if let Some(v) = self.uptime_seconds { parts.push(format!("\"uptime_seconds\":{}", v)); }
+        if let Some(ref v) = self.boot_time_rfc3339 {
             parts.push(format!("\"boot_time\":{}", v.to_json()));
         }
+        if let Some((kernel, userspace)) = self.boot_duration {
+            parts.push(format!("\"boot_duration\":{{\"kernel\":{},\"userspace\":{}}}", kernel, userspace));
+        }
         if let Some(ref v) = self.bootloader {
             parts.push(format!("\"bootloader\":{}", v.to_json()));
         }
         if let Some(ref v) = self.packages {
-            parts.push(format!("\"packages\":{}", v.to_json()));
+            let counts: Vec<String> = v.iter()
+                .map(|(name, count)| format!("{}:{}", name.to_json(), count))
+                .collect();
+            parts.push(format!("\"packages\":{{{}}}", counts.join(",")));
+            parts.push(format!("\"packages_total\":{}", v.iter().map(|(_, count)| count).sum::<usize>()));
         }
+        if let Some(n) = self.updates { parts.push(format!("\"updates\":{}", n)); }
         if let Some(ref v) = self.shell {
             parts.push(format!("\"shell\":{}", v.to_json()));
         }
@@ -727,64 +2573,342 @@ impl ToJson for Info {
         if let Some(ref v) = self.terminal {
             parts.push(format!("\"terminal\":{}", v.to_json()));
         }
+        if let Some(ref v) = self.terminal_font { parts.push(format!("\"terminal_font\":{}", v.to_json())); }
+        if let Some(ref v) = self.term_size { parts.push(format!("\"term_size\":{}", v.to_json())); }
+        if let Some(ref v) = self.terminal_bg { parts.push(format!("\"terminal_background\":{}", v.to_json())); }
         if let Some(ref v) = self.cpu {
             parts.push(format!("\"cpu\":{}", v.to_json()));
         }
+        if let Some(ref v) = self.cpu_vendor { parts.push(format!("\"cpu_vendor\":{}", v.to_json())); }
+        if let Some(ref v) = self.cpu_uarch { parts.push(format!("\"cpu_uarch\":{}", v.to_json())); }
         if let Some(ref v) = self.cpu_temp {
             parts.push(format!("\"cpu_temp\":{}", v.to_json()));
         }
+        if let Some(ref v) = self.cpu_features { parts.push(format!("\"cpu_features\":{}", v.to_json())); }
+        if let Some(ref v) = self.cpu_governor { parts.push(format!("\"cpu_governor\":{}", v.to_json())); }
         if let Some(ref v) = self.gpu {
             parts.push(format!("\"gpu\":{}", v.to_json()));
         }
+        if let Some(i) = self.gpu_active { parts.push(format!("\"gpu_active\":{}", i)); }
+        if let Some(i) = self.gpu_compositor { parts.push(format!("\"gpu_compositor\":{}", i)); }
         if let Some(ref v) = self.gpu_temps {
             let temps_json: Vec<String> = v.iter().map(|t| t.to_json()).collect();
             parts.push(format!("\"gpu_temps\":[{}]", temps_json.join(",")));
         }
-        if let Some((used, total)) = self.memory {
-            parts.push(format!("\"memory\":{{\"used\":{},\"total\":{}}}", used, total));
+        if let Some(ref v) = self.gpu_vram_used {
+            let vram_json: Vec<String> = v.iter().map(|u| match u {
+                Some((used, total)) => format!("{{\"used\":{},\"total\":{}}}", used, total),
+                None => "null".to_string(),
+            }).collect();
+            parts.push(format!("\"gpu_vram_used\":[{}]", vram_json.join(",")));
+        }
+        if let Some(m) = self.memory {
+            parts.push(format!("\"memory\":{{\"used\":{},\"total\":{},\"buffers\":{},\"cached\":{},\"shmem\":{}}}",
+                m.used / KIB_PER_GIB, m.total / KIB_PER_GIB, m.buffers / KIB_PER_GIB, m.cached / KIB_PER_GIB, m.shmem / KIB_PER_GIB));
         }
         if let Some((used, total)) = self.swap {
-            parts.push(format!("\"swap\":{{\"used\":{},\"total\":{}}}", used, total));
+            let zram = match self.zram {
+                Some(ref z) => format!(",\"zram\":true,\"zram_devices\":{},\"zram_ratio\":{}",
+                    z.devices.to_json(), z.ratio.to_json()),
+                None => String::new(),
+            };
+            parts.push(format!("\"swap\":{{\"used\":{},\"total\":{}{}}}", used / KIB_PER_GIB, total / KIB_PER_GIB, zram));
+        }
+        let pressure: Vec<String> = [("memory", self.memory_pressure), ("cpu", self.cpu_pressure), ("io", self.io_pressure)]
+            .iter()
+            .filter_map(|(resource, pct)| pct.map(|p| format!("\"{}\":{}", resource, p)))
+            .collect();
+        if !pressure.is_empty() {
+            parts.push(format!("\"pressure\":{{{}}}", pressure.join(",")));
         }
         if let Some(ref v) = self.network {
             parts.push(format!("\"network\":{}", v.to_json()));
         }
+        if let Some(ref v) = self.gateway { parts.push(format!("\"gateway\":{}", v.to_json())); }
+        if let Some(ref v) = self.dns { parts.push(format!("\"dns\":{}", v.to_json())); }
+        if let Some(ref v) = self.vpn { parts.push(format!("\"vpn\":{}", v.to_json())); }
         if let Some(ref v) = self.display {
             parts.push(format!("\"display\":{}", v.to_json()));
         }
-        if let Some((cap, ref status)) = self.battery {
-            parts.push(format!("\"battery\":{{\"capacity\":{},\"status\":{}}}", cap, status.to_json()));
+        if let Some(ref v) = self.monitors {
+            parts.push(format!("\"monitors\":{}", v.to_json()));
+        }
+        if let Some(ref v) = self.power_source { parts.push(format!("\"power_source\":{}", v.to_json())); }
+        if let Some(ref bat) = self.battery {
+            let power = bat.power.map(|w| format!(",\"power_w\":{}", w)).unwrap_or_default();
+            parts.push(format!("\"battery\":{{\"capacity\":{},\"status\":{}{}}}", bat.capacity, bat.status.to_json(), power));
         }
         
         if let Some(ref v) = self.model { parts.push(format!("\"model\":{}", v.to_json())); }
+        if let Some(ref v) = self.chassis { parts.push(format!("\"chassis\":{}", v.to_json())); }
         if let Some(ref v) = self.motherboard { parts.push(format!("\"motherboard\":{}", v.to_json())); }
+        if let Some(ref v) = self.mobo_temp { parts.push(format!("\"mobo_temp\":{}", v.to_json())); }
+        if let Some(ref fans) = self.fans {
+            let items: Vec<String> = fans.iter()
+                .map(|(label, rpm)| format!("{{\"label\":{},\"rpm\":{}}}", label.to_json(), rpm))
+                .collect();
+            parts.push(format!("\"fans\":[{}]", items.join(",")));
+        }
         if let Some(ref v) = self.bios { parts.push(format!("\"bios\":{}", v.to_json())); }
         if let Some(ref v) = self.theme { parts.push(format!("\"theme\":{}", v.to_json())); }
         if let Some(ref v) = self.icons { parts.push(format!("\"icons\":{}", v.to_json())); }
         if let Some(ref v) = self.font { parts.push(format!("\"font\":{}", v.to_json())); }
         if let Some(ref v) = self.processes { parts.push(format!("\"processes\":{}", v.to_json())); }
+        if let Some(ref p) = self.process_states {
+            parts.push(format!("\"process_states\":{{\"running\":{},\"sleeping\":{},\"zombie\":{},\"stopped\":{}}}",
+                p.running, p.sleeping, p.zombie, p.stopped));
+        }
         if let Some(ref v) = self.cpu_freq { parts.push(format!("\"cpu_freq\":{}", v.to_json())); }
         if let Some(ref v) = self.locale { parts.push(format!("\"locale\":{}", v.to_json())); }
-        if let Some(ref v) = self.public_ip { parts.push(format!("\"public_ip\":{}", v.to_json())); }
+        if let Some(ref v) = self.audio { parts.push(format!("\"audio\":{}", v.to_json())); }
+        if !self.custom.is_empty() {
+            let fields: Vec<String> = self.custom.iter()
+                .map(|(_, label, output)| format!("{}:{}", label.to_json(), output.to_json()))
+                .collect();
+            parts.push(format!("\"custom\":{{{}}}", fields.join(",")));
+        }
+        // "public_ip" predates the per-family fields; kept for existing consumers
+        if let Some(v) = self.public_ipv4.as_ref().or(self.public_ipv6.as_ref()) { parts.push(format!("\"public_ip\":{}", v.to_json())); }
+        if let Some(ref v) = self.public_ipv4 { parts.push(format!("\"public_ipv4\":{}", v.to_json())); }
+        if let Some(ref v) = self.public_ipv6 { parts.push(format!("\"public_ipv6\":{}", v.to_json())); }
+        if let Some(ref v) = self.container { parts.push(format!("\"container\":{}", v.to_json())); }
+        if let Some(ref v) = self.wsl { parts.push(format!("\"wsl\":{}", v.to_json())); }
+        if let Some(ref v) = self.virtualization { parts.push(format!("\"virtualization\":{}", v.to_json())); }
         
         format!("{{{}}}", parts.join(","))
     }
 }
 
 // ============================================================================
-// CACHE SYSTEM
+// PROMETHEUS EXPOSITION
 // ============================================================================
 
-fn save_cache(info: &Info) {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
+/// One metric family: `# HELP`/`# TYPE` header plus a sample per label set.
+/// Families without samples are left out entirely.
+fn prometheus_family(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+    if samples.is_empty() {
+        return;
+    }
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            out.push_str(&format!("{} {}\n", name, value));
+        } else {
+            out.push_str(&format!("{}{{{}}} {}\n", name, labels, value));
+        }
+    }
+}
+
+/// `key="value"` pairs with `\`, `"` and newlines escaped as the format requires.
+fn prometheus_labels(pairs: &[(&str, &str)]) -> String {
+    pairs.iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Leading number of a display string: "45°C" -> 45, "256/256" -> 256.
+fn leading_number(s: &str) -> Option<f64> {
+    let end = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+    s[..end].parse().ok()
+}
+
+impl Info {
+    /// Numeric modules as Prometheus gauges/counters; text-only modules are skipped.
+    fn to_prometheus(&self) -> String {
+        let mut out = String::with_capacity(2048);
+        let single = |v: f64| vec![(String::new(), v)];
+
+        if let Some(m) = self.memory {
+            prometheus_family(&mut out, "rustfetch_memory_used_bytes", "gauge", "Memory in use.", &single(m.used * 1024.0));
+            prometheus_family(&mut out, "rustfetch_memory_total_bytes", "gauge", "Total usable memory.", &single(m.total * 1024.0));
+            prometheus_family(&mut out, "rustfetch_memory_buffers_bytes", "gauge", "Memory used for block device buffers.", &single(m.buffers * 1024.0));
+            prometheus_family(&mut out, "rustfetch_memory_cached_bytes", "gauge", "Memory used for the page cache.", &single(m.cached * 1024.0));
+            prometheus_family(&mut out, "rustfetch_memory_shared_bytes", "gauge", "Shared memory (tmpfs, shm).", &single(m.shmem * 1024.0));
+        }
+        if let Some((used, total)) = self.swap {
+            prometheus_family(&mut out, "rustfetch_swap_used_bytes", "gauge", "Swap in use.", &single(used * 1024.0));
+            prometheus_family(&mut out, "rustfetch_swap_total_bytes", "gauge", "Total swap.", &single(total * 1024.0));
+        }
+        if let Some(ratio) = self.zram.as_ref().and_then(|z| z.ratio) {
+            prometheus_family(&mut out, "rustfetch_zram_compression_ratio", "gauge", "Uncompressed to compressed zram size.", &single(ratio));
+        }
+        if let Some(secs) = self.uptime_seconds {
+            prometheus_family(&mut out, "rustfetch_uptime_seconds", "gauge", "Time since boot.", &single(secs as f64));
+        }
+        if let Some(t) = self.cpu_temp.as_deref().and_then(leading_number) {
+            prometheus_family(&mut out, "rustfetch_cpu_temp_celsius", "gauge", "CPU package temperature.", &single(t));
+        }
+        if let Some(t) = self.mobo_temp.as_deref().and_then(leading_number) {
+            prometheus_family(&mut out, "rustfetch_mobo_temp_celsius", "gauge", "Motherboard temperature.", &single(t));
+        }
+        if let Some(ref fans) = self.fans {
+            let rpms: Vec<(String, f64)> = fans.iter()
+                .map(|(label, rpm)| (prometheus_labels(&[("fan", label)]), *rpm as f64))
+                .collect();
+            prometheus_family(&mut out, "rustfetch_fan_rpm", "gauge", "Fan speed from hwmon.", &rpms);
+        }
+        if let Some((cores, threads)) = self.cpu_cores {
+            prometheus_family(&mut out, "rustfetch_cpu_cores", "gauge", "Physical CPU cores.", &single(cores as f64));
+            prometheus_family(&mut out, "rustfetch_cpu_threads", "gauge", "Logical CPUs.", &single(threads as f64));
+        }
+
+        if let Some(ref gpus) = self.gpu {
+            let label = |i: usize| prometheus_labels(&[("gpu", &gpus[i]), ("index", &i.to_string())]);
+            let temps: Vec<(String, f64)> = self.gpu_temps.iter().flatten().enumerate()
+                .filter(|(i, _)| *i < gpus.len())
+                .filter_map(|(i, t)| Some((label(i), t.as_deref().and_then(leading_number)?)))
+                .collect();
+            prometheus_family(&mut out, "rustfetch_gpu_temp_celsius", "gauge", "GPU temperature.", &temps);
+            let vram: Vec<(usize, (f64, f64))> = self.gpu_vram_used.iter().flatten().enumerate()
+                .filter(|(i, _)| *i < gpus.len())
+                .filter_map(|(i, v)| Some((i, (*v)?)))
+                .collect();
+            let used: Vec<_> = vram.iter().map(|&(i, (u, _))| (label(i), u * BYTES_PER_GIB)).collect();
+            let total: Vec<_> = vram.iter().map(|&(i, (_, t))| (label(i), t * BYTES_PER_GIB)).collect();
+            prometheus_family(&mut out, "rustfetch_gpu_vram_used_bytes", "gauge", "GPU memory in use.", &used);
+            prometheus_family(&mut out, "rustfetch_gpu_vram_total_bytes", "gauge", "Total GPU memory.", &total);
+        }
+
+        if let Some(ref parts) = self.partitions {
+            let label = |p: &Partition| prometheus_labels(&[("device", &p.device), ("fstype", &p.fstype), ("mount", &p.mount)]);
+            let used: Vec<_> = parts.iter().map(|p| (label(p), p.used * BYTES_PER_GIB)).collect();
+            let size: Vec<_> = parts.iter().map(|p| (label(p), p.total * BYTES_PER_GIB)).collect();
+            prometheus_family(&mut out, "rustfetch_disk_used_bytes", "gauge", "Filesystem space in use.", &used);
+            prometheus_family(&mut out, "rustfetch_disk_size_bytes", "gauge", "Filesystem size.", &size);
+        }
+
+        if let Some(ref nets) = self.network {
+            let label = |n: &NetworkInfo| prometheus_labels(&[("iface", &n.interface)]);
+            let rx: Vec<_> = nets.iter().filter_map(|n| Some((label(n), n.rx_bytes? as f64))).collect();
+            let tx: Vec<_> = nets.iter().filter_map(|n| Some((label(n), n.tx_bytes? as f64))).collect();
+            let up: Vec<_> = nets.iter().map(|n| (label(n), if n.state == "UP" { 1.0 } else { 0.0 })).collect();
+            let ping: Vec<_> = nets.iter().filter_map(|n| Some((label(n), n.ping? / 1000.0))).collect();
+            prometheus_family(&mut out, "rustfetch_network_rx_bytes", "counter", "Bytes received since boot.", &rx);
+            prometheus_family(&mut out, "rustfetch_network_tx_bytes", "counter", "Bytes sent since boot.", &tx);
+            prometheus_family(&mut out, "rustfetch_network_up", "gauge", "1 if the interface is up.", &up);
+            prometheus_family(&mut out, "rustfetch_network_ping_seconds", "gauge", "Round-trip time of the ping test.", &ping);
+        }
+
+        if let Some(ref bat) = self.battery {
+            prometheus_family(&mut out, "rustfetch_battery_percent", "gauge", "Battery charge.", &single(bat.capacity as f64));
+            if let Some(w) = bat.power {
+                prometheus_family(&mut out, "rustfetch_battery_power_watts", "gauge", "Battery charge (+) or discharge (-) rate.", &single(w));
+            }
+        }
+        if let Some(n) = self.processes {
+            prometheus_family(&mut out, "rustfetch_processes", "gauge", "Running processes.", &single(n as f64));
+        }
+        if let Some(ref p) = self.process_states {
+            let by_state: Vec<_> = [("running", p.running), ("sleeping", p.sleeping), ("zombie", p.zombie), ("stopped", p.stopped)]
+                .iter()
+                .map(|(state, n)| (prometheus_labels(&[("state", state)]), *n as f64))
+                .collect();
+            prometheus_family(&mut out, "rustfetch_processes_by_state", "gauge", "Processes per scheduler state.", &by_state);
+        }
+        let pressure: Vec<_> = [("memory", self.memory_pressure), ("cpu", self.cpu_pressure), ("io", self.io_pressure)]
+            .iter()
+            .filter_map(|(resource, pct)| pct.map(|p| (prometheus_labels(&[("resource", resource)]), p)))
+            .collect();
+        prometheus_family(&mut out, "rustfetch_pressure_some_avg10_percent", "gauge", "Share of the last 10s some task stalled on the resource (PSI).", &pressure);
+        if let Some(n) = self.users {
+            prometheus_family(&mut out, "rustfetch_users", "gauge", "Logged-in users.", &single(n as f64));
+        }
+        if let Some((kernel, userspace)) = self.boot_duration {
+            prometheus_family(&mut out, "rustfetch_boot_duration_seconds", "gauge", "Boot time per stage from systemd-analyze.",
+                &[("stage=\"kernel\"".to_string(), kernel), ("stage=\"userspace\"".to_string(), userspace)]);
+        }
+        if let Some(n) = self.updates {
+            prometheus_family(&mut out, "rustfetch_updates_available", "gauge", "Pending package updates.", &single(n as f64));
+        }
+        if let Some(ref units) = self.failed_units {
+            prometheus_family(&mut out, "rustfetch_failed_units", "gauge", "Failed systemd units.", &single(units.len() as f64));
+        }
+        if let Some(bits) = self.entropy.as_deref().and_then(leading_number) {
+            prometheus_family(&mut out, "rustfetch_entropy_bits", "gauge", "Available kernel entropy.", &single(bits));
+        }
+        out
+    }
+}
+
+// ============================================================================
+// CACHE SYSTEM
+// ============================================================================
+
+fn save_cache(info: &Info) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
         .unwrap_or(0);
     
-    let json = format!("{{\"timestamp\":{},\"data\":{}}}", now, info.to_json());
+    let json = format!("{{\"schema_version\":{},\"timestamp\":{},\"data\":{}}}",
+        JSON_SCHEMA_VERSION, now, info.to_json());
     let _ = fs::write(CACHE_FILE, json);
 }
 
+/// The slow, rarely changing fields (packages, bootloader) of a main cache file
+/// younger than `ttl` seconds (0 = never expires) written by this schema version.
+fn load_cache(ttl: u64) -> Option<Info> {
+    let cache = JsonValue::parse(&fs::read_to_string(CACHE_FILE).ok()?)?;
+    let number = |path: &str| match cache.get_path(path) {
+        Some(JsonValue::Number(n)) => n.parse::<u64>().ok(),
+        _ => None,
+    };
+    if number("schema_version") != Some(JSON_SCHEMA_VERSION as u64) {
+        return None;
+    }
+    let timestamp = number("timestamp")?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    if ttl > 0 && now.saturating_sub(timestamp) > ttl {
+        log_debug("CACHE", "Cache expired");
+        return None;
+    }
+
+    let mut info = Info::default();
+    if let Some(JsonValue::Object(counts)) = cache.get_path("data.packages") {
+        info.packages = Some(counts.iter()
+            .filter_map(|(name, count)| match count {
+                JsonValue::Number(n) => Some((name.clone(), n.parse().ok()?)),
+                _ => None,
+            })
+            .collect());
+    }
+    if let Some(JsonValue::Str(bootloader)) = cache.get_path("data.bootloader") {
+        info.bootloader = Some(bootloader.clone());
+    }
+    if info.packages.is_none() && info.bootloader.is_none() {
+        return None;
+    }
+    Some(info)
+}
+
+/// Cached public addresses, if the cache file is younger than `ttl` seconds
+/// (0 = never expires) and holds at least one valid address.
+fn load_public_ip_cache(ttl: u64) -> Option<(Option<String>, Option<String>)> {
+    let content = fs::read_to_string(PUBLIC_IP_CACHE_FILE).ok()?;
+    let field = |key: &str| content.lines().find_map(|l| l.strip_prefix(key)?.strip_prefix('=')).map(str::trim);
+
+    let timestamp: u64 = field("timestamp")?.parse().ok()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    if ttl > 0 && now.saturating_sub(timestamp) > ttl {
+        log_debug("PUBLIC_IP", "Public IP cache expired");
+        return None;
+    }
+    // Anything that doesn't parse as an address of the right family is ignored
+    let v4 = field("ipv4").filter(|v| v.parse::<std::net::Ipv4Addr>().is_ok()).map(str::to_string);
+    let v6 = field("ipv6").filter(|v| v.parse::<std::net::Ipv6Addr>().is_ok()).map(str::to_string);
+    if v4.is_none() && v6.is_none() {
+        return None;
+    }
+    Some((v4, v6))
+}
+
+fn save_public_ip_cache(v4: Option<&str>, v6: Option<&str>) {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let _ = fs::write(PUBLIC_IP_CACHE_FILE,
+        format!("timestamp={}\nipv4={}\nipv6={}\n", now, v4.unwrap_or(""), v6.unwrap_or("")));
+}
+
 // ============================================================================
 // MAIN ENTRY
 // ============================================================================
@@ -798,8 +2922,8 @@ fn main() {
             log_info("CONFIG", "Command line arguments parsed successfully");
             log_debug("CONFIG", &format!("Color enabled: {}, Theme: {}, JSON output: {}", 
                 cfg.use_color, cfg.color_scheme, cfg.json_output));
-            log_debug("CONFIG", &format!("Cache enabled: {}, TTL: {}s, Fast mode: {}", 
-                cfg.cache_enabled, cfg.cache_ttl, cfg.fast_mode));
+            log_debug("CONFIG", &format!("Cache read: {}, write: {}, TTL: {}s, Fast mode: {}", 
+                cfg.cache_read, cfg.cache_write, cfg.cache_ttl, cfg.fast_mode));
             cfg
         },
         None => {
@@ -808,6 +2932,18 @@ fn main() {
         }
     };
     
+    if let Some(ref theme) = config.preview_theme {
+        print_theme_preview(&config, theme);
+        return;
+    }
+    
+    // Hardened containers can mask /proc; say so once instead of printing a
+    // mostly empty fetch with no explanation
+    if cfg!(target_os = "linux") && !proc_accessible() {
+        log_warn("STARTUP", "/proc is not readable, most modules will be empty");
+        eprintln!("{}: (/proc not accessible — run with host PID namespace?)", PROGRAM_NAME);
+    }
+    
     if config.benchmark {
         log_info("BENCHMARK", "Running in benchmark mode");
         run_benchmarks(&config);
@@ -815,331 +2951,53 @@ fn main() {
         return;
     }
     
+    if let Some(ref field) = config.field {
+        if !run_field_query(&config, field) {
+            std::process::exit(1);
+        }
+        return;
+    }
+    
+    if let Some(interval) = config.watch {
+        log_info("WATCH", &format!("Refreshing every {}s", interval));
+        run_watch(&config, interval);
+        return;
+    }
+    
     log_info("EXECUTION", "Beginning system information collection");
     let start_time = std::time::Instant::now();
-    // Snapshot /proc/net/dev as early as possible for bandwidth delta
-    let net_start = if config.show_network { 
-        log_debug("NETWORK", "Reading initial network statistics from /proc/net/dev");
-        match read_file_trim("/proc/net/dev") {
-            Some(data) => {
-                log_debug("NETWORK", "Successfully captured initial network state");
-                Some(data)
-            },
-            None => {
-                log_warn("NETWORK", "Failed to read /proc/net/dev for network statistics");
-                None
-            }
-        }
-    } else { 
-        log_debug("NETWORK", "Network display disabled, skipping network stats");
-        None 
-    };
-
-    log_info("THREADS", "Spawning 5 parallel threads for system information gathering");
-    let info = thread::scope(|s| {
-        // ── Thread 1: pure env + file reads. ZERO spawns. ──
-        log_debug("THREAD1", "Starting Thread 1: Environment and file-based info (user, hostname, OS, kernel, etc.)");
-        let cfg1 = config.clone();
-        let t1 = s.spawn(move || {
-            log_debug("THREAD1", "Collecting user information");
-            let user        = get_user();
-            if user.is_some() { log_debug("THREAD1", "User information collected successfully"); }
-            else { log_warn("THREAD1", "Failed to determine current user"); }
-            
-            log_debug("THREAD1", "Collecting hostname");
-            let hostname    = get_hostname();
-            if hostname.is_some() { log_debug("THREAD1", "Hostname collected successfully"); }
-            else { log_warn("THREAD1", "Failed to determine hostname"); }
-            
-            log_debug("THREAD1", "Detecting operating system");
-            let os          = get_os();
-            if os.is_some() { log_debug("THREAD1", &format!("OS detected: {:?}", os)); }
-            else { log_warn("THREAD1", "Failed to detect operating system"); }
-            
-            log_debug("THREAD1", "Reading kernel version");
-            let kernel      = get_kernel();
-            if kernel.is_some() { log_debug("THREAD1", &format!("Kernel: {:?}", kernel)); }
-            else { log_warn("THREAD1", "Failed to read kernel version"); }
-            
-            let uptime      = if cfg1.show_uptime    { 
-                log_debug("THREAD1", "Calculating system uptime");
-                let up = get_uptime();
-                if up.is_some() { log_debug("THREAD1", "Uptime calculated successfully"); }
-                else { log_warn("THREAD1", "Failed to calculate uptime"); }
-                up
-            } else { None };
-            
-            let shell       = if cfg1.show_shell     { 
-                log_debug("THREAD1", "Detecting shell");
-                let sh = get_shell();
-                if sh.is_some() { log_debug("THREAD1", &format!("Shell detected: {:?}", sh)); }
-                else { log_warn("THREAD1", "Failed to detect shell"); }
-                sh
-            } else { None };
-            
-            let de          = if cfg1.show_de        { 
-                log_debug("THREAD1", "Detecting desktop environment");
-                let desktop = get_de();
-                if desktop.is_some() { log_debug("THREAD1", &format!("DE detected: {:?}", desktop)); }
-                else { log_debug("THREAD1", "No desktop environment detected (normal for servers/minimal installs)"); }
-                desktop
-            } else { None };
-            
-            let init        = if cfg1.show_init      { 
-                log_debug("THREAD1", "Detecting init system");
-                get_init()
-            } else { None };
-            
-            let terminal    = if cfg1.show_terminal  { 
-                log_debug("THREAD1", "Detecting terminal emulator");
-                get_terminal()
-            } else { None };
-            
-            let locale      = if cfg1.show_locale    { 
-                log_debug("THREAD1", "Reading locale settings");
-                get_locale()
-            } else { None };
-            
-            let model       = if cfg1.show_model     { 
-                log_debug("THREAD1", "Reading hardware model information");
-                get_model()
-            } else { None };
-            
-            let motherboard = if cfg1.show_motherboard { 
-                log_debug("THREAD1", "Reading motherboard information");
-                get_motherboard()
-            } else { None };
-            
-            let bios        = if cfg1.show_bios      { 
-                log_debug("THREAD1", "Reading BIOS version");
-                get_bios()
-            } else { None };
-            
-            log_debug("THREAD1", "Thread 1 completed successfully");
-            (user, hostname, os, kernel, uptime, shell, de, init, terminal, locale, model, motherboard, bios)
-        });
-
-        // ── Thread 2: cpu, mem+swap (1 read), battery, processes, users, entropy ──
-        log_debug("THREAD2", "Starting Thread 2: CPU, memory, battery, and process info");
-        let cfg2 = config.clone();
-        let t2 = s.spawn(move || {
-            log_debug("THREAD2", "Collecting CPU information");
-            let cpu_info  = get_cpu_info_combined();
-            if cpu_info.name.is_some() { log_debug("THREAD2", &format!("CPU detected: {:?}", cpu_info.name)); }
-            else { log_warn("THREAD2", "Failed to detect CPU name"); }
-            
-            let cpu_temp  = if cfg2.show_cpu_temp && !cfg2.fast_mode { 
-                log_debug("THREAD2", "Reading CPU temperature");
-                let temp = get_cpu_temp();
-                if temp.is_some() { log_debug("THREAD2", &format!("CPU temp: {:?}°C", temp)); }
-                else { log_warn("THREAD2", "CPU temperature not available (normal for some systems/VMs)"); }
-                temp
-            } else { 
-                if cfg2.fast_mode { log_debug("THREAD2", "Skipping CPU temperature (fast mode enabled)"); }
-                None 
-            };
-            
-            log_debug("THREAD2", "Reading memory and swap information");
-            let (memory, swap) = if cfg2.show_memory || cfg2.show_swap { 
-                let mem_swap = get_memory_and_swap();
-                if mem_swap.0.is_some() { log_debug("THREAD2", "Memory info collected successfully"); }
-                else { log_warn("THREAD2", "Failed to read memory information"); }
-                mem_swap
-            } else { (None, None) };
-            
-            let battery   = if cfg2.show_battery   { 
-                log_debug("THREAD2", "Checking for battery");
-                let bat = get_battery();
-                if bat.is_some() { log_debug("THREAD2", &format!("Battery found: {:?}", bat)); }
-                else { log_debug("THREAD2", "No battery detected (normal for desktops)"); }
-                bat
-            } else { None };
-            
-            let processes = if cfg2.show_processes { 
-                log_debug("THREAD2", "Counting running processes");
-                get_processes()
-            } else { None };
-            
-            let users     = if cfg2.show_users     { 
-                log_debug("THREAD2", "Counting logged-in users");
-                get_users_count()
-            } else { None };
-            
-            let entropy   = if cfg2.show_entropy   { 
-                log_debug("THREAD2", "Reading system entropy");
-                get_entropy()
-            } else { None };
-            
-            log_debug("THREAD2", "Thread 2 completed successfully");
-            (cpu_info, cpu_temp, memory, swap, battery, processes, users, entropy)
-        });
-
-        // ── Thread 3: single lspci -v → gpu names + vram, then gpu temps ──
-        log_debug("THREAD3", "Starting Thread 3: GPU detection and information");
-        let cfg3 = config.clone();
-        let t3 = s.spawn(move || {
-            let (gpus, gpu_vram) = if cfg3.show_gpu || cfg3.show_gpu_vram {
-                log_debug("THREAD3", "Running lspci to detect GPU(s)");
-                let gpu_info = get_gpu_combined();
-                if gpu_info.0.is_some() { log_debug("THREAD3", &format!("GPU(s) detected: {:?}", gpu_info.0)); }
-                else { log_warn("THREAD3", "No GPU detected or lspci unavailable"); }
-                gpu_info
-            } else { (None, None) };
-            
-            let gpu_temps = if cfg3.show_gpu && !cfg3.fast_mode {
-                log_debug("THREAD3", "Reading GPU temperature");
-                let temps = get_gpu_temp_with_gpus(gpus.as_ref());
-                if temps.is_some() { log_debug("THREAD3", &format!("GPU temps: {:?}°C", temps)); }
-                else { log_debug("THREAD3", "GPU temperature not available (normal for some GPUs/drivers)"); }
-                temps
-            } else { 
-                if cfg3.fast_mode { log_debug("THREAD3", "Skipping GPU temperature (fast mode enabled)"); }
-                None 
-            };
-            
-            log_debug("THREAD3", "Thread 3 completed successfully");
-            (gpus, gpu_temps, gpu_vram)
-        });
-
-        // ── Thread 4: packages, partitions (statfs), bootloader, wm, failed, theme ──
-        log_debug("THREAD4", "Starting Thread 4: Package counts, partitions, bootloader, WM, and theme");
-        let cfg4 = config.clone();
-        let t4 = s.spawn(move || {
-            let packages     = if cfg4.show_packages     { 
-                log_debug("THREAD4", "Counting installed packages");
-                let pkgs = get_packages();
-                if pkgs.is_some() { log_debug("THREAD4", &format!("Packages counted: {:?}", pkgs)); }
-                else { log_warn("THREAD4", "Failed to count packages"); }
-                pkgs
-            } else { None };
-            
-            let partitions   = if cfg4.show_partitions   { 
-                log_debug("THREAD4", "Reading partition information");
-                get_partitions_impl()
-            } else { None };
-            
-            let boot_time    = if cfg4.show_boot_time    { 
-                log_debug("THREAD4", "Calculating boot time");
-                get_boot_time()
-            } else { None };
-            
-            let bootloader   = if cfg4.show_bootloader   { 
-                log_debug("THREAD4", "Detecting bootloader");
-                get_bootloader()
-            } else { None };
-            
-            let wm           = if cfg4.show_wm           { 
-                log_debug("THREAD4", "Detecting window manager");
-                let window_mgr = get_wm();
-                if window_mgr.is_some() { log_debug("THREAD4", &format!("WM detected: {:?}", window_mgr)); }
-                else { log_debug("THREAD4", "No window manager detected (normal for servers)"); }
-                window_mgr
-            } else { None };
-            
-            let public_ip    = if cfg4.show_public_ip && !cfg4.fast_mode { 
-                log_debug("THREAD4", "Fetching public IP address (may take a moment)");
-                let ip = get_public_ip();
-                if ip.is_some() { log_debug("THREAD4", "Public IP retrieved"); }
-                else { log_warn("THREAD4", "Failed to retrieve public IP (check internet connection)"); }
-                ip
-            } else { 
-                if cfg4.fast_mode { log_debug("THREAD4", "Skipping public IP (fast mode enabled)"); }
-                None 
-            };
-            
-            let failed_units = if cfg4.show_failed_units { 
-                log_debug("THREAD4", "Checking for failed systemd units");
-                get_failed_units()
-            } else { None };
-            
-            let theme_info   = if cfg4.show_theme || cfg4.show_icons || cfg4.show_font {
-                log_debug("THREAD4", "Reading desktop theme information");
-                get_theme_info()
-            } else { ThemeInfo { theme: None, icons: None, font: None } };
-            
-            log_debug("THREAD4", "Thread 4 completed successfully");
-            (packages, partitions, boot_time, bootloader, wm, public_ip, failed_units, theme_info)
-        });
-
-        // ── Thread 5: display+resolution (1 xrandr) + prefetch ip for network ──
-        log_debug("THREAD5", "Starting Thread 5: Display info and network IP prefetch");
-        let cfg5 = config.clone();
-        let t5 = s.spawn(move || {
-            let (display, resolution) = if cfg5.show_display || cfg5.show_resolution {
-                log_debug("THREAD5", "Running xrandr to detect display and resolution");
-                let disp_info = get_display_and_resolution();
-                if disp_info.0.is_some() || disp_info.1.is_some() { 
-                    log_debug("THREAD5", "Display information collected"); 
-                } else { 
-                    log_debug("THREAD5", "Display info not available (normal for headless/server systems)"); 
-                }
-                disp_info
-            } else { (None, None) };
-            
-            // Prefetch ip output so network assembly after join has zero extra latency
-            let ip_out = if cfg5.show_network { 
-                log_debug("THREAD5", "Pre-fetching network IP addresses");
-                run_cmd("ip", &["-o", "addr", "show"])
-            } else { None };
-            
-            log_debug("THREAD5", "Thread 5 completed successfully");
-            (display, resolution, ip_out)
-        });
-
-        // ── join ──
-        log_debug("THREADS", "Waiting for all threads to complete");
-        let (user, hostname, os, kernel, uptime, shell, de, init, terminal, locale, model, motherboard, bios) = t1.join().unwrap();
-        log_debug("THREADS", "Thread 1 joined");
-        
-        let (cpu_info, cpu_temp, memory, swap, battery, processes, users, entropy) = t2.join().unwrap();
-        log_debug("THREADS", "Thread 2 joined");
-        
-        let (gpu, gpu_temps, gpu_vram) = t3.join().unwrap();
-        log_debug("THREADS", "Thread 3 joined");
-        
-        let (packages, partitions, boot_time, bootloader, wm, public_ip, failed_units, theme_info) = t4.join().unwrap();
-        log_debug("THREADS", "Thread 4 joined");
-        
-        let (display, resolution, ip_out) = t5.join().unwrap();
-        log_debug("THREADS", "Thread 5 joined - all threads completed");
-
-        // Network: uses pre-fetched ip output — no spawn on critical path
-        log_debug("NETWORK", "Finalizing network statistics");
-        let network = if config.show_network {
-            let delta = start_time.elapsed().as_secs_f64();
-            log_debug("NETWORK", &format!("Network delta time: {:.3}s", delta));
-            let net = get_network_final_with_ip(net_start, delta, config.show_network_ping, ip_out);
-            if net.is_some() { log_debug("NETWORK", "Network information collected successfully"); }
-            else { log_warn("NETWORK", "Failed to collect network information"); }
-            net
-        } else { None };
-
-        log_info("COLLECTION", "All system information collected successfully");
-
-        Info {
-            user, hostname, os, kernel, uptime, shell, de, wm, init, terminal,
-            cpu: cpu_info.name,
-            cpu_temp,
-            cpu_cores: if cpu_info.cores.is_some() && cpu_info.threads > 0 {
-                Some((cpu_info.cores.unwrap_or(cpu_info.threads), cpu_info.threads))
-            } else { None },
-            cpu_cache: cpu_info.cache,
-            cpu_freq: cpu_info.freq,
-            gpu, gpu_temps, gpu_vram,
-            memory, swap, partitions, network, display, battery,
-            model, motherboard, bios,
-            theme: theme_info.theme, icons: theme_info.icons, font: theme_info.font,
-            processes, users, entropy, locale, public_ip, resolution, failed_units,
-            boot_time, bootloader, packages,
-        }
-    });
+    let info = collect_info(&config);
     
     let elapsed = start_time.elapsed();
     log_info("PERFORMANCE", &format!("Total execution time: {:.3}s", elapsed.as_secs_f64()));
     
-    if config.json_output {
+    // Warm-up only: write the cache in the foreground (nothing else keeps the
+    // process alive for a background write) and exit without rendering
+    if config.warm_cache {
+        log_info("CACHE", "Warming cache, output suppressed");
+        save_cache(&info);
+        log_info("SHUTDOWN", "Cache warmed");
+        return;
+    }
+    
+    if config.prometheus_output {
+        log_debug("OUTPUT", "Rendering output in Prometheus format");
+        write_output(&config, info.to_prometheus().as_bytes());
+    } else if config.json_output {
         log_debug("OUTPUT", "Rendering output in JSON format");
-        println!("{}", info.to_json());
+        let json = info.to_json();
+        let json = if config.json_pretty {
+            match JsonValue::parse(&json) {
+                Some(value) => value.to_json_pretty(0),
+                None => {
+                    log_warn("OUTPUT", "Could not read back the generated JSON, printing it compact");
+                    json
+                }
+            }
+        } else {
+            json
+        };
+        write_output(&config, format!("{}\n", json).as_bytes());
         log_info("OUTPUT", "JSON output rendered successfully");
     } else {
         log_debug("OUTPUT", "Rendering output in standard format");
@@ -1148,7 +3006,7 @@ fn main() {
     }
     
     // Fire-and-forget cache write — doesn't block exit
-    if config.cache_enabled {
+    if config.cache_write && !info.from_cache {
         log_debug("CACHE", "Spawning background thread to save cache");
         let info_c = info.clone();
         std::thread::spawn(move || {
@@ -1156,13 +3014,722 @@ fn main() {
             save_cache(&info_c);
             log_debug("CACHE", "Cache saved successfully");
         });
+    } else if info.from_cache {
+        log_debug("CACHE", "Served from the cache, leaving it as is");
     } else {
-        log_debug("CACHE", "Cache disabled, skipping save");
+        log_debug("CACHE", "Cache writes disabled, skipping save");
     }
     
     log_info("SHUTDOWN", "Rustfetch completed successfully");
 }
 
+/// `--field`: print one value from the JSON output by its dotted path
+/// ("memory.used", "network.0.ipv4"). Strings print bare, fractional numbers
+/// with two decimals, objects and arrays as compact JSON. Returns false (exit
+/// status 1, nothing printed) when the field is missing or null.
+fn run_field_query(config: &Config, field: &str) -> bool {
+    let info = collect_info(&config_for_field(config, field));
+    let json = info.to_json();
+    let value = match JsonValue::parse(&json) {
+        Some(root) => root,
+        None => {
+            log_error("FIELD", "Could not read back the generated JSON");
+            return false;
+        }
+    };
+    let text = match value.get_path(field) {
+        None | Some(JsonValue::Null) => {
+            log_debug("FIELD", &format!("'{}' is absent", field));
+            return false;
+        }
+        Some(JsonValue::Str(s)) => s.clone(),
+        Some(JsonValue::Number(n)) => match n.parse::<f64>() {
+            Ok(v) if v.fract() != 0.0 => format!("{:.2}", v).trim_end_matches('0').trim_end_matches('.').to_string(),
+            _ => n.clone(),
+        },
+        Some(other) => other.to_json(),
+    };
+    write_output(config, format!("{}\n", text).as_bytes());
+    true
+}
+
+/// `config` with only the module behind a JSON key switched on, so `--field`
+/// skips every other probe. Unknown keys keep the full module set.
+fn config_for_field(config: &Config, field: &str) -> Config {
+    let key = field.split('.').next().unwrap_or("");
+    if key == "custom" {
+        return Config { custom_modules: config.custom_modules.clone(), ..config_without_modules(config) };
+    }
+    let Some(enable) = field_module(key) else {
+        return config.clone();
+    };
+    let mut only = config_without_modules(config);
+    enable(&mut only);
+    only
+}
+
+/// Switches on the module behind a top-level JSON key; None for keys `to_json`
+/// doesn't write (and for "custom", which carries the user's commands instead).
+fn field_module(key: &str) -> Option<fn(&mut Config)> {
+    let enable: fn(&mut Config) = match key {
+        "os" | "os_id" | "os_id_like" | "os_version_id" | "os_build_id" => |c| c.show_os = true,
+        "kernel" | "arch" => |c| c.show_kernel = true,
+        "kernel_installed" => |c| { c.show_kernel = true; c.show_kernel_check = true; },
+        "uptime" | "uptime_seconds" => |c| c.show_uptime = true,
+        "boot_time" => |c| c.show_boot_time = true,
+        "boot_duration" => |c| c.show_boot_duration = true,
+        // collected whenever WSL is detected
+        "wsl" => |_| {},
+        "bootloader" => |c| c.show_bootloader = true,
+        "packages" | "packages_total" => |c| c.show_packages = true,
+        "updates" => |c| c.show_updates = true,
+        "shell" => |c| c.show_shell = true,
+        "de" => |c| c.show_de = true,
+        "wm" => |c| c.show_wm = true,
+        "init" => |c| c.show_init = true,
+        "terminal" => |c| c.show_terminal = true,
+        "terminal_font" => |c| c.show_terminal_font = true,
+        "term_size" => |c| c.show_term_size = true,
+        "cpu" | "cpu_vendor" | "cpu_uarch" => |c| c.show_cpu = true,
+        "cpu_temp" => |c| c.show_cpu_temp = true,
+        "cpu_features" => |c| c.show_cpu_features = true,
+        "cpu_governor" => |c| c.show_governor = true,
+        "cpu_freq" => |c| c.show_cpu_freq = true,
+        "gpu" | "gpu_active" | "gpu_compositor" | "gpu_temps" => |c| c.show_gpu = true,
+        "gpu_vram_used" => |c| { c.show_gpu = true; c.show_gpu_vram = true; },
+        "memory" => |c| c.show_memory = true,
+        "swap" => |c| c.show_swap = true,
+        "pressure" => |c| { c.show_memory_pressure = true; c.show_pressure_all = true; },
+        "network" => |c| c.show_network = true,
+        "gateway" => |c| c.show_gateway = true,
+        "dns" => |c| c.show_dns = true,
+        "vpn" => |c| c.show_vpn = true,
+        "public_ip" | "public_ipv4" | "public_ipv6" => |c| c.show_public_ip = true,
+        "display" | "monitors" => |c| { c.show_display = true; c.show_resolution = true; },
+        "battery" => |c| c.show_battery = true,
+        "power_source" => |c| c.show_power = true,
+        "processes" => |c| c.show_processes = true,
+        "process_states" => |c| { c.show_processes = true; c.show_processes_detail = true; },
+        "theme" => |c| c.show_theme = true,
+        "icons" => |c| c.show_icons = true,
+        "font" => |c| c.show_font = true,
+        "locale" => |c| c.show_locale = true,
+        "audio" => |c| c.show_audio = true,
+        "model" => |c| c.show_model = true,
+        "chassis" => |c| c.show_chassis = true,
+        "motherboard" => |c| c.show_motherboard = true,
+        "mobo_temp" => |c| c.show_mobo_temp = true,
+        "fans" => |c| c.show_fan = true,
+        "bios" => |c| c.show_bios = true,
+        "container" => |c| c.show_container = true,
+        "virtualization" => |c| c.show_virtualization = true,
+        // user, hostname and the version fields are always collected; the
+        // background is queried while parsing arguments
+        "user" | "hostname" | "schema_version" | "rustfetch_version" | "terminal_background" => |_| {},
+        _ => return None,
+    };
+    Some(enable)
+}
+
+/// `config` with every probe switched off (detail flags like
+/// `show_shell_version` are left alone; they do nothing on their own).
+fn config_without_modules(config: &Config) -> Config {
+    let mut c = config.clone();
+    for flag in [
+        &mut c.show_os, &mut c.show_kernel, &mut c.show_uptime, &mut c.show_boot_time, &mut c.show_boot_duration,
+        &mut c.show_bootloader, &mut c.show_packages, &mut c.show_updates, &mut c.show_shell, &mut c.show_de,
+        &mut c.show_wm, &mut c.show_init, &mut c.show_terminal, &mut c.show_terminal_font,
+        &mut c.show_term_size, &mut c.show_cpu, &mut c.show_cpu_temp, &mut c.show_gpu,
+        &mut c.show_memory, &mut c.show_swap, &mut c.show_memory_pressure, &mut c.show_partitions, &mut c.show_network,
+        &mut c.show_gateway, &mut c.show_dns, &mut c.show_vpn, &mut c.show_display,
+        &mut c.show_battery, &mut c.show_power, &mut c.show_model, &mut c.show_chassis, &mut c.show_motherboard,
+        &mut c.show_mobo_temp, &mut c.show_fan, &mut c.show_bios, &mut c.show_theme, &mut c.show_icons,
+        &mut c.show_font, &mut c.show_processes, &mut c.show_cpu_freq, &mut c.show_locale,
+        &mut c.show_audio, &mut c.show_public_ip, &mut c.show_cpu_cores, &mut c.show_cpu_cache,
+        &mut c.show_cpu_features, &mut c.show_governor, &mut c.show_gpu_vram, &mut c.show_resolution, &mut c.show_entropy,
+        &mut c.show_users, &mut c.show_failed_units, &mut c.show_container, &mut c.show_virtualization,
+    ] {
+        *flag = false;
+    }
+    c.custom_modules.clear();
+    c
+}
+
+/// `--watch`: re-collect every `interval` seconds until interrupted. Text mode
+/// redraws in place; JSON mode appends one timestamped object per line so the
+/// stream can be piped into `jq` or a log shipper. Stops quietly when the
+/// reader goes away.
+fn run_watch(config: &Config, interval: u64) {
+    let stdout = std::io::stdout();
+    loop {
+        let info = collect_info(config);
+        if config.json_output {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+            let json = info.to_json();
+            let mut out = stdout.lock();
+            if writeln!(out, "{{\"timestamp\":{},{}", now, &json[1..]).and_then(|_| out.flush()).is_err() {
+                log_debug("WATCH", "stdout closed, stopping");
+                return;
+            }
+        } else {
+            if config.strip_ansi {
+                println!();
+            } else {
+                print!("\x1b[H\x1b[2J");
+            }
+            render_output(&info, config);
+            if stdout.lock().flush().is_err() {
+                return;
+            }
+        }
+        thread::sleep(std::time::Duration::from_secs(interval));
+    }
+}
+
+// ============================================================================
+// COLLECTION (WORK QUEUE)
+// ============================================================================
+
+/// One finished probe, routed into the matching `Info` field(s) by `apply_collected()`.
+enum Collected {
+    User(Option<String>),
+    Hostname(Option<String>),
+    Os(Option<HashMap<String, String>>),
+    Kernel(Option<String>, Option<String>),
+    KernelInstalled(Option<String>),
+    Uptime(Option<u64>),
+    Shell(Option<String>),
+    De(Option<String>),
+    Init(Option<String>),
+    /// terminal (with version), terminal font
+    Terminal(Option<String>, Option<String>),
+    TermSize(Option<String>),
+    Locale(Option<String>),
+    Gateway(Option<String>),
+    Dns(Option<String>),
+    Vpn(Option<String>),
+    Audio(Option<String>),
+    Model(Option<String>),
+    Chassis(Option<String>),
+    Motherboard(Option<String>),
+    Bios(Option<String>),
+    Cpu(CpuInfo),
+    CpuTemp(Option<String>),
+    CpuFeatures(Option<String>),
+    Governor(Option<String>),
+    MoboTemp(Option<String>),
+    Fans(Option<Vec<(String, u32)>>),
+    MemorySwap(Option<MemoryInfo>, Option<(f64, f64)>),
+    Zram(Option<ZramSwap>),
+    /// memory, cpu, io
+    Pressure(Option<f64>, Option<f64>, Option<f64>),
+    Battery(Option<Battery>),
+    PowerSource(Option<String>),
+    Processes(Option<usize>, Option<ProcessStates>),
+    Users(Option<usize>),
+    Entropy(Option<String>),
+    Gpu {
+        gpus: Option<Vec<String>>,
+        vram: Option<Vec<String>>,
+        temps: Option<Vec<Option<String>>>,
+        vram_used: Option<Vec<Option<(f64, f64)>>>,
+        active: Option<usize>,
+        compositor: Option<usize>,
+    },
+    Packages(Option<Vec<(String, usize)>>),
+    Updates(Option<usize>),
+    Partitions(Option<Vec<Partition>>),
+    BootTime(Option<String>, Option<String>),
+    BootDuration(Option<(f64, f64)>),
+    Bootloader(Option<String>),
+    Wm(Option<String>),
+    PublicIp(Option<String>, Option<String>),
+    FailedUnits(Option<Vec<String>>),
+    Container(Option<String>),
+    Wsl(Option<String>),
+    Virtualization(Option<String>),
+    Theme(ThemeInfo),
+    Display(Option<String>, Option<Vec<Monitor>>),
+    IpAddr(Option<String>),
+    /// (index into `config.custom_modules`, label, output)
+    Custom(usize, String, Option<String>),
+}
+
+type Job<'a> = Box<dyn FnOnce() -> Collected + Send + 'a>;
+
+/// Logs the outcome of a probe that is expected to succeed on most systems.
+fn logged<T: std::fmt::Debug>(category: &str, what: &str, value: Option<T>) -> Option<T> {
+    match value {
+        Some(ref v) => log_debug(category, &format!("{} detected: {:?}", what, v)),
+        None => log_warn(category, &format!("Failed to detect {}", what.to_lowercase())),
+    }
+    value
+}
+
+/// Builds the probe list for `config`. Slow probes (spawns, network) go first so
+/// they start as early as possible; cheap file reads fill the gaps behind them.
+fn build_jobs(config: &Config) -> Vec<Job<'_>> {
+    let mut jobs: Vec<Job> = Vec::with_capacity(40);
+
+    // ── slow: subprocesses / network ──
+    if config.show_gpu || config.show_gpu_vram {
+        jobs.push(Box::new(move || {
+            log_debug("GPU", "Running lspci to detect GPU(s)");
+            let (gpus, vram, slots) = get_gpu_combined();
+            if gpus.is_some() { log_debug("GPU", &format!("GPU(s) detected: {:?}", gpus)); }
+            else { log_warn("GPU", "No GPU detected or lspci unavailable"); }
+
+            let temps = if config.show_gpu && !config.fast_mode && config.wsl.is_none() {
+                log_debug("GPU", "Reading GPU temperature");
+                get_gpu_temp_with_gpus(gpus.as_ref(), &slots)
+            } else {
+                if config.fast_mode { log_debug("GPU", "Skipping GPU temperature (fast mode enabled)"); }
+                None
+            };
+            let vram_used = if config.show_gpu && config.show_gpu_vram {
+                log_debug("GPU", "Reading GPU VRAM usage");
+                get_gpu_vram_usage(gpus.as_ref(), &slots, config.fast_mode)
+            } else { None };
+            let active = gpus.as_deref().and_then(|g| get_active_gpu(g, &slots));
+            let compositor = gpus.as_deref().and_then(|g| get_compositor_gpu(g, &slots));
+            Collected::Gpu { gpus, vram, temps, vram_used, active, compositor }
+        }));
+    }
+    if config.show_public_ip && !config.fast_mode {
+        jobs.push(Box::new(move || {
+            log_debug("PUBLIC_IP", "Fetching public IP address (may take a moment)");
+            let cached = if config.cache_read { load_public_ip_cache(config.public_ip_ttl) } else { None };
+            let (v4, v6) = match cached {
+                Some(ips) => {
+                    log_debug("PUBLIC_IP", "Using cached public IP");
+                    ips
+                }
+                None => {
+                    let (v4, v6) = get_public_ips();
+                    if config.cache_write && (v4.is_some() || v6.is_some()) {
+                        save_public_ip_cache(v4.as_deref(), v6.as_deref());
+                    }
+                    (v4, v6)
+                }
+            };
+            Collected::PublicIp(logged("PUBLIC_IP", "Public IPv4", v4), logged("PUBLIC_IP", "Public IPv6", v6))
+        }));
+    }
+    if config.show_packages {
+        jobs.push(Box::new(|| {
+            log_debug("PACKAGES", "Counting installed packages");
+            Collected::Packages(logged("PACKAGES", "Packages", get_packages()))
+        }));
+    }
+    if config.show_updates && !config.fast_mode {
+        jobs.push(Box::new(|| {
+            log_debug("UPDATES", "Checking for pending updates");
+            Collected::Updates(logged("UPDATES", "Updates", get_pending_updates()))
+        }));
+    }
+    if config.show_bootloader {
+        jobs.push(Box::new(|| Collected::Bootloader(get_bootloader())));
+    }
+    if config.show_display || config.show_resolution {
+        jobs.push(Box::new(|| {
+            log_debug("DISPLAY", "Detecting display and resolution");
+            let (display, monitors) = get_display_and_resolution();
+            if display.is_none() && monitors.is_none() {
+                log_debug("DISPLAY", "Display info not available (normal for headless/server systems)");
+            }
+            Collected::Display(display, monitors)
+        }));
+    }
+    if config.show_theme || config.show_icons || config.show_font {
+        jobs.push(Box::new(|| {
+            log_debug("THEME", "Reading desktop theme information");
+            Collected::Theme(get_theme_info())
+        }));
+    }
+    if config.show_wm {
+        jobs.push(Box::new(|| {
+            log_debug("WM", "Detecting window manager");
+            let wm = get_wm();
+            if wm.is_none() { log_debug("WM", "No window manager detected (normal for servers)"); }
+            Collected::Wm(wm)
+        }));
+    }
+    if config.show_users {
+        jobs.push(Box::new(|| Collected::Users(get_users_count())));
+    }
+    if config.show_failed_units {
+        jobs.push(Box::new(|| {
+            log_debug("SYSTEMD", "Checking for failed systemd units");
+            Collected::FailedUnits(get_failed_units())
+        }));
+    }
+    if config.show_container {
+        jobs.push(Box::new(move || {
+            let container = get_container(!config.fast_mode);
+            log_debug("CONTAINER", &format!("Container: {}", container.as_deref().unwrap_or("none")));
+            Collected::Container(Some(container.unwrap_or_else(|| "none".to_string())))
+        }));
+    }
+    if let Some(version) = config.wsl {
+        jobs.push(Box::new(move || Collected::Wsl(Some(wsl_description(version)))));
+    }
+    if config.show_virtualization {
+        jobs.push(Box::new(move || {
+            let virt = get_virtualization(!config.fast_mode);
+            log_debug("VIRT", &format!("Hypervisor: {}", virt.as_deref().unwrap_or("none (bare metal)")));
+            Collected::Virtualization(virt)
+        }));
+    }
+    for (index, custom) in config.custom_modules.iter().enumerate().filter(|_| !config.fast_mode) {
+        if custom.label.is_empty() || custom.command.is_empty() {
+            log_warn("CUSTOM", &format!("Skipping [[custom]] entry {}: needs both label and command", index + 1));
+            continue;
+        }
+        jobs.push(Box::new(move || Collected::Custom(index, custom.label.clone(), run_custom_module(custom))));
+    }
+    if config.show_network {
+        // Prefetch ip output so network assembly after the queue drains has zero extra latency
+        jobs.push(Box::new(|| {
+            log_debug("NETWORK", "Pre-fetching network IP addresses");
+            Collected::IpAddr(run_cmd("ip", &["-o", "addr", "show"]))
+        }));
+    }
+
+    // ── fast: env + file reads ──
+    jobs.push(Box::new(|| Collected::User(logged("USER", "User", get_user()))));
+    jobs.push(Box::new(|| Collected::Hostname(logged("HOSTNAME", "Hostname", get_hostname()))));
+    jobs.push(Box::new(|| {
+        let os_release = get_os_release();
+        logged("OS", "OS", os_release.as_ref().and_then(os_pretty_name));
+        Collected::Os(os_release)
+    }));
+    jobs.push(Box::new(|| Collected::Kernel(logged("KERNEL", "Kernel", get_kernel()), get_machine_arch())));
+    if config.show_kernel && config.show_kernel_check {
+        jobs.push(Box::new(|| Collected::KernelInstalled(get_kernel().and_then(|running| get_newer_installed_kernel(&running)))));
+    }
+    jobs.push(Box::new(|| {
+        let cpu = get_cpu_info_combined();
+        if cpu.name.is_some() { log_debug("CPU", &format!("CPU detected: {:?}", cpu.name)); }
+        else { log_warn("CPU", "Failed to detect CPU name"); }
+        Collected::Cpu(cpu)
+    }));
+    if config.show_cpu_temp && !config.fast_mode {
+        jobs.push(Box::new(|| {
+            let temp = get_cpu_temp();
+            if temp.is_none() { log_warn("CPU", "CPU temperature not available (normal for some systems/VMs)"); }
+            Collected::CpuTemp(temp)
+        }));
+    }
+    if config.show_cpu_features {
+        jobs.push(Box::new(|| Collected::CpuFeatures(logged("CPU", "CPU features", get_cpu_features()))));
+    }
+    if config.show_governor {
+        jobs.push(Box::new(|| Collected::Governor(logged("CPU", "CPU governor", get_cpu_governor()))));
+    }
+    if config.show_mobo_temp && !config.fast_mode {
+        jobs.push(Box::new(|| Collected::MoboTemp(logged("SENSORS", "Motherboard temperature", get_mobo_temp()))));
+    }
+    if config.show_fan {
+        jobs.push(Box::new(|| Collected::Fans(logged("SENSORS", "Fan speeds", get_fan_speeds()))));
+    }
+    if config.show_memory || config.show_swap {
+        jobs.push(Box::new(|| {
+            let (memory, swap) = get_memory_and_swap();
+            if memory.is_none() { log_warn("MEMORY", "Failed to read memory information"); }
+            Collected::MemorySwap(memory, swap)
+        }));
+    }
+    if config.show_swap && config.show_swap_detail {
+        jobs.push(Box::new(|| Collected::Zram(get_zram_swap())));
+    }
+    if config.show_memory_pressure {
+        jobs.push(Box::new(move || {
+            let (cpu, io) = if config.show_pressure_all { (get_pressure("cpu"), get_pressure("io")) } else { (None, None) };
+            Collected::Pressure(logged("MEMORY", "Memory pressure", get_pressure("memory")), cpu, io)
+        }));
+    }
+    if config.show_uptime { jobs.push(Box::new(|| Collected::Uptime(logged("UPTIME", "Uptime", get_uptime_seconds())))); }
+    if config.show_boot_time {
+        jobs.push(Box::new(move || {
+            let ts = get_boot_timestamp();
+            Collected::BootTime(ts.map(|t| format_local_timestamp(t, config.utc)), ts.map(|t| format_rfc3339(t, config.utc)))
+        }));
+    }
+    if config.show_boot_duration && !config.fast_mode {
+        jobs.push(Box::new(|| {
+            log_debug("SYSTEMD", "Reading boot duration from systemd-analyze");
+            Collected::BootDuration(get_boot_duration())
+        }));
+    }
+    if config.show_shell {
+        if config.show_shell_version {
+            jobs.push(Box::new(move || Collected::Shell(logged("SHELL", "Shell", get_shell_with_version(!config.fast_mode)))));
+        } else {
+            jobs.push(Box::new(|| Collected::Shell(logged("SHELL", "Shell", get_shell()))));
+        }
+    }
+    if config.show_de {
+        jobs.push(Box::new(move || {
+            let de = if config.show_de_version { get_de_with_version(!config.fast_mode) } else { get_de() };
+            if de.is_none() { log_debug("DE", "No desktop environment detected (normal for servers/minimal installs)"); }
+            Collected::De(de)
+        }));
+    }
+    if config.show_init { jobs.push(Box::new(move || Collected::Init(get_init(config.show_init_version && !config.fast_mode)))); }
+    if config.show_terminal || config.show_terminal_font {
+        // One walk up the process tree serves both lines
+        jobs.push(Box::new(move || {
+            let name = get_terminal_name();
+            let font = if config.show_terminal_font {
+                logged("TERMINAL", "Terminal font", name.as_deref().and_then(|n| get_terminal_font(n, !config.fast_mode)))
+            } else { None };
+            let terminal = name.filter(|_| config.show_terminal).map(terminal_with_version);
+            Collected::Terminal(terminal, font)
+        }));
+    }
+    if config.show_term_size { jobs.push(Box::new(|| Collected::TermSize(get_terminal_size()))); }
+    if config.show_locale {
+        jobs.push(Box::new(move || Collected::Locale(if config.show_locale_detail { get_locale_detail() } else { get_locale() })));
+    }
+    if config.show_gateway { jobs.push(Box::new(|| Collected::Gateway(logged("NETWORK", "Gateway", get_gateway())))); }
+    if config.show_dns { jobs.push(Box::new(|| Collected::Dns(logged("NETWORK", "DNS", get_dns())))); }
+    if config.show_vpn { jobs.push(Box::new(|| Collected::Vpn(get_vpn()))); }
+    if config.show_audio { jobs.push(Box::new(|| Collected::Audio(logged("AUDIO", "Audio server", get_audio_server())))); }
+    if config.show_model { jobs.push(Box::new(|| Collected::Model(get_model()))); }
+    if config.show_chassis { jobs.push(Box::new(|| Collected::Chassis(get_chassis()))); }
+    if config.show_motherboard { jobs.push(Box::new(|| Collected::Motherboard(get_motherboard()))); }
+    if config.show_bios { jobs.push(Box::new(|| Collected::Bios(get_bios()))); }
+    if config.show_battery {
+        jobs.push(Box::new(move || {
+            let bat = get_battery(config.show_battery_power);
+            if bat.is_none() { log_debug("BATTERY", "No battery detected (normal for desktops)"); }
+            Collected::Battery(bat)
+        }));
+    }
+    if config.show_power { jobs.push(Box::new(|| Collected::PowerSource(get_power_source()))); }
+    if config.show_processes {
+        if config.show_processes_detail {
+            jobs.push(Box::new(|| {
+                let states = get_process_states();
+                Collected::Processes(states.as_ref().map(|s| s.total), states)
+            }));
+        } else {
+            jobs.push(Box::new(|| Collected::Processes(get_processes(), None)));
+        }
+    }
+    if config.show_entropy { jobs.push(Box::new(|| Collected::Entropy(get_entropy()))); }
+    if config.show_partitions { jobs.push(Box::new(move || Collected::Partitions(get_partitions_impl(config.wsl.is_some())))); }
+
+    jobs
+}
+
+fn apply_collected(info: &mut Info, collected: Collected, ip_out: &mut Option<String>) {
+    match collected {
+        Collected::User(v) => info.user = v,
+        Collected::Hostname(v) => info.hostname = v,
+        Collected::Os(v) => {
+            if let Some(fields) = v {
+                info.os = os_pretty_name(&fields);
+                info.os_short = os_short_name(&fields);
+                info.os_id = fields.get("ID").cloned();
+                info.os_id_like = fields.get("ID_LIKE").cloned();
+                info.os_version_id = fields.get("VERSION_ID").cloned();
+                info.os_build_id = fields.get("BUILD_ID").cloned();
+            }
+        }
+        Collected::Kernel(kernel, arch) => {
+            info.kernel = kernel;
+            info.arch = arch;
+        }
+        Collected::KernelInstalled(v) => info.kernel_installed = v,
+        Collected::Uptime(v) => {
+            info.uptime = v.map(|secs| format_uptime(secs, UptimePrecision::Auto));
+            info.uptime_seconds = v;
+        }
+        Collected::Shell(v) => info.shell = v,
+        Collected::De(v) => info.de = v,
+        Collected::Init(v) => info.init = v,
+        Collected::Terminal(terminal, font) => {
+            info.terminal = terminal;
+            info.terminal_font = font;
+        }
+        Collected::TermSize(v) => info.term_size = v,
+        Collected::Locale(v) => info.locale = v,
+        Collected::Gateway(v) => info.gateway = v,
+        Collected::Dns(v) => info.dns = v,
+        Collected::Vpn(v) => info.vpn = v,
+        Collected::Audio(v) => info.audio = v,
+        Collected::Model(v) => info.model = v,
+        Collected::Chassis(v) => info.chassis = v,
+        Collected::Motherboard(v) => info.motherboard = v,
+        Collected::Bios(v) => info.bios = v,
+        Collected::Cpu(cpu) => {
+            info.cpu_cores = if cpu.cores.is_some() && cpu.threads > 0 {
+                Some((cpu.cores.unwrap_or(cpu.threads), cpu.threads))
+            } else { None };
+            info.cpu = cpu.name;
+            info.cpu_cache = cpu.cache;
+            info.cpu_vendor = cpu.vendor;
+            info.cpu_uarch = cpu.uarch;
+            info.cpu_freq = cpu.freq;
+        }
+        Collected::CpuTemp(v) => info.cpu_temp = v,
+        Collected::CpuFeatures(v) => info.cpu_features = v,
+        Collected::Governor(v) => info.cpu_governor = v,
+        Collected::MoboTemp(v) => info.mobo_temp = v,
+        Collected::Fans(v) => info.fans = v,
+        Collected::MemorySwap(memory, swap) => {
+            info.memory = memory;
+            info.swap = swap;
+        }
+        Collected::Zram(v) => info.zram = v,
+        Collected::Pressure(memory, cpu, io) => {
+            info.memory_pressure = memory;
+            info.cpu_pressure = cpu;
+            info.io_pressure = io;
+        }
+        Collected::Battery(v) => info.battery = v,
+        Collected::PowerSource(v) => info.power_source = v,
+        Collected::Processes(count, states) => {
+            info.processes = count;
+            info.process_states = states;
+        }
+        Collected::Users(v) => info.users = v,
+        Collected::Entropy(v) => info.entropy = v,
+        Collected::Gpu { gpus, vram, temps, vram_used, active, compositor } => {
+            info.gpu = gpus;
+            info.gpu_active = active;
+            info.gpu_compositor = compositor;
+            info.gpu_vram = vram;
+            info.gpu_temps = temps;
+            info.gpu_vram_used = vram_used;
+        }
+        Collected::Packages(v) => info.packages = v,
+        Collected::Updates(v) => info.updates = v,
+        Collected::Partitions(v) => info.partitions = v,
+        Collected::BootTime(human, rfc3339) => {
+            info.boot_time = human;
+            info.boot_time_rfc3339 = rfc3339;
+        }
+        Collected::BootDuration(v) => info.boot_duration = v,
+        Collected::Bootloader(v) => info.bootloader = v,
+        Collected::Wm(v) => info.wm = v,
+        Collected::PublicIp(v4, v6) => {
+            info.public_ipv4 = v4;
+            info.public_ipv6 = v6;
+        }
+        Collected::FailedUnits(v) => info.failed_units = v,
+        Collected::Container(v) => info.container = v,
+        Collected::Wsl(v) => info.wsl = v,
+        Collected::Virtualization(v) => info.virtualization = v,
+        Collected::Theme(t) => {
+            info.theme = t.theme;
+            info.icons = t.icons;
+            info.font = t.font;
+        }
+        Collected::Display(display, monitors) => {
+            info.display = display;
+            info.monitors = monitors;
+        }
+        Collected::IpAddr(v) => *ip_out = v,
+        Collected::Custom(index, label, v) => {
+            if let Some(output) = v { info.custom.push((index, label, output)); }
+        }
+    }
+}
+
+/// Runs every enabled probe on a fixed-size pool (one worker per available core)
+/// and folds the results into an `Info`.
+fn collect_info(config: &Config) -> Info {
+    let start_time = std::time::Instant::now();
+    // Snapshot /proc/net/dev as early as possible for bandwidth delta
+    let net_start = if config.show_network {
+        log_debug("NETWORK", "Reading initial network statistics from /proc/net/dev");
+        let snapshot = read_file_trim("/proc/net/dev");
+        if snapshot.is_none() { log_warn("NETWORK", "Failed to read /proc/net/dev for network statistics"); }
+        snapshot
+    } else {
+        log_debug("NETWORK", "Network display disabled, skipping network stats");
+        None
+    };
+
+    // --warm-cache exists to refresh the cache, so it never reads it
+    let cached = if config.cache_read && !config.warm_cache { load_cache(config.cache_ttl) } else { None };
+    let probe_config = match cached {
+        Some(ref c) => {
+            log_info("CACHE", "Using cached package count and bootloader");
+            Config {
+                show_packages: config.show_packages && c.packages.is_none(),
+                show_bootloader: config.show_bootloader && c.bootloader.is_none(),
+                ..config.clone()
+            }
+        }
+        None => config.clone(),
+    };
+
+    let jobs = build_jobs(&probe_config);
+    let job_count = jobs.len();
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(4).clamp(1, job_count.max(1));
+    log_info("THREADS", &format!("Running {} probes on {} worker threads", job_count, workers));
+
+    let queue = std::sync::Mutex::new(jobs.into_iter());
+    let (tx, rx) = std::sync::mpsc::channel::<Collected>();
+    thread::scope(|s| {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let queue = &queue;
+            s.spawn(move || loop {
+                let job = match queue.lock() {
+                    Ok(mut q) => q.next(),
+                    Err(_) => None,
+                };
+                match job {
+                    Some(job) => { let _ = tx.send(job()); }
+                    None => break,
+                }
+            });
+        }
+    });
+    drop(tx);
+
+    let mut info = Info::default();
+    if let Some(c) = cached {
+        info.from_cache = true;
+        if config.show_packages { info.packages = c.packages; }
+        if config.show_bootloader { info.bootloader = c.bootloader; }
+    }
+    let mut ip_out = None;
+    for collected in rx {
+        apply_collected(&mut info, collected, &mut ip_out);
+    }
+    log_debug("THREADS", "All probes completed");
+    // Workers finish in any order; custom lines keep the order of the config file
+    info.custom.sort_by_key(|(index, _, _)| *index);
+
+    // Queried while parsing arguments, before any output
+    info.terminal_bg = config.terminal_bg.clone();
+
+    // Env-var fallbacks can make DE and WM identical; don't print the same value twice
+    if let (Some(de), Some(wm)) = (&info.de, &info.wm) {
+        if de.eq_ignore_ascii_case(wm) {
+            log_debug("WM", "WM matches DE, hiding duplicate WM line");
+            info.wm = None;
+        }
+    }
+
+    // Network: uses pre-fetched ip output — no spawn on critical path
+    if config.show_network {
+        let delta = start_time.elapsed().as_secs_f64();
+        log_debug("NETWORK", &format!("Network delta time: {:.3}s", delta));
+        info.network = get_network_final_with_ip(net_start, delta, config, ip_out);
+        if info.network.is_some() { log_debug("NETWORK", "Network information collected successfully"); }
+        else { log_warn("NETWORK", "Failed to collect network information"); }
+    }
+
+    log_info("COLLECTION", "All system information collected successfully");
+    info
+}
+
 // ============================================================================
 // BENCHMARKING
 // ============================================================================
@@ -1183,59 +3750,226 @@ fn run_benchmarks(config: &Config) {
     bench!("Hostname", get_hostname());
     bench!("OS", get_os());
     bench!("Kernel", get_kernel());
+    bench!("Kernel check", get_kernel().and_then(|k| get_newer_installed_kernel(&k)));
     bench!("Uptime", get_uptime());
-    bench!("Boot time", get_boot_time());
+    bench!("Boot time", get_boot_time(config.utc));
     bench!("Bootloader", get_bootloader());
     bench!("Packages", get_packages());
+    bench!("Packages (sequential)", count_packages(false));
     bench!("Shell", get_shell());
+    bench!("Shell version", get_shell_with_version(!config.fast_mode));
     bench!("DE", get_de());
     bench!("WM", get_wm());
-    bench!("Init", get_init());
+    bench!("Init", get_init(config.show_init_version && !config.fast_mode));
     bench!("Terminal", get_terminal());
+    bench!("Terminal font", get_terminal_name().and_then(|n| get_terminal_font(&n, !config.fast_mode)));
+    bench!("Terminal size", get_terminal_size());
     bench!("CPU (combined)", get_cpu_info_combined());
+    bench!("CPU features", get_cpu_features());
+    bench!("CPU governor", get_cpu_governor());
     bench!("Memory+Swap", get_memory_and_swap());
-    bench!("Partitions", get_partitions_impl());
+    bench!("Partitions", get_partitions_impl(config.wsl.is_some()));
     bench!("Display+Res", get_display_and_resolution());
-    bench!("Battery", get_battery());
+    bench!("Battery", get_battery(true));
+    bench!("Power source", get_power_source());
+    bench!("Fans", get_fan_speeds());
     bench!("Model", get_model());
+    bench!("Chassis", get_chassis());
     bench!("Motherboard", get_motherboard());
     bench!("BIOS", get_bios());
     bench!("Theme info", get_theme_info());
     bench!("Processes", get_processes());
+    bench!("Process states", get_process_states().map(|s| s.total));
     bench!("Users", get_users_count());
     bench!("Entropy", get_entropy());
+    bench!("Pressure", get_pressure("memory"));
     bench!("Locale", get_locale());
+    bench!("Gateway", get_gateway());
+    bench!("DNS", get_dns());
+    bench!("VPN", get_vpn());
+    bench!("Audio", get_audio_server());
     bench!("Failed units", get_failed_units());
+    bench!("Container", get_container(!config.fast_mode));
+    bench!("Virtualization", get_virtualization(!config.fast_mode));
     bench!("GPU+VRAM", get_gpu_combined());
     
     if !config.fast_mode {
         println!("\nExpensive operations (skipped in --fast mode):");
         bench!("CPU temp", get_cpu_temp());
-        bench!("Public IP", get_public_ip());
-        let (gpus, _) = get_gpu_combined();
-        bench!("GPU temps", get_gpu_temp_with_gpus(gpus.as_ref()));
+        bench!("Mobo temp", get_mobo_temp());
+        bench!("Boot duration", get_boot_duration());
+        bench!("Public IP", get_public_ips());
+        bench!("Updates", get_pending_updates());
+        let (gpus, _, slots) = get_gpu_combined();
+        bench!("GPU temps", get_gpu_temp_with_gpus(gpus.as_ref(), &slots));
     } else {
         println!("\n(Use without --fast to benchmark expensive operations)");
     }
-    
-    println!("\nTip: Run 'rustfetch --fast' for ~60% faster execution");
+    
+    println!("\nTip: Run 'rustfetch --fast' for ~60% faster execution");
+}
+
+// ============================================================================
+// RENDERING UTILS
+// ============================================================================
+
+fn get_terminal_width(override_width: Option<usize>) -> usize {
+    // Explicit --width / config override beats any detection
+    if let Some(w) = override_width {
+        return w;
+    }
+    // $COLUMNS next (shell sets it, fastest)
+    if let Some(w) = env::var("COLUMNS").ok().and_then(|s| s.parse::<usize>().ok()) {
+        if w > 0 { return w; }
+    }
+    if let Some(ws) = get_winsize() {
+        if ws.cols > 0 { return ws.cols as usize; }
+    }
+    80
+}
+
+#[repr(C)]
+struct Winsize { rows: u16, cols: u16, xpixel: u16, ypixel: u16 }
+
+fn stdout_is_tty() -> bool {
+    extern "C" { fn isatty(fd: i32) -> i32; }
+    unsafe { isatty(1) == 1 }
+}
+
+/// "120x40" columns by rows, only when stdout is a terminal (piped output has no size).
+fn get_terminal_size() -> Option<String> {
+    if !stdout_is_tty() {
+        return None;
+    }
+    let ws = get_winsize().filter(|ws| ws.cols > 0 && ws.rows > 0)?;
+    Some(format!("{}x{}", ws.cols, ws.rows))
+}
+
+/// Background brightness via OSC 11: "dark" or "light". The terminal answers
+/// `ESC ] 11 ; rgb:RRRR/GGGG/BBBB` (BEL or ST terminated) on the tty, read in
+/// raw mode so it isn't echoed. Terminals that ignore the query time out
+/// after 200ms and give None.
+fn query_terminal_background() -> Option<String> {
+    use std::io::Read;
+    use std::os::unix::io::AsRawFd;
+    extern "C" {
+        fn tcgetattr(fd: i32, termios: *mut u8) -> i32;
+        fn tcsetattr(fd: i32, action: i32, termios: *const u8) -> i32;
+        fn cfmakeraw(termios: *mut u8);
+        fn poll(fds: *mut PollFd, nfds: NfdsT, timeout: i32) -> i32;
+    }
+    // nfds_t is unsigned long on Linux but unsigned int on macOS
+    #[cfg(target_os = "macos")]
+    type NfdsT = std::os::raw::c_uint;
+    #[cfg(not(target_os = "macos"))]
+    type NfdsT = std::os::raw::c_ulong;
+    #[repr(C)]
+    struct PollFd { fd: i32, events: i16, revents: i16 }
+    const TCSANOW: i32 = 0;
+    const POLLIN: i16 = 1;
+
+    if !stdout_is_tty() {
+        return None;
+    }
+    let mut tty = fs::OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let fd = tty.as_raw_fd();
+    // struct termios differs per platform; this is larger than any of them
+    let mut saved = [0u8; 256];
+    if unsafe { tcgetattr(fd, saved.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let mut raw = saved;
+    unsafe {
+        cfmakeraw(raw.as_mut_ptr());
+        tcsetattr(fd, TCSANOW, raw.as_ptr());
+    }
+
+    let mut reply = Vec::new();
+    if tty.write_all(b"\x1b]11;?\x07").and_then(|_| tty.flush()).is_ok() {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(200);
+        let mut buf = [0u8; 64];
+        loop {
+            let left = deadline.saturating_duration_since(std::time::Instant::now()).as_millis() as i32;
+            let mut pfd = PollFd { fd, events: POLLIN, revents: 0 };
+            if left <= 0 || unsafe { poll(&mut pfd, 1, left) } <= 0 {
+                break;
+            }
+            match tty.read(&mut buf) {
+                Ok(n) if n > 0 => reply.extend_from_slice(&buf[..n]),
+                _ => break,
+            }
+            if reply.ends_with(b"\x07") || reply.ends_with(b"\x1b\\") {
+                break;
+            }
+        }
+    }
+    unsafe { tcsetattr(fd, TCSANOW, saved.as_ptr()); }
+
+    let reply = String::from_utf8_lossy(&reply);
+    let rgb = reply.split("rgb:").nth(1)?;
+    // 1-4 hex digits per channel; scale each to 0.0-1.0
+    let channels: Vec<f64> = rgb.split('/')
+        .take(3)
+        .filter_map(|c| {
+            let hex: String = c.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+            let max = 16f64.powi(hex.len() as i32) - 1.0;
+            u32::from_str_radix(&hex, 16).ok().map(|v| v as f64 / max)
+        })
+        .collect();
+    if channels.len() != 3 {
+        log_debug("TERMINAL", &format!("Unrecognised OSC 11 reply {:?}", reply));
+        return None;
+    }
+    let luminance = 0.2126 * channels[0] + 0.7152 * channels[1] + 0.0722 * channels[2];
+    Some(if luminance < 0.5 { "dark" } else { "light" }.to_string())
 }
 
-// ============================================================================
-// RENDERING UTILS
-// ============================================================================
+/// ioctl TIOCGWINSZ on stderr — zero spawns
+fn get_winsize() -> Option<Winsize> {
+    extern "C" { fn ioctl(fd: i32, req: u64, ...) -> i32; }
+    let mut ws = Winsize { rows: 0, cols: 0, xpixel: 0, ypixel: 0 };
+    if unsafe { ioctl(2, 0x5413, &mut ws) } == 0 { Some(ws) } else { None }
+}
 
-fn get_terminal_width() -> usize {
-    // $COLUMNS first (shell sets it, fastest)
-    if let Some(w) = env::var("COLUMNS").ok().and_then(|s| s.parse::<usize>().ok()) {
-        if w > 0 { return w; }
+/// `s` without terminal escape sequences: CSI (colors, cursor moves), OSC
+/// and APC/DCS strings (hyperlinks, kitty graphics) and two-byte escapes.
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters, then one final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) { break; }
+                }
+            }
+            // String sequences end with BEL (OSC only) or ESC \
+            Some(']') | Some('_') | Some('P') | Some('^') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' { break; }
+                    if c == '\x1b' && chars.next_if_eq(&'\\').is_some() { break; }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Terminal columns taken by `c`: 2 for CJK, Hangul, fullwidth forms and emoji, else 1.
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF | 0xA000..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F | 0x1F900..=0x1F9FF | 0x20000..=0x3FFFD => 2,
+        _ => 1,
     }
-    // ioctl TIOCGWINSZ — zero spawns
-    #[repr(C)] struct Winsize { rows: u16, cols: u16, _xp: u16, _yp: u16 }
-    extern "C" { fn ioctl(fd: i32, req: u64, ...) -> i32; }
-    let mut ws = Winsize { rows: 0, cols: 0, _xp: 0, _yp: 0 };
-    if unsafe { ioctl(2, 0x5413, &mut ws) } == 0 && ws.cols > 0 { return ws.cols as usize; }
-    80
 }
 
 fn visible_len(s: &str) -> usize {
@@ -1249,7 +3983,7 @@ fn visible_len(s: &str) -> usize {
                 in_ansi = false;
             }
         } else {
-            len += 1;
+            len += char_width(c);
         }
     }
     len
@@ -1259,26 +3993,33 @@ fn truncate_ansi(s: &str, max_width: usize) -> String {
     let mut current_width = 0;
     let mut result = String::new();
     let mut in_ansi = false;
+    let mut seq = String::new();
+    // Whether an SGR colour/attribute is open at the current position
+    let mut sgr_active = false;
     
     for c in s.chars() {
         if c == '\x1b' {
             in_ansi = true;
+            seq.clear();
             result.push(c);
         } else if in_ansi {
             result.push(c);
+            seq.push(c);
             if c.is_ascii_alphabetic() {
                 in_ansi = false;
+                if c == 'm' {
+                    sgr_active = seq != "[0m" && seq != "[m";
+                }
             }
+        } else if current_width + char_width(c) <= max_width {
+            // a wide char that would straddle the limit is dropped whole
+            result.push(c);
+            current_width += char_width(c);
         } else {
-            if current_width < max_width {
-                result.push(c);
-                current_width += 1;
-            } else {
-                break;
-            }
+            break;
         }
     }
-    if !result.is_empty() && s.contains('\x1b') {
+    if sgr_active {
         result.push_str("\x1b[0m");
     }
     result
@@ -1289,226 +4030,664 @@ fn truncate_ansi(s: &str, max_width: usize) -> String {
 // ============================================================================
 
 fn render_output(info: &Info, config: &Config) {
+    let mut buf = Vec::new();
+    render_to(&mut buf, info, config);
+    if config.strip_ansi {
+        buf = strip_ansi(&String::from_utf8_lossy(&buf)).into_bytes();
+    }
+    write_output(config, &buf);
+}
+
+/// Hands finished output to stdout, or to the `--output` file (creating its
+/// directory). A path that can't be written is reported and exits with 1.
+fn write_output(config: &Config, bytes: &[u8]) {
+    let Some(ref path) = config.output else {
+        std::io::stdout().lock().write_all(bytes).unwrap_or(());
+        return;
+    };
+    let result = match Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) => fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e)),
+        None => Ok(()),
+    }.and_then(|_| fs::write(path, bytes).map_err(|e| format!("cannot write {}: {}", path, e)));
+    match result {
+        Ok(()) => log_info("OUTPUT", &format!("Wrote {} bytes to {}", bytes.len(), path)),
+        Err(e) => {
+            log_error("OUTPUT", &e);
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Lays out the logo and info lines into `handle`.
+fn render_to(handle: &mut Vec<u8>, info: &Info, config: &Config) {
     let cs = ColorScheme::new(config);
-    let term_width = get_terminal_width();
+    let mut term_width = get_terminal_width(config.width);
     
-    let logo_lines = if let Some(ref os) = info.os {
-        get_logo(os)
+    // Image logo replaces the ASCII art with a blank column of the same footprint.
+    // It is nothing but escapes, so --strip-ansi falls back to the ASCII art
+    let image_logo = config.image_path.as_ref()
+        .filter(|_| !config.strip_ansi)
+        .and_then(|path| load_image_logo(path));
+    let logo_lines = if let Some(ref img) = image_logo {
+        vec![" ".repeat(img.cols); img.rows]
+    } else if let Some(ref distro) = config.ascii_distro {
+        get_logo(distro, config.logo_size)
     } else {
-        get_logo("unknown")
+        select_logo(info, config.logo_size)
     };
     
-    let logo_width = logo_lines.iter().map(|s| visible_len(s.trim_end())).max().unwrap_or(0);
-    let available_info_width = term_width.saturating_sub(logo_width + 2).max(60);
+    // Art lines carry uneven trailing blanks, so the column is sized (and every
+    // line padded) by the trimmed width; image placeholders are blank by design
+    let logo_width = match image_logo {
+        Some(ref img) => img.cols,
+        None => logo_lines.iter().map(|s| visible_len(s.trim_end())).max().unwrap_or(0),
+    };
+    if config.width.is_some() && term_width < logo_width + 2 {
+        log_warn("RENDER", &format!("Width {} is narrower than the logo ({} columns), clamping to {}",
+            term_width, logo_width, logo_width + 2));
+        term_width = logo_width + 2;
+    }
+    // Only the side-by-side layouts give up width to the logo
+    let beside_logo = if config.logo_position == LogoPosition::Top { 0 } else { logo_width + 2 };
+    // Whatever the logo leaves, so narrow terminals truncate instead of wrapping
+    let available_info_width = term_width.saturating_sub(beside_logo).max(1);
     let bar_width = (available_info_width.saturating_sub(40)).clamp(2, 25);
     
     let mut info_lines = Vec::with_capacity(30);
     
-    if let (Some(ref user), Some(ref host)) = (&info.user, &info.hostname) {
-        let separator = "─".repeat(user.len() + host.len() + 1);
-        info_lines.push(format!("{}{}{}@{}", cs.bold, cs.primary, user, host));
-        info_lines.push(format!("{}{}{}", cs.muted, separator, cs.reset));
-    }
-    
-    module!(info_lines, config.show_os, "OS", info.os, cs);
-    module!(info_lines, config.show_kernel, "Kernel", info.kernel, cs);
-    module!(info_lines, config.show_uptime, "Uptime", info.uptime, cs);
-    module!(info_lines, config.show_boot_time, "Boot", info.boot_time, cs);
-    
-    if config.show_failed_units {
-        if let Some(failed) = info.failed_units {
-            if failed > 0 {
-                info_lines.push(format!("{}Failed Units:{} {}", cs.warning, cs.reset, failed));
-            }
+    let title = header_title(info.user.as_deref(), info.hostname.as_deref());
+    if let Some(ref title) = title {
+        info_lines.push(format!("{}{}{}", cs.bold, cs.primary, title));
+        if !config.compact {
+            let separator = "─".repeat(title.chars().count());
+            info_lines.push(format!("{}{}{}", cs.muted, separator, cs.reset));
         }
     }
     
-    module!(info_lines, config.show_bootloader, "Bootloader", info.bootloader, cs);
-    module!(info_lines, config.show_packages, "Packages", info.packages, cs);
-    module!(info_lines, config.show_shell, "Shell", info.shell, cs);
-    module!(info_lines, config.show_de, "DE", info.de, cs);
-    module!(info_lines, config.show_wm, "WM", info.wm, cs);
-    module!(info_lines, config.show_init, "Init", info.init, cs);
-    module!(info_lines, config.show_terminal, "Terminal", info.terminal, cs);
-    module!(info_lines, config.show_processes, "Processes", info.processes.map(|x| x.to_string()), cs);
-    module!(info_lines, config.show_users, "Users", info.users.map(|x| x.to_string()), cs);
-    module!(info_lines, config.show_entropy, "Entropy", info.entropy, cs);
-    module!(info_lines, config.show_model, "Model", info.model, cs);
-    module!(info_lines, config.show_motherboard, "Mobo", info.motherboard, cs);
-    module!(info_lines, config.show_bios, "BIOS", info.bios, cs);
-
-    if config.show_cpu {
-        if let Some(ref cpu) = info.cpu {
-            let mut details = Vec::with_capacity(3);
-            if config.show_cpu_freq {
-                if let Some(ref f) = info.cpu_freq { details.push(f.clone()); }
+    let ctx = RenderCtx { info, config, cs: &cs, bar_width };
+    if config.group_sections {
+        // Sections in MODULE_GROUPS order, table order within each; empty sections get no
+        // rule, and --compact drops the rules like every other spacer line
+        let rule = (!config.compact).then(|| {
+            let width = title.as_ref().map_or(GROUP_RULE_WIDTH, |t| t.chars().count());
+            format!("{}{}{}", cs.muted, "╌".repeat(width), cs.reset)
+        });
+        let header_lines = info_lines.len();
+        for group in MODULE_GROUPS {
+            let mut section = Vec::new();
+            for module in MODULES.iter().filter(|m| m.group == group && (m.enabled)(config)) {
+                render_module(module, &ctx, &mut section);
             }
-            if config.show_cpu_cores {
-                if let Some((c, t)) = info.cpu_cores { details.push(format!("{}C/{}T", c, t)); }
+            if section.is_empty() {
+                continue;
             }
-            if config.show_cpu_cache {
-                if let Some(ref cache) = info.cpu_cache { details.push(format!("{} L3", cache)); }
+            if let Some(rule) = rule.as_ref().filter(|_| info_lines.len() > header_lines) {
+                info_lines.push(rule.clone());
             }
-            
-            let detail_str = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
-            info_lines.push(format!("{}CPU:{} {}{}", cs.primary, cs.reset, cpu, detail_str));
+            info_lines.extend(section);
+        }
+    } else {
+        for module in MODULES {
+            if (module.enabled)(config) {
+                render_module(module, &ctx, &mut info_lines);
+            }
+        }
+    }
+    
+    let info_lines: Vec<String> = info_lines.iter().map(|l| truncate_ansi(l, available_info_width)).collect();
+    // Right layout: the logo starts after the widest info line
+    let info_width = info_lines.iter().map(|l| visible_len(l)).max().unwrap_or(0);
+    
+    if let Some(ref img) = image_logo {
+        // Make room first so a scroll can't shift the image, then draw it and restore the cursor
+        let shift = if config.logo_position == LogoPosition::Right { format!("\x1b[{}C", info_width + 2) } else { String::new() };
+        write!(handle, "{}\x1b[{}A\x1b7{}{}\x1b8", "\n".repeat(img.rows), img.rows, shift, img.escape).unwrap_or(());
+    }
+    
+    if logo_lines.is_empty() {
+        for line in &info_lines {
+            writeln!(handle, "{}", line).unwrap_or(());
         }
+        return;
     }
     
-    if config.show_cpu_temp {
-        if let Some(ref temp) = info.cpu_temp {
-            info_lines.push(format!("{}CPU Temp:{} {}", cs.primary, cs.reset, temp));
+    if config.logo_position == LogoPosition::Top {
+        for line in &logo_lines {
+            writeln!(handle, "{}{}{}", cs.primary, line.trim_end(), cs.reset).unwrap_or(());
         }
+        writeln!(handle).unwrap_or(());
+        for line in &info_lines {
+            writeln!(handle, "{}", line).unwrap_or(());
+        }
+        return;
     }
     
-    if config.show_gpu {
-        if let Some(ref gpus) = info.gpu {
-            let temps = info.gpu_temps.as_ref();
-            for (i, gpu) in gpus.iter().enumerate() {
-                let mut details = Vec::with_capacity(2);
-                if let Some(temps_vec) = temps {
-                    if let Some(Some(ref temp)) = temps_vec.get(i) { details.push(temp.clone()); }
+    let max_lines = std::cmp::max(logo_lines.len(), info_lines.len());
+    for i in 0..max_lines {
+        // Past the end of the logo this is pure padding, keeping the info column aligned
+        let logo_content = logo_lines.get(i).map_or("", |l| l.trim_end());
+        let info_part = info_lines.get(i).map_or("", |l| l.as_str());
+        
+        if config.logo_position == LogoPosition::Right {
+            let padding = " ".repeat(info_width.saturating_sub(visible_len(info_part)));
+            let line = format!("{}{}  {}{}{}", info_part, padding, cs.primary, logo_content, cs.reset);
+            writeln!(handle, "{}", line.trim_end()).unwrap_or(());
+        } else {
+            let padding = " ".repeat(logo_width.saturating_sub(visible_len(logo_content)));
+            writeln!(handle, "{}{}{}{}  {}", cs.primary, logo_content, cs.reset, padding, info_part).unwrap_or(());
+        }
+    }
+}
+
+// ============================================================================
+// MODULE TABLE
+// ============================================================================
+
+/// A displayable module. `render_output()` walks `MODULES` in order, and
+/// `--list-modules` prints the same table, so the two can't drift apart.
+struct ModuleDef {
+    name: &'static str,
+    label: &'static str,
+    group: ModuleGroup,
+    enabled: fn(&Config) -> bool,
+}
+
+/// Sections for `--group`, in the order they are printed.
+#[derive(Clone, Copy, PartialEq)]
+enum ModuleGroup {
+    System,
+    Hardware,
+    Network,
+    Desktop,
+    Extra,
+}
+
+const MODULE_GROUPS: [ModuleGroup; 5] = [
+    ModuleGroup::System, ModuleGroup::Hardware, ModuleGroup::Network, ModuleGroup::Desktop, ModuleGroup::Extra,
+];
+
+const MODULES: &[ModuleDef] = &[
+    ModuleDef { name: "os", label: "OS", group: ModuleGroup::System, enabled: |c| c.show_os },
+    ModuleDef { name: "kernel", label: "Kernel", group: ModuleGroup::System, enabled: |c| c.show_kernel },
+    ModuleDef { name: "uptime", label: "Uptime", group: ModuleGroup::System, enabled: |c| c.show_uptime },
+    ModuleDef { name: "boot", label: "Boot", group: ModuleGroup::System, enabled: |c| c.show_boot_time },
+    ModuleDef { name: "boot_duration", label: "Boot Time (kernel/userspace)", group: ModuleGroup::System, enabled: |c| c.show_boot_duration },
+    ModuleDef { name: "failed", label: "Failed Units", group: ModuleGroup::System, enabled: |c| c.show_failed_units },
+    ModuleDef { name: "bootloader", label: "Bootloader", group: ModuleGroup::System, enabled: |c| c.show_bootloader },
+    ModuleDef { name: "packages", label: "Packages", group: ModuleGroup::System, enabled: |c| c.show_packages },
+    ModuleDef { name: "updates", label: "Updates", group: ModuleGroup::System, enabled: |c| c.show_updates },
+    ModuleDef { name: "shell", label: "Shell", group: ModuleGroup::Desktop, enabled: |c| c.show_shell },
+    ModuleDef { name: "de", label: "DE", group: ModuleGroup::Desktop, enabled: |c| c.show_de },
+    ModuleDef { name: "wm", label: "WM", group: ModuleGroup::Desktop, enabled: |c| c.show_wm },
+    ModuleDef { name: "init", label: "Init", group: ModuleGroup::System, enabled: |c| c.show_init },
+    ModuleDef { name: "wsl", label: "WSL", group: ModuleGroup::System, enabled: |c| c.wsl.is_some() },
+    ModuleDef { name: "container", label: "Container", group: ModuleGroup::System, enabled: |c| c.show_container },
+    ModuleDef { name: "terminal", label: "Terminal", group: ModuleGroup::Desktop, enabled: |c| c.show_terminal },
+    ModuleDef { name: "terminal_font", label: "Terminal Font", group: ModuleGroup::Desktop, enabled: |c| c.show_terminal_font },
+    ModuleDef { name: "term_size", label: "Terminal Size", group: ModuleGroup::Desktop, enabled: |c| c.show_term_size },
+    ModuleDef { name: "terminal_bg", label: "Terminal Background", group: ModuleGroup::Desktop, enabled: |c| c.detect_terminal_bg },
+    ModuleDef { name: "processes", label: "Processes", group: ModuleGroup::System, enabled: |c| c.show_processes },
+    ModuleDef { name: "users", label: "Users", group: ModuleGroup::System, enabled: |c| c.show_users },
+    ModuleDef { name: "entropy", label: "Entropy", group: ModuleGroup::System, enabled: |c| c.show_entropy },
+    ModuleDef { name: "model", label: "Model", group: ModuleGroup::Hardware, enabled: |c| c.show_model },
+    ModuleDef { name: "chassis", label: "Chassis", group: ModuleGroup::Hardware, enabled: |c| c.show_chassis },
+    ModuleDef { name: "host", label: "Host", group: ModuleGroup::Hardware, enabled: |c| c.show_virtualization },
+    ModuleDef { name: "motherboard", label: "Mobo", group: ModuleGroup::Hardware, enabled: |c| c.show_motherboard },
+    ModuleDef { name: "mobo_temp", label: "Mobo Temp", group: ModuleGroup::Hardware, enabled: |c| c.show_mobo_temp },
+    ModuleDef { name: "fan", label: "Fan", group: ModuleGroup::Hardware, enabled: |c| c.show_fan },
+    ModuleDef { name: "bios", label: "BIOS", group: ModuleGroup::Hardware, enabled: |c| c.show_bios },
+    ModuleDef { name: "cpu", label: "CPU", group: ModuleGroup::Hardware, enabled: |c| c.show_cpu },
+    ModuleDef { name: "cpu_features", label: "CPU Features", group: ModuleGroup::Hardware, enabled: |c| c.show_cpu_features },
+    ModuleDef { name: "cpu_temp", label: "CPU Temp", group: ModuleGroup::Hardware, enabled: |c| c.show_cpu_temp },
+    ModuleDef { name: "governor", label: "Governor", group: ModuleGroup::Hardware, enabled: |c| c.show_governor },
+    ModuleDef { name: "gpu", label: "GPU", group: ModuleGroup::Hardware, enabled: |c| c.show_gpu },
+    ModuleDef { name: "memory", label: "Memory", group: ModuleGroup::Hardware, enabled: |c| c.show_memory },
+    ModuleDef { name: "swap", label: "Swap", group: ModuleGroup::Hardware, enabled: |c| c.show_swap },
+    ModuleDef { name: "pressure", label: "Memory Pressure", group: ModuleGroup::Hardware, enabled: |c| c.show_memory_pressure },
+    ModuleDef { name: "disk", label: "Disk", group: ModuleGroup::Hardware, enabled: |c| c.show_partitions },
+    ModuleDef { name: "network", label: "Network", group: ModuleGroup::Network, enabled: |c| c.show_network },
+    ModuleDef { name: "gateway", label: "Gateway", group: ModuleGroup::Network, enabled: |c| c.show_gateway },
+    ModuleDef { name: "dns", label: "DNS", group: ModuleGroup::Network, enabled: |c| c.show_dns },
+    ModuleDef { name: "vpn", label: "VPN", group: ModuleGroup::Network, enabled: |c| c.show_vpn },
+    ModuleDef { name: "public_ip", label: "Public IP", group: ModuleGroup::Network, enabled: |c| c.show_public_ip },
+    ModuleDef { name: "display", label: "Display", group: ModuleGroup::Hardware, enabled: |c| c.show_display },
+    ModuleDef { name: "locale", label: "Locale", group: ModuleGroup::System, enabled: |c| c.show_locale },
+    ModuleDef { name: "audio", label: "Audio", group: ModuleGroup::Hardware, enabled: |c| c.show_audio },
+    ModuleDef { name: "theme", label: "Theme", group: ModuleGroup::Desktop, enabled: |c| c.show_theme },
+    ModuleDef { name: "icons", label: "Icons", group: ModuleGroup::Desktop, enabled: |c| c.show_icons },
+    ModuleDef { name: "font", label: "Font", group: ModuleGroup::Desktop, enabled: |c| c.show_font },
+    ModuleDef { name: "custom", label: "Custom", group: ModuleGroup::Extra, enabled: |c| !c.custom_modules.is_empty() },
+    ModuleDef { name: "battery", label: "Battery", group: ModuleGroup::Hardware, enabled: |c| c.show_battery },
+    ModuleDef { name: "power", label: "Power", group: ModuleGroup::Hardware, enabled: |c| c.show_power },
+    ModuleDef { name: "colors", label: "Colors", group: ModuleGroup::Extra, enabled: |c| c.show_colors && c.use_color && !c.compact },
+];
+
+/// `--list-modules`: canonical name, default state and the label as currently configured.
+fn print_module_list(config: &Config) {
+    let defaults = Config::default();
+    println!("{:<14}{:<9}LABEL", "MODULE", "DEFAULT");
+    for module in MODULES {
+        let state = if (module.enabled)(&defaults) { "on" } else { "off" };
+        println!("{:<14}{:<9}{}", module.name, state, module_label(config, module.name, module.label));
+    }
+}
+
+/// "user@host", or whichever half is known; the header is only dropped when both are missing.
+fn header_title(user: Option<&str>, host: Option<&str>) -> Option<String> {
+    match (user, host) {
+        (Some(user), Some(host)) => Some(format!("{}@{}", user, host)),
+        (Some(name), None) | (None, Some(name)) => Some(name.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Everything a module needs to render its lines.
+struct RenderCtx<'a> {
+    info: &'a Info,
+    config: &'a Config,
+    cs: &'a ColorScheme,
+    bar_width: usize,
+}
+
+fn render_module(module: &ModuleDef, ctx: &RenderCtx, info_lines: &mut Vec<String>) {
+    let (info, config, cs, bar_width) = (ctx.info, ctx.config, ctx.cs, ctx.bar_width);
+    let label = module_label(config, module.name, module.label);
+
+    match module.name {
+        "os" => {
+            let os = match config.os_format {
+                OsFormat::Full => info.os.clone(),
+                OsFormat::Short => info.os_short.clone().or_else(|| info.os.clone()),
+                OsFormat::Id => info.os_id.clone().or_else(|| info.os.clone()),
+            };
+            module!(info_lines, true, label, os, cs)
+        }
+        "kernel" => {
+            let arch = info.arch.as_ref().filter(|_| config.show_kernel_arch);
+            let kernel = info.kernel.as_ref().map(|k| match arch {
+                Some(a) => format!("{} ({})", k, a),
+                None => k.clone(),
+            }).map(|k| match info.kernel_installed {
+                Some(_) => format!("{} {}(reboot recommended){}", k, cs.warning, cs.reset),
+                None => k,
+            });
+            module!(info_lines, true, label, kernel, cs)
+        }
+        "uptime" => {
+            let uptime = info.uptime_seconds.map(|secs| format_uptime(secs, config.uptime_precision));
+            module!(info_lines, true, label, uptime, cs)
+        }
+        "boot" => module!(info_lines, true, label, info.boot_time, cs),
+        "boot_duration" => {
+            let duration = info.boot_duration.map(|(kernel, userspace)| format!("{:.1}s / {:.1}s", kernel, userspace));
+            module!(info_lines, true, label, duration, cs)
+        }
+        "failed" => {
+            if let Some(ref failed) = info.failed_units {
+                if !failed.is_empty() {
+                    let detail = if config.show_failed_detail {
+                        let mut names = failed.iter().take(FAILED_UNITS_SHOWN).cloned().collect::<Vec<_>>().join(", ");
+                        if failed.len() > FAILED_UNITS_SHOWN {
+                            names.push_str(&format!(", +{} more", failed.len() - FAILED_UNITS_SHOWN));
+                        }
+                        format!(" ({})", names)
+                    } else {
+                        String::new()
+                    };
+                    info_lines.push(format!("{}{}:{} {}{}", cs.warning, label, cs.reset, failed.len(), detail));
                 }
-                if config.show_gpu_vram {
-                    if let Some(ref vram_vec) = info.gpu_vram {
-                        if let Some(vram) = vram_vec.get(i) { details.push(vram.clone()); }
-                    }
+            }
+        }
+        "bootloader" => module!(info_lines, true, label, info.bootloader, cs),
+        "packages" => {
+            let packages = info.packages.as_ref().map(|counts| format_packages(counts, config.packages_format));
+            module!(info_lines, true, label, packages, cs)
+        }
+        "updates" => module!(info_lines, true, label, info.updates.map(|n| format!("{} available", n)), cs),
+        "shell" => module!(info_lines, true, label, info.shell, cs),
+        "de" => module!(info_lines, true, label, info.de, cs),
+        "wm" => module!(info_lines, true, label, info.wm, cs),
+        "init" => module!(info_lines, true, label, info.init, cs),
+        "wsl" => module!(info_lines, true, label, info.wsl, cs),
+        "container" => module!(info_lines, true, label, info.container, cs),
+        "terminal" => module!(info_lines, true, label, info.terminal, cs),
+        "terminal_font" => module!(info_lines, true, label, info.terminal_font, cs),
+        "term_size" => module!(info_lines, true, label, info.term_size, cs),
+        "terminal_bg" => module!(info_lines, true, label, info.terminal_bg, cs),
+        "processes" => {
+            let detail = info.process_states.as_ref().map(|s| {
+                let mut parts = vec![format!("{} running", s.running)];
+                if s.zombie > 0 { parts.push(format!("{} zombie", s.zombie)); }
+                if s.stopped > 0 { parts.push(format!("{} stopped", s.stopped)); }
+                format!(" ({})", parts.join(", "))
+            }).unwrap_or_default();
+            module!(info_lines, true, label, info.processes.map(|x| format!("{}{}", x, detail)), cs)
+        }
+        "users" => module!(info_lines, true, label, info.users.map(|x| x.to_string()), cs),
+        "entropy" => module!(info_lines, true, label, info.entropy, cs),
+        "model" => module!(info_lines, true, label, info.model, cs),
+        "chassis" => module!(info_lines, true, label, info.chassis, cs),
+        "host" => module!(info_lines, true, label, info.virtualization, cs),
+        "motherboard" => module!(info_lines, true, label, info.motherboard, cs),
+        "mobo_temp" => module!(info_lines, true, label, info.mobo_temp, cs),
+        "fan" => {
+            // a fan labelled as the CPU's wins, otherwise the fastest one
+            let fan = info.fans.as_ref().and_then(|fans| {
+                fans.iter().find(|(label, _)| label.to_lowercase().contains("cpu"))
+                    .or_else(|| fans.iter().max_by_key(|(_, rpm)| *rpm))
+            });
+            module!(info_lines, true, label, fan.map(|(_, rpm)| format!("{} RPM", rpm)), cs)
+        }
+        "bios" => module!(info_lines, true, label, info.bios, cs),
+        "cpu" => {
+            if let Some(ref cpu) = info.cpu {
+                let mut details = Vec::with_capacity(4);
+                if config.show_cpu_uarch {
+                    if let Some(ref u) = info.cpu_uarch { details.push(u.clone()); }
+                }
+                if config.show_cpu_freq {
+                    if let Some(ref f) = info.cpu_freq { details.push(f.clone()); }
+                }
+                if config.show_cpu_cores {
+                    if let Some((c, t)) = info.cpu_cores { details.push(format!("{}C/{}T", c, t)); }
                 }
+                if config.show_cpu_cache {
+                    if let Some(ref cache) = info.cpu_cache { details.push(format!("{} L3", cache)); }
+                }
+
                 let detail_str = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
-                info_lines.push(format!("{}GPU:{} {}{}", cs.primary, cs.reset, gpu, detail_str));
+                info_lines.push(format!("{}{}:{} {}{}", cs.primary, label, cs.reset, cpu, detail_str));
             }
         }
-    }
-    
-    if config.show_memory {
-        if let Some((used, total)) = info.memory {
-            let percent = ((used / total * 100.0) as u8).min(100);
-            let bar = create_bar(percent, &cs.secondary, &cs.muted, config.use_color, bar_width);
-            info_lines.push(format!("{}Memory:{} {:.1}GiB / {:.1}GiB {}",
-                cs.primary, cs.reset, used, total, bar));
+        "cpu_temp" => module!(info_lines, true, label, info.cpu_temp, cs),
+        "cpu_features" => module!(info_lines, true, label, info.cpu_features, cs),
+        "governor" => module!(info_lines, true, label, info.cpu_governor, cs),
+        "gpu" => {
+            if let Some(ref gpus) = info.gpu {
+                let temps = info.gpu_temps.as_ref();
+                for (i, gpu) in gpus.iter().enumerate() {
+                    let mut details = Vec::with_capacity(3);
+                    if info.gpu_active == Some(i) { details.push("active".to_string()); }
+                    if info.gpu_compositor == Some(i) { details.push("compositor".to_string()); }
+                    if let Some(temps_vec) = temps {
+                        if let Some(Some(ref temp)) = temps_vec.get(i) { details.push(temp.clone()); }
+                    }
+                    if config.show_gpu_vram {
+                        let used = info.gpu_vram_used.as_ref().and_then(|v| v.get(i).copied().flatten());
+                        if let Some((used, total)) = used {
+                            details.push(format!("VRAM: {:.1}/{:.1} GiB", used, total));
+                        } else if let Some(ref vram_vec) = info.gpu_vram {
+                            if let Some(vram) = vram_vec.get(i) { details.push(vram.clone()); }
+                        }
+                    }
+                    let detail_str = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
+                    info_lines.push(format!("{}{}:{} {}{}", cs.primary, label, cs.reset, gpu, detail_str));
+                }
+            }
         }
-    }
-    
-    if config.show_swap {
-        if let Some((used, total)) = info.swap {
-            if total > 0.0 {
-                let percent = ((used / total * 100.0) as u8).min(100);
-                let bar = create_bar(percent, &cs.warning, &cs.muted, config.use_color, bar_width);
-                info_lines.push(format!("{}Swap:{} {:.1}GiB / {:.1}GiB {}",
-                    cs.primary, cs.reset, used, total, bar));
+        "memory" => {
+            if let Some(mem) = info.memory {
+                let percent = ((mem.used / mem.total * 100.0) as u8).min(100);
+                let bar = create_bar(percent, bar_color_for(percent, &cs.secondary, config, cs), &cs.muted, config.use_color, bar_width, config.bar_style);
+                let detail = if config.show_memory_detail {
+                    format!(" ({} cached, {} buffers, {} shared)", format_size(mem.cached, mem.total, config.mem_unit),
+                        format_size(mem.buffers, mem.total, config.mem_unit), format_size(mem.shmem, mem.total, config.mem_unit))
+                } else {
+                    String::new()
+                };
+                info_lines.push(format!("{}{}:{} {}{} {}",
+                    cs.primary, label, cs.reset, format_usage(mem.used, mem.total, config.mem_unit), detail, bar));
             }
         }
-    }
-    
-    if config.show_partitions {
-        if let Some(ref parts) = info.partitions {
-            for (_, mount, used, total) in parts {
-                let percent = if *total > 0.0 { ((used / total * 100.0) as u8).min(100) } else { 0 };
-                let bar = create_bar(percent, &cs.secondary, &cs.muted, config.use_color, bar_width);
-                info_lines.push(format!("{}Disk ({}):{} {:.1}GiB / {:.1}GiB {}",
-                    cs.primary, mount, cs.reset, used, total, bar));
+        "swap" => {
+            if let Some((used, total)) = info.swap {
+                if total > 0.0 {
+                    let percent = ((used / total * 100.0) as u8).min(100);
+                    let bar = create_bar(percent, bar_color_for(percent, &cs.warning, config, cs), &cs.muted, config.use_color, bar_width, config.bar_style);
+                    let zram = if config.show_swap_detail { info.zram.as_ref() } else { None };
+                    let swap_label = if zram.is_some() { format!("{} (zram)", label) } else { label };
+                    let ratio = zram.and_then(|z| z.ratio).map(|r| format!(" ({:.1}x)", r)).unwrap_or_default();
+                    info_lines.push(format!("{}{}:{} {}{} {}",
+                        cs.primary, swap_label, cs.reset, format_usage(used, total, config.mem_unit), ratio, bar));
+                }
             }
         }
-    }
-    
-    if config.show_network {
-        if let Some(ref networks) = info.network {
-            for net in networks {
-                let mut parts = Vec::with_capacity(4);
-                parts.push(net.interface.clone());
-                if let Some(ref ip) = net.ipv4 { parts.push(ip.clone()); }
-                if let Some(p) = net.ping {
-                    let j = net.jitter.map(|j| format!(" | ±{:.1}ms", j)).unwrap_or_default();
-                    let l = net.packet_loss.map(|l| format!(" | {:.0}% loss", l)).unwrap_or_default();
-                    parts.push(format!("[{:.1}ms{}{}]", p, j, l));
-                }
-                if let (Some(rx), Some(tx)) = (net.rx_rate_mbs, net.tx_rate_mbs) {
-                    if rx > 0.01 || tx > 0.01 { parts.push(format!("↓{:.2}MB/s ↑{:.2}MB/s", rx, tx)); }
-                } else if let (Some(rx), Some(tx)) = (net.rx_bytes, net.tx_bytes) {
-                    parts.push(format!("↓{} ↑{}", format_bytes(rx), format_bytes(tx)));
-                }
-                info_lines.push(format!("{}Network:{} {}", cs.primary, cs.reset, parts.join(" ")));
-            }
-        }
-    }
-
-    module!(info_lines, config.show_public_ip, "Public IP", info.public_ip, cs);
-    
-    if config.show_display {
-        if let Some(ref disp) = info.display {
-            let res = if config.show_resolution { 
-                if let Some(ref r) = info.resolution { 
-                    format!(" @ {}", r) 
-                } else { 
-                    String::new() 
-                } 
-            } else { 
-                String::new() 
+        "pressure" => {
+            let resources = [(label, info.memory_pressure),
+                             (module_label(config, module.name, "CPU Pressure"), info.cpu_pressure),
+                             (module_label(config, module.name, "IO Pressure"), info.io_pressure)];
+            for (label, pct) in resources.iter() {
+                if let Some(pct) = pct {
+                    info_lines.push(format!("{}{}:{} {:.2}% (some avg10)", cs.primary, label, cs.reset, pct));
+                }
+            }
+        }
+        "disk" => {
+            if let Some(ref parts) = info.partitions {
+                for part in parts {
+                    let percent = if part.total > 0.0 { ((part.used / part.total * 100.0) as u8).min(100) } else { 0 };
+                    let bar = create_bar(percent, bar_color_for(percent, &cs.secondary, config, cs), &cs.muted, config.use_color, bar_width, config.bar_style);
+                    let mut flags = String::new();
+                    if config.show_disk_fstype { flags.push_str(&format!(" {}", part.fstype)); }
+                    if part.read_only { flags.push_str(&format!(" {}[ro]{}", cs.warning, cs.reset)); }
+                    let usage = format_usage(part.used * KIB_PER_GIB, part.total * KIB_PER_GIB, config.mem_unit);
+                    info_lines.push(format!("{}{} ({}):{} {}{} {}",
+                        cs.primary, label, part.mount, cs.reset, usage, flags, bar));
+                }
+            }
+        }
+        "network" => {
+            if let Some(ref networks) = info.network {
+                for net in networks {
+                    let mut parts = Vec::with_capacity(4);
+                    parts.push(net.interface.clone());
+                    if let Some(ref ip) = net.ipv4 { parts.push(ip.clone()); }
+                    if let Some(p) = net.ping {
+                        let j = net.jitter.map(|j| format!(" | ±{:.1}ms", j)).unwrap_or_default();
+                        let l = net.packet_loss.map(|l| format!(" | {:.0}% loss", l)).unwrap_or_default();
+                        parts.push(format!("[{:.1}ms{}{}]", p, j, l));
+                    }
+                    if let (Some(rx), Some(tx)) = (net.rx_rate_mbs, net.tx_rate_mbs) {
+                        if rx > 0.01 || tx > 0.01 { parts.push(format!("↓{:.2}MB/s ↑{:.2}MB/s", rx, tx)); }
+                    } else if let (Some(rx), Some(tx)) = (net.rx_bytes, net.tx_bytes) {
+                        parts.push(format!("↓{} ↑{}", format_bytes(rx), format_bytes(tx)));
+                    }
+                    info_lines.push(format!("{}{}:{} {}", cs.primary, label, cs.reset, parts.join(" ")));
+                }
+            }
+        }
+        "gateway" => module!(info_lines, true, label, info.gateway, cs),
+        "dns" => module!(info_lines, true, label, info.dns, cs),
+        "vpn" => module!(info_lines, true, label, info.vpn, cs),
+        "public_ip" => {
+            let ips: Vec<&str> = [&info.public_ipv4, &info.public_ipv6].iter().filter_map(|ip| ip.as_deref()).collect();
+            if !ips.is_empty() {
+                info_lines.push(format!("{}{}:{} {}", cs.primary, label, cs.reset, ips.join(" / ")));
+            }
+        }
+        "display" => {
+            if let Some(ref disp) = info.display {
+                let monitors = info.monitors.as_ref().filter(|_| config.show_resolution);
+                let res = match monitors {
+                    Some(m) => {
+                        // Only worth marking the primary when there is a choice
+                        let modes: Vec<String> = m.iter()
+                            .map(|mon| if mon.primary && m.len() > 1 { format!("{} (primary)", mon.mode()) } else { mon.mode() })
+                            .collect();
+                        format!(" @ {}", modes.join(", "))
+                    }
+                    None => String::new(),
+                };
+                info_lines.push(format!("{}{}:{} {}{}", cs.primary, label, cs.reset, disp, res));
+                if let Some(m) = monitors.filter(|m| m.len() > 1) {
+                    let count_label = module_label(config, module.name, "Displays");
+                    info_lines.push(format!("{}{}:{} {}", cs.primary, count_label, cs.reset, m.len()));
+                }
+            }
+        }
+        "locale" => module!(info_lines, true, label, info.locale, cs),
+        "audio" => module!(info_lines, true, label, info.audio, cs),
+        "theme" => module!(info_lines, true, label, info.theme, cs),
+        "icons" => module!(info_lines, true, label, info.icons, cs),
+        "font" => module!(info_lines, true, label, info.font, cs),
+        "custom" => {
+            for (_, label, output) in &info.custom {
+                let label = module_label(config, module.name, label);
+                info_lines.push(format!("{}{}:{} {}", cs.primary, label, cs.reset, output));
+            }
+        }
+        "power" => module!(info_lines, true, label, info.power_source, cs),
+        "battery" => {
+            if let Some(ref bat) = info.battery {
+                let capacity = bat.capacity;
+                let bar_color = if capacity > 50 { &cs.secondary } else if capacity > 20 { &cs.warning } else { &cs.error };
+                let bar = create_bar(capacity, bar_color, &cs.muted, config.use_color, bar_width, config.bar_style);
+                let power = bat.power.map(|w| format!(", {:+.1}W", w)).unwrap_or_default();
+                info_lines.push(format!("{}{}:{} {}% ({}{}) {}",
+                    cs.primary, label, cs.reset, capacity, bat.status, power, bar));
+            }
+        }
+        "colors" => {
+            info_lines.push(String::new());
+            let normal = [&cs.color0, &cs.color1, &cs.color2, &cs.color3, &cs.color4, &cs.color5, &cs.color6, &cs.color7];
+            let swatch = |colors: &[&String]| {
+                let mut row: String = colors.iter().map(|c| format!("{}███", c)).collect();
+                row.push_str(cs.reset);
+                row
             };
-            info_lines.push(format!("{}Display:{} {}{}", cs.primary, cs.reset, disp, res));
+            match config.palette {
+                8 => info_lines.push(swatch(&normal)),
+                16 => {
+                    info_lines.push(swatch(&normal));
+                    info_lines.push(swatch(&cs.bright.iter().collect::<Vec<_>>()));
+                }
+                _ => info_lines.push(swatch(&normal[1..7])),
+            }
         }
+        _ => log_warn("RENDER", &format!("No renderer for module '{}'", module.name)),
     }
+}
 
-    module!(info_lines, config.show_locale, "Locale", info.locale, cs);
-    module!(info_lines, config.show_theme, "Theme", info.theme, cs);
-    module!(info_lines, config.show_icons, "Icons", info.icons, cs);
-    module!(info_lines, config.show_font, "Font", info.font, cs);
-    
-    if config.show_battery {
-        if let Some((capacity, ref status)) = info.battery {
-            let bar_color = if capacity > 50 { &cs.secondary } else if capacity > 20 { &cs.warning } else { &cs.error };
-            let bar = create_bar(capacity, bar_color, &cs.muted, config.use_color, bar_width);
-            info_lines.push(format!("{}Battery:{} {}% ({}) {}",
-                cs.primary, cs.reset, capacity, status, bar));
-        }
+/// Default Nerd Font glyph per module, overridable from the `[glyphs]` config section.
+fn default_glyph(module: &str) -> Option<&'static str> {
+    Some(match module {
+        "os"          => "\u{f17c}",
+        "kernel"      => "\u{f013}",
+        "uptime"      => "\u{f017}",
+        "boot"        => "\u{f011}",
+        "boot_duration" => "\u{f0e4}",
+        "failed"      => "\u{f071}",
+        "bootloader"  => "\u{f0e7}",
+        "packages"    => "\u{f187}",
+        "updates"     => "\u{f0aa}",
+        "shell"       => "\u{f120}",
+        "de"          => "\u{f108}",
+        "wm"          => "\u{f2d0}",
+        "init"        => "\u{f085}",
+        "container"   => "\u{f308}",
+        "wsl"         => "\u{f17a}",
+        "terminal"    => "\u{f489}",
+        "terminal_font" => "\u{f031}",
+        "term_size"   => "\u{f065}",
+        "terminal_bg" => "\u{f042}",
+        "processes"   => "\u{f0ae}",
+        "users"       => "\u{f0c0}",
+        "entropy"     => "\u{f074}",
+        "model"       => "\u{f109}",
+        "chassis"     => "\u{f108}",
+        "host"        => "\u{f233}",
+        "motherboard" => "\u{f2db}",
+        "mobo_temp"   => "\u{f2c9}",
+        "fan"         => "\u{f863}",
+        "bios"        => "\u{f2db}",
+        "cpu"         => "\u{f4bc}",
+        "cpu_features" => "\u{f4bc}",
+        "governor"    => "\u{f0e7}",
+        "cpu_temp"    => "\u{f2c9}",
+        "gpu"         => "\u{f1b2}",
+        "memory"      => "\u{f035b}",
+        "swap"        => "\u{f021}",
+        "pressure"    => "\u{f0e4}",
+        "disk"        => "\u{f0a0}",
+        "network"     => "\u{f1eb}",
+        "gateway"     => "\u{f0e8}",
+        "dns"         => "\u{f0ac}",
+        "vpn"         => "\u{f023}",
+        "public_ip"   => "\u{f0ac}",
+        "display"     => "\u{f26c}",
+        "locale"      => "\u{f1ab}",
+        "audio"       => "\u{f028}",
+        "theme"       => "\u{f1fc}",
+        "icons"       => "\u{f03e}",
+        "font"        => "\u{f031}",
+        "custom"      => "\u{f120}",
+        "battery"     => "\u{f240}",
+        "power"       => "\u{f1e6}",
+        _ => return None,
+    })
+}
+
+/// Line label for `module`, prefixed with its glyph when glyph mode is on.
+fn module_label(config: &Config, module: &str, text: &str) -> String {
+    if !config.use_glyphs {
+        return text.to_string();
     }
-    
-    if config.show_colors && config.use_color {
-        info_lines.push(String::new());
-        info_lines.push(format!("{}███{}███{}███{}███{}███{}███{}",
-            cs.color1, cs.color2, cs.color3, cs.color4, cs.color5, cs.color6, cs.reset));
+    let glyph = config.glyph_overrides.get(module).map(|g| g.as_str()).or_else(|| default_glyph(module));
+    match glyph {
+        Some(g) if !g.is_empty() => format!("{} {}", g, text),
+        _ => text.to_string(),
     }
-    
-    use std::io::Write;
-    let stdout = std::io::stdout();
-    let mut handle = std::io::BufWriter::new(stdout.lock());
-    
-    let max_lines = std::cmp::max(logo_lines.len(), info_lines.len());
-    for i in 0..max_lines {
-        let (logo_content, logo_len) = if i < logo_lines.len() {
-            (logo_lines[i].as_str(), visible_len(&logo_lines[i]))
-        } else {
-            ("", 0)
-        };
-        
-        let padding = " ".repeat(logo_width.saturating_sub(logo_len));
-        let logo_part = format!("{}{}{}{}", cs.primary, logo_content, cs.reset, padding);
-        
-        let info_part = if i < info_lines.len() {
-            truncate_ansi(&info_lines[i], available_info_width)
-        } else {
-            String::new()
-        };
-        
-        writeln!(handle, "{}  {}", logo_part, info_part).unwrap_or(());
+}
+
+/// Usage bar color: green below `bar_warn_pct`, yellow up to `bar_crit_pct`, red past it.
+/// With both thresholds off the bar keeps its `normal` color.
+fn bar_color_for<'a>(percent: u8, normal: &'a str, config: &Config, cs: &'a ColorScheme) -> &'a str {
+    if config.bar_warn_pct.is_none() && config.bar_crit_pct.is_none() {
+        return normal;
+    }
+    if config.bar_crit_pct.is_some_and(|crit| percent >= crit) {
+        &cs.error
+    } else if config.bar_warn_pct.is_some_and(|warn| percent >= warn) {
+        &cs.warning
+    } else {
+        &cs.secondary
     }
 }
 
-fn create_bar(percent: u8, filled_color: &str, empty_color: &str, use_color: bool, width: usize) -> String {
-    let filled = ((percent as usize * width) / 100).min(width);
-    let empty = width.saturating_sub(filled);
+/// `width` is in cells (chars), so multi-byte glyphs don't skew the layout.
+fn create_bar(percent: u8, filled_color: &str, empty_color: &str, use_color: bool, width: usize, style: BarStyle) -> String {
+    let (filled_char, empty_char) = style.chars();
+    let percent = (percent as usize).min(100);
+    let mut filled_part: String;
+    let empty;
+
+    if style == BarStyle::Braille {
+        // 8 dots per cell: the last partially filled cell takes a ramp glyph
+        let dots = percent * width * 8 / 100;
+        let full = dots / 8;
+        filled_part = std::iter::repeat_n(filled_char, full).collect();
+        let partial = dots % 8;
+        if partial > 0 {
+            filled_part.push(BRAILLE_RAMP[partial - 1]);
+        }
+        empty = width.saturating_sub(filled_part.chars().count());
+    } else {
+        let filled = (percent * width / 100).min(width);
+        filled_part = std::iter::repeat_n(filled_char, filled).collect();
+        empty = width.saturating_sub(filled);
+    }
+    let empty_part: String = std::iter::repeat_n(empty_char, empty).collect();
     
     if use_color {
         format!("[{}{}{}{}{}]",
             filled_color,
-            FILLED_CHAR.to_string().repeat(filled),
+            filled_part,
             empty_color,
-            EMPTY_CHAR.to_string().repeat(empty),
+            empty_part,
             "\x1b[0m")
     } else {
-        format!("[{}{}]",
-            FILLED_CHAR.to_string().repeat(filled),
-            EMPTY_CHAR.to_string().repeat(empty))
+        format!("[{}{}]", filled_part, empty_part)
     }
 }
 
@@ -1536,26 +4715,149 @@ fn format_bytes(bytes: u64) -> String {
 // SYSTEM INFO GATHERING (OPTIMIZED)
 // ============================================================================
 
+/// $USER, then $LOGNAME, then the passwd entry for the effective uid
+/// (cron and some sudo setups don't export either variable).
 fn get_user() -> Option<String> {
-    std::env::var("USER").ok()
+    let from_env = |var: &str| env::var(var).ok().filter(|s| !s.is_empty());
+    from_env("USER")
+        .or_else(|| from_env("LOGNAME"))
+        .or_else(|| {
+            extern "C" {
+                fn geteuid() -> u32;
+                fn getpwuid(uid: u32) -> *const *const std::ffi::c_char;
+            }
+            // pw_name is the first field of struct passwd
+            unsafe {
+                let pw = getpwuid(geteuid());
+                if pw.is_null() || (*pw).is_null() {
+                    return None;
+                }
+                let name = std::ffi::CStr::from_ptr(*pw).to_string_lossy().into_owned();
+                if name.is_empty() { None } else { Some(name) }
+            }
+        })
 }
 
+/// /proc, then $HOSTNAME, then /etc/hostname, then gethostname(2);
+/// the first can be empty or unreadable inside containers.
 fn get_hostname() -> Option<String> {
-    fs::read_to_string("/proc/sys/kernel/hostname")
-        .ok()
-        .map(|s| s.trim().to_string())
+    let non_empty = |s: String| {
+        let s = s.trim().to_string();
+        if s.is_empty() { None } else { Some(s) }
+    };
+    fs::read_to_string("/proc/sys/kernel/hostname").ok().and_then(non_empty)
+        .or_else(|| env::var("HOSTNAME").ok().and_then(non_empty))
+        .or_else(|| fs::read_to_string("/etc/hostname").ok().and_then(non_empty))
+        .or_else(|| {
+            extern "C" { fn gethostname(name: *mut u8, len: usize) -> i32; }
+            let mut buf = [0u8; 256];
+            if unsafe { gethostname(buf.as_mut_ptr(), buf.len()) } != 0 {
+                return None;
+            }
+            let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+            non_empty(String::from_utf8_lossy(&buf[..len]).into_owned())
+        })
 }
 
 fn get_os() -> Option<String> {
-    let os_release = fs::read_to_string("/etc/os-release").ok()?;
-    
-    for line in os_release.lines() {
-        if line.starts_with("PRETTY_NAME=") {
-            return Some(line.split('=').nth(1)?.trim_matches('"').to_string());
+    os_pretty_name(&get_os_release()?)
+}
+
+/// PRETTY_NAME, falling back to NAME for minimal os-release files
+fn os_pretty_name(fields: &HashMap<String, String>) -> Option<String> {
+    fields.get("PRETTY_NAME").or_else(|| fields.get("NAME")).filter(|s| !s.is_empty()).cloned()
+}
+
+/// How the OS line is written; JSON always carries the full name and the ID.
+#[derive(Clone, Copy, PartialEq)]
+enum OsFormat {
+    /// "Ubuntu 24.04.1 LTS (Noble Numbat)"
+    Full,
+    /// "Ubuntu 24.04.1 LTS"
+    Short,
+    /// "ubuntu"
+    Id,
+}
+
+fn parse_os_format(value: &str) -> Result<OsFormat, String> {
+    match value.trim().to_lowercase().as_str() {
+        "full" => Ok(OsFormat::Full),
+        "short" => Ok(OsFormat::Short),
+        "id" => Ok(OsFormat::Id),
+        _ => Err(format!("unknown OS format '{}' (expected full, short or id)", value)),
+    }
+}
+
+/// The pretty name with "(...)" runs and the VERSION_CODENAME word removed,
+/// e.g. "Debian GNU/Linux 12 (bookworm)" -> "Debian GNU/Linux 12".
+fn os_short_name(fields: &HashMap<String, String>) -> Option<String> {
+    let pretty = os_pretty_name(fields)?;
+    let mut stripped = String::with_capacity(pretty.len());
+    let mut depth = 0;
+    for c in pretty.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
         }
     }
-    
-    None
+    let codename = fields.get("VERSION_CODENAME").map(|c| c.to_lowercase()).filter(|c| !c.is_empty());
+    let short = stripped.split_whitespace()
+        .filter(|word| codename.as_deref() != Some(word.to_lowercase().as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if short.is_empty() { None } else { Some(short) }
+}
+
+/// `/etc/os-release`, or `/usr/lib/os-release` when /etc has none.
+fn get_os_release() -> Option<HashMap<String, String>> {
+    let content = fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .ok()?;
+    Some(parse_os_release(&content))
+}
+
+/// os-release is a shell-compatible `KEY=value` file: values may be single or
+/// double quoted, use backslash escapes and contain `=` themselves.
+fn parse_os_release(content: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        if let Some((key, raw)) = line.split_once('=') {
+            let key = key.trim();
+            if !key.is_empty() {
+                fields.insert(key.to_string(), unquote_shell_value(raw.trim()));
+            }
+        }
+    }
+    fields
+}
+
+fn unquote_shell_value(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            // Inside single quotes everything is literal
+            (Some('\''), c) => out.push(c),
+            // Double quotes only escape these; elsewhere the backslash stays
+            (Some('"'), '\\') => match chars.next() {
+                Some(n @ ('"' | '\\' | '$' | '`')) => out.push(n),
+                Some(n) => { out.push('\\'); out.push(n); }
+                None => out.push('\\'),
+            },
+            (None, '\\') => if let Some(n) = chars.next() { out.push(n) },
+            // Unquoted whitespace ends the value (e.g. a trailing comment)
+            (None, c) if c.is_whitespace() => break,
+            (_, c) => out.push(c),
+        }
+    }
+    out
 }
 
 fn get_kernel() -> Option<String> {
@@ -1564,36 +4866,236 @@ fn get_kernel() -> Option<String> {
         .map(|s| s.trim().to_string())
 }
 
-fn get_uptime() -> Option<String> {
-    let uptime_str = fs::read_to_string("/proc/uptime").ok()?;
-    let seconds = uptime_str.split_whitespace().next()?.parse::<f64>().ok()?;
-    
-    let days = (seconds / 86400.0) as u64;
-    let hours = ((seconds % 86400.0) / 3600.0) as u64;
-    let mins = ((seconds % 3600.0) / 60.0) as u64;
-    
-    if days > 0 {
-        Some(format!("{}d {}h {}m", days, hours, mins))
-    } else if hours > 0 {
-        Some(format!("{}h {}m", hours, mins))
-    } else {
-        Some(format!("{}m", mins))
+/// Newest installed kernel of the same flavor as `running`, when that isn't
+/// `running` itself: a reboot would pick up an update. Looks at module trees
+/// (/usr/lib/modules/<release>) and /boot/vmlinuz-<release>. A running kernel
+/// whose module tree was removed (Arch deletes it on upgrade) also counts.
+fn get_newer_installed_kernel(running: &str) -> Option<String> {
+    let mut installed: Vec<String> = Vec::new();
+    for dir in ["/usr/lib/modules", "/lib/modules"].iter() {
+        if let Ok(entries) = fs::read_dir(dir) {
+            installed.extend(entries.filter_map(Result::ok)
+                .filter(|e| e.path().join("modules.dep").exists() || e.path().join("vmlinuz").exists())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string)));
+        }
+    }
+    if let Ok(entries) = fs::read_dir("/boot") {
+        installed.extend(entries.filter_map(Result::ok)
+            .filter_map(|e| e.file_name().to_str()?.strip_prefix("vmlinuz-").map(str::to_string)));
+    }
+    installed.sort();
+    installed.dedup();
+
+    // Same flavor = same text once digits are dropped: "6.1.0-18-amd64" and
+    // "6.1.0-21-amd64" match, "6.6.63-1-lts" and "6.12.1-arch1-1" don't
+    let flavor = |release: &str| release.chars().filter(|c| !c.is_ascii_digit()).collect::<String>();
+    let numbers = |release: &str| release.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse::<u64>().ok())
+        .collect::<Vec<_>>();
+    let newest = installed.iter()
+        .filter(|r| flavor(r) == flavor(running))
+        .max_by_key(|r| numbers(r))?;
+
+    if installed.iter().any(|r| r == running) && numbers(newest) <= numbers(running) {
+        return None;
+    }
+    log_info("KERNEL", &format!("Running {}, newest installed {}", running, newest));
+    Some(newest.clone())
+}
+
+/// Machine architecture (`uname -m`) straight from the uname syscall.
+fn get_machine_arch() -> Option<String> {
+    // struct utsname: six fixed-size fields on Linux, five 256-byte ones on the BSDs/macOS
+    #[cfg(target_os = "linux")]
+    const UTS_LEN: usize = 65;
+    #[cfg(not(target_os = "linux"))]
+    const UTS_LEN: usize = 256;
+    extern "C" { fn uname(buf: *mut [[u8; UTS_LEN]; 6]) -> i32; }
+
+    let mut buf = [[0u8; UTS_LEN]; 6];
+    if unsafe { uname(&mut buf) } != 0 {
+        return None;
+    }
+    let machine = &buf[4];
+    let len = machine.iter().position(|&b| b == 0).unwrap_or(UTS_LEN);
+    let arch = String::from_utf8_lossy(&machine[..len]).into_owned();
+    if arch.is_empty() { None } else { Some(arch) }
+}
+
+fn get_uptime() -> Option<String> {
+    get_uptime_seconds().map(|secs| format_uptime(secs, UptimePrecision::Auto))
+}
+
+fn get_uptime_seconds() -> Option<u64> {
+    let uptime_str = fs::read_to_string("/proc/uptime").ok()?;
+    let seconds = uptime_str.split_whitespace().next()?.parse::<f64>().ok()?;
+    Some(seconds as u64)
+}
+
+/// How the Uptime line is written; JSON keeps the auto form next to `uptime_seconds`.
+#[derive(Clone, Copy, PartialEq)]
+enum UptimePrecision {
+    /// "3d 4h 12m"
+    Auto,
+    /// "3d 4h 12m 7s"
+    Seconds,
+    /// "3d"
+    Compact,
+}
+
+fn parse_uptime_precision(value: &str) -> Result<UptimePrecision, String> {
+    match value.trim().to_lowercase().as_str() {
+        "auto" => Ok(UptimePrecision::Auto),
+        "seconds" => Ok(UptimePrecision::Seconds),
+        "compact" => Ok(UptimePrecision::Compact),
+        _ => Err(format!("unknown uptime precision '{}' (expected auto, seconds or compact)", value)),
+    }
+}
+
+/// "3d 4h 12m", dropping leading zero units; `Seconds` appends the seconds and
+/// `Compact` keeps only the largest non-zero unit.
+fn format_uptime(seconds: u64, precision: UptimePrecision) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let mins = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+
+    match precision {
+        UptimePrecision::Auto => {}
+        UptimePrecision::Seconds => {
+            let auto = if seconds < 60 { String::new() } else { format_uptime(seconds, UptimePrecision::Auto) + " " };
+            return format!("{}{}s", auto, secs);
+        }
+        UptimePrecision::Compact => {
+            return match (days, hours, mins) {
+                (0, 0, 0) => format!("{}s", secs),
+                (0, 0, _) => format!("{}m", mins),
+                (0, _, _) => format!("{}h", hours),
+                _ => format!("{}d", days),
+            };
+        }
+    }
+    
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, mins)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+fn get_boot_time(utc: bool) -> Option<String> {
+    get_boot_timestamp().map(|ts| format_local_timestamp(ts, utc))
+}
+
+/// (kernel, userspace) seconds from systemd-analyze's
+/// "Startup finished in 2.1s (kernel) + 1.3s (initrd) + 8.4s (userspace) = 11.8s".
+/// The command fails on non-systemd systems and while boot is still running.
+fn get_boot_duration() -> Option<(f64, f64)> {
+    let output = run_cmd("systemd-analyze", &["time"])?;
+    let line = output.lines().find(|l| l.starts_with("Startup finished in "))?;
+    let stages = line.trim_start_matches("Startup finished in ").split(" = ").next()?;
+    let (mut kernel, mut userspace) = (None, None);
+    for stage in stages.split(" + ") {
+        let (time, name) = stage.rsplit_once(" (")?;
+        match name.trim_end_matches(')') {
+            "kernel" => kernel = parse_systemd_timespan(time),
+            "userspace" => userspace = parse_systemd_timespan(time),
+            _ => {}
+        }
+    }
+    Some((kernel?, userspace?))
+}
+
+/// Seconds in a systemd timespan like "1min 2.345s", "812ms" or "1h 3min".
+fn parse_systemd_timespan(span: &str) -> Option<f64> {
+    let mut total = 0.0;
+    for part in span.split_whitespace() {
+        let split = part.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let value: f64 = part[..split].parse().ok()?;
+        total += value * match &part[split..] {
+            "us" | "µs" => 1e-6,
+            "ms" => 1e-3,
+            "s" => 1.0,
+            "min" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return None,
+        };
     }
+    Some(total)
 }
 
-fn get_boot_time() -> Option<String> {
+/// Boot time as a Unix timestamp (`btime` in /proc/stat).
+fn get_boot_timestamp() -> Option<i64> {
     let stat = fs::read_to_string("/proc/stat").ok()?;
     
     for line in stat.lines() {
         if line.starts_with("btime ") {
-            let timestamp = line.split_whitespace().nth(1)?.parse::<i64>().ok()?;
-            return Some(format_unix_timestamp(timestamp));
+            return line.split_whitespace().nth(1)?.parse::<i64>().ok();
         }
     }
     
     None
 }
 
+/// Mirror of libc's `struct tm` (glibc/musl/BSD layout, including `tm_gmtoff`)
+#[repr(C)]
+struct Tm {
+    tm_sec: i32,
+    tm_min: i32,
+    tm_hour: i32,
+    tm_mday: i32,
+    tm_mon: i32,
+    tm_year: i32,
+    tm_wday: i32,
+    tm_yday: i32,
+    tm_isdst: i32,
+    tm_gmtoff: std::os::raw::c_long,
+    tm_zone: *const u8,
+}
+
+/// Seconds east of UTC at `timestamp`, from `localtime_r` (honours `$TZ` and
+/// /etc/localtime, including DST). Falls back to 0 = UTC.
+fn local_utc_offset(timestamp: i64) -> i64 {
+    extern "C" {
+        fn tzset();
+        fn localtime_r(time: *const i64, result: *mut Tm) -> *mut Tm;
+    }
+    let mut tm = Tm {
+        tm_sec: 0, tm_min: 0, tm_hour: 0, tm_mday: 0, tm_mon: 0, tm_year: 0,
+        tm_wday: 0, tm_yday: 0, tm_isdst: 0, tm_gmtoff: 0, tm_zone: std::ptr::null(),
+    };
+    unsafe {
+        tzset();
+        if localtime_r(&timestamp, &mut tm).is_null() {
+            log_warn("TIME", "localtime_r failed, showing UTC");
+            return 0;
+        }
+    }
+    tm.tm_gmtoff as i64
+}
+
+/// `YYYY-MM-DD HH:MM:SS` in the local timezone, or UTC when `utc` is set.
+fn format_local_timestamp(timestamp: i64, utc: bool) -> String {
+    let offset = if utc { 0 } else { local_utc_offset(timestamp) };
+    format_unix_timestamp(timestamp + offset)
+}
+
+/// RFC 3339 for machine consumers: `2024-06-01T08:15:00+02:00`, or `...Z` with `utc`.
+fn format_rfc3339(timestamp: i64, utc: bool) -> String {
+    let offset = if utc { 0 } else { local_utc_offset(timestamp) };
+    let zone = if utc {
+        "Z".to_string()
+    } else {
+        let sign = if offset < 0 { '-' } else { '+' };
+        format!("{}{:02}:{:02}", sign, offset.abs() / 3600, (offset.abs() % 3600) / 60)
+    };
+    format!("{}{}", format_unix_timestamp(timestamp + offset).replacen(' ', "T", 1), zone)
+}
+
+/// Formats `timestamp` as a UTC calendar date; apply any offset beforehand.
 fn format_unix_timestamp(timestamp: i64) -> String {
     const SECONDS_PER_DAY: i64 = 86400;
     const DAYS_PER_400_YEARS: i64 = 146097;
@@ -2220,90 +5722,476 @@ fn get_bootloader() -> Option<String> {
     }
 }
 
-fn get_packages() -> Option<String> {
-    let mut counts = Vec::with_capacity(5);
-    
-    if let Ok(entries) = fs::read_dir("/var/lib/pacman/local") {
-        let count = entries.filter_map(Result::ok)
-            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
-            .count();
-        if count > 0 {
-            counts.push(format!("{} (pacman)", count));
-        }
-    }
-    
-    if Path::new("/var/lib/dpkg/status").exists() {
-        if let Some(count) = run_cmd("dpkg", &["-l"]).map(|s| s.lines().filter(|l| l.starts_with("ii")).count()) {
-            counts.push(format!("{} (dpkg)", count));
-        }
-    }
-    
-    if Path::new("/var/lib/rpm").exists() {
-        if let Some(count) = run_cmd("rpm", &["-qa"]).map(|s| s.lines().count()) {
-            counts.push(format!("{} (rpm)", count));
-        }
-    }
+type PackageCounter = fn() -> Option<usize>;
+
+/// Package managers in display order, each with a cheap standalone counter.
+const PACKAGE_COUNTERS: &[(&str, PackageCounter)] = &[
+    ("pacman", count_pacman),
+    ("dpkg", count_dpkg),
+    ("rpm", count_rpm),
+    ("flatpak", count_flatpak),
+    ("snap", count_snap),
+    ("nix", count_nix),
+    ("xbps", count_xbps),
+    ("apk", count_apk),
+    ("portage", count_portage),
+    ("cargo", count_cargo),
+];
 
-    if let Ok(entries) = fs::read_dir("/var/lib/flatpak/app") {
-        let count = entries.filter_map(Result::ok).count();
-        if count > 0 { counts.push(format!("{} (flatpak)", count)); }
+/// Managers that install on top of the distro's own one; `native` skips them.
+const CROSS_DISTRO_MANAGERS: &[&str] = &["flatpak", "snap", "nix", "cargo"];
+
+/// How the Packages line is written; the per-manager counts are always kept.
+#[derive(Clone, Copy, PartialEq)]
+enum PackagesFormat {
+    /// "1234 (pacman), 56 (flatpak)"
+    Full,
+    /// "1290"
+    Total,
+    /// "1234 (pacman)"
+    Native,
+}
+
+fn parse_packages_format(value: &str) -> Result<PackagesFormat, String> {
+    match value.trim().to_lowercase().as_str() {
+        "full" => Ok(PackagesFormat::Full),
+        "total" => Ok(PackagesFormat::Total),
+        "native" => Ok(PackagesFormat::Native),
+        _ => Err(format!("unknown packages format '{}' (expected full, total or native)", value)),
     }
-    
-    if let Ok(entries) = fs::read_dir("/var/lib/snapd/snaps") {
-        let count = entries.filter_map(Result::ok)
-            .filter(|e| e.file_name().to_string_lossy().ends_with(".snap"))
-            .count();
-        if count > 0 { counts.push(format!("{} (snap)", count)); }
+}
+
+fn format_packages(counts: &[(String, usize)], format: PackagesFormat) -> String {
+    let entry = |(name, count): &(String, usize)| format!("{} ({})", count, name);
+    match format {
+        PackagesFormat::Full => counts.iter().map(entry).collect::<Vec<_>>().join(", "),
+        PackagesFormat::Total => counts.iter().map(|(_, count)| count).sum::<usize>().to_string(),
+        // Only cross-distro managers (e.g. NixOS reports just nix): show the first
+        PackagesFormat::Native => counts.iter()
+            .find(|(name, _)| !CROSS_DISTRO_MANAGERS.contains(&name.as_str()))
+            .or_else(|| counts.first())
+            .map(entry)
+            .unwrap_or_default(),
     }
+}
+
+fn get_packages() -> Option<Vec<(String, usize)>> {
+    count_packages(true)
+}
+
+/// Runs every counter (on its own thread when `parallel`) and keeps the
+/// non-empty results in `PACKAGE_COUNTERS` order, whatever order they finish in.
+fn count_packages(parallel: bool) -> Option<Vec<(String, usize)>> {
+    let results: Vec<Option<usize>> = if parallel {
+        thread::scope(|s| {
+            let handles: Vec<_> = PACKAGE_COUNTERS.iter()
+                .map(|&(_, counter)| s.spawn(counter))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap_or(None)).collect()
+        })
+    } else {
+        PACKAGE_COUNTERS.iter().map(|&(_, counter)| counter()).collect()
+    };
+
+    let counts: Vec<(String, usize)> = PACKAGE_COUNTERS.iter().zip(results)
+        .filter_map(|(&(name, _), count)| count.filter(|&c| c > 0).map(|c| (name.to_string(), c)))
+        .collect();
     
     if counts.is_empty() {
         None
     } else {
-        Some(counts.join(", "))
+        Some(counts)
     }
 }
 
+fn count_pacman() -> Option<usize> {
+    let entries = fs::read_dir("/var/lib/pacman/local").ok()?;
+    Some(entries.filter_map(Result::ok)
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .count())
+}
+
+fn count_dpkg() -> Option<usize> {
+    if !Path::new("/var/lib/dpkg/status").exists() { return None; }
+    run_cmd("dpkg", &["-l"]).map(|s| s.lines().filter(|l| l.starts_with("ii")).count())
+}
+
+fn count_rpm() -> Option<usize> {
+    if !Path::new("/var/lib/rpm").exists() { return None; }
+    run_cmd("rpm", &["-qa"]).map(|s| s.lines().count())
+}
+
+fn count_flatpak() -> Option<usize> {
+    let entries = fs::read_dir("/var/lib/flatpak/app").ok()?;
+    Some(entries.filter_map(Result::ok).count())
+}
+
+fn count_snap() -> Option<usize> {
+    let entries = fs::read_dir("/var/lib/snapd/snaps").ok()?;
+    Some(entries.filter_map(Result::ok)
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".snap"))
+        .count())
+}
+
+/// Nix: store paths, ignoring derivations and lock files
+fn count_nix() -> Option<usize> {
+    let entries = fs::read_dir("/nix/store").ok()?;
+    Some(entries.filter_map(Result::ok)
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            !name.ends_with(".drv") && !name.ends_with(".lock") && !name.starts_with('.')
+        })
+        .count())
+}
+
+/// xbps (Void): one .<pkg>-files.plist per installed package
+fn count_xbps() -> Option<usize> {
+    let entries = fs::read_dir("/var/db/xbps").ok()?;
+    Some(entries.filter_map(Result::ok)
+        .filter(|e| {
+            let name = e.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with('.') && name.ends_with("-files.plist")
+        })
+        .count())
+}
+
+/// apk (Alpine): one P: record per package in the installed db
+fn count_apk() -> Option<usize> {
+    let db = fs::read_to_string("/lib/apk/db/installed").ok()?;
+    Some(db.lines().filter(|l| l.starts_with("P:")).count())
+}
+
+/// portage (Gentoo): /var/db/pkg/<category>/<package>
+fn count_portage() -> Option<usize> {
+    let categories = fs::read_dir("/var/db/pkg").ok()?;
+    Some(categories.filter_map(Result::ok)
+        .filter_map(|c| fs::read_dir(c.path()).ok())
+        .map(|pkgs| pkgs.filter_map(Result::ok).filter(|e| e.path().is_dir()).count())
+        .sum())
+}
+
+/// cargo install: every entry in .crates2.json carries a "bins" list
+fn count_cargo() -> Option<usize> {
+    let home = env::var("HOME").ok()?;
+    let crates = fs::read_to_string(format!("{}/.cargo/.crates2.json", home)).ok()?;
+    Some(crates.matches("\"bins\":").count())
+}
+
 fn get_shell() -> Option<String> {
     std::env::var("SHELL")
         .ok()
         .map(|s| s.rsplit('/').next().unwrap_or(&s).to_string())
 }
 
+/// Shell name plus version when it can be found: the shell's own version
+/// variable first, then `<shell> --version` unless `allow_spawn` is off.
+fn get_shell_with_version(allow_spawn: bool) -> Option<String> {
+    let path = env::var("SHELL").ok()?;
+    let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+
+    // Version variables; most shells don't export theirs, nushell does
+    let env_var = match name.as_str() {
+        "bash" => "BASH_VERSION",
+        "zsh" => "ZSH_VERSION",
+        "fish" => "FISH_VERSION",
+        "nu" => "NU_VERSION",
+        _ => "",
+    };
+    let version = env::var(env_var).ok()
+        .and_then(|v| extract_version(&v))
+        .or_else(|| {
+            if !allow_spawn { return None; }
+            // elvish only understood -version before 0.19
+            let flag = if name == "elvish" { "-version" } else { "--version" };
+            run_cmd(&path, &[flag]).and_then(|out| out.lines().next().and_then(extract_version))
+        });
+
+    Some(match version {
+        Some(v) => format!("{} {}", name, v),
+        None => name,
+    })
+}
+
+/// First dotted number in `text`: "GNU bash, version 5.2.15(1)-release" -> "5.2.15"
+fn extract_version(text: &str) -> Option<String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .map(|word| word.trim_start_matches('v'))
+        .filter(|word| word.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|word| word.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect::<String>())
+        .find(|v| v.contains('.'))
+        .map(|v| v.trim_end_matches('.').to_string())
+}
+
 fn get_de() -> Option<String> {
     std::env::var("XDG_CURRENT_DESKTOP").ok()
         .or_else(|| std::env::var("DESKTOP_SESSION").ok())
 }
 
+/// Desktop name plus version for GNOME and KDE Plasma. Version files are read
+/// first; `gnome-shell --version` / `plasmashell --version` only run when
+/// `allow_spawn` is on. Other desktops come back unchanged.
+fn get_de_with_version(allow_spawn: bool) -> Option<String> {
+    let de = get_de()?;
+    // XDG_CURRENT_DESKTOP is a colon-separated list, e.g. "ubuntu:GNOME"
+    let is = |name: &str| de.split(':').any(|d| d.eq_ignore_ascii_case(name));
+
+    let (name, version) = if is("GNOME") {
+        let version = fs::read_to_string("/usr/share/gnome/gnome-version.xml").ok()
+            .and_then(|xml| {
+                let tag = |t: &str| xml.split(&format!("<{}>", t)).nth(1)
+                    .and_then(|rest| rest.split('<').next())
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty());
+                let platform = tag("platform")?;
+                Some(match tag("minor") {
+                    Some(minor) => format!("{}.{}", platform, minor),
+                    None => platform,
+                })
+            })
+            .or_else(|| {
+                if !allow_spawn { return None; }
+                run_cmd("gnome-shell", &["--version"]).and_then(|out| extract_version(&out))
+            });
+        ("GNOME", version)
+    } else if is("KDE") {
+        // Plasma session files carry the full version; KDE_SESSION_VERSION only the major
+        let version = ["/usr/share/wayland-sessions/plasma.desktop",
+                       "/usr/share/wayland-sessions/plasmawayland.desktop",
+                       "/usr/share/xsessions/plasma.desktop",
+                       "/usr/share/xsessions/plasmax11.desktop"]
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .find_map(|content| ini_value(&content, "X-KDE-PluginInfo-Version"))
+            .or_else(|| {
+                if !allow_spawn { return None; }
+                run_cmd("plasmashell", &["--version"]).and_then(|out| extract_version(&out))
+            })
+            .or_else(|| env::var("KDE_SESSION_VERSION").ok().filter(|v| !v.is_empty()));
+        ("KDE Plasma", version)
+    } else {
+        return Some(de);
+    };
+
+    Some(match version {
+        Some(v) => format!("{} {}", name, v),
+        None => de,
+    })
+}
+
+/// Known window manager / compositor binaries and their display names.
+const KNOWN_WMS: &[(&str, &str)] = &[
+    ("i3", "i3"),
+    ("bspwm", "bspwm"),
+    ("openbox", "Openbox"),
+    ("dwm", "dwm"),
+    ("awesome", "awesome"),
+    ("xmonad", "xmonad"),
+    ("herbstluftwm", "herbstluftwm"),
+    ("qtile", "Qtile"),
+    ("fluxbox", "Fluxbox"),
+    ("icewm", "IceWM"),
+    ("xfwm4", "Xfwm4"),
+    ("marco", "Marco"),
+    ("muffin", "Muffin"),
+    ("kwin_x11", "KWin"),
+    ("kwin_wayland", "KWin"),
+    ("gnome-shell", "Mutter"),
+    ("sway", "Sway"),
+    ("Hyprland", "Hyprland"),
+    ("river", "river"),
+    ("labwc", "labwc"),
+    ("wayfire", "Wayfire"),
+    ("niri", "niri"),
+];
+
 fn get_wm() -> Option<String> {
-    std::env::var("XDG_CURRENT_DESKTOP")
-        .ok()
-        .or_else(|| run_cmd("wmctrl", &["-m"]).and_then(|s| {
+    // Wayland compositors advertise themselves through their IPC sockets — zero spawns
+    if env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
+        return Some("Hyprland".to_string());
+    }
+    if env::var("SWAYSOCK").is_ok() {
+        return Some("Sway".to_string());
+    }
+
+    // EWMH: _NET_SUPPORTING_WM_CHECK on the root window points at the WM's own window
+    if env::var("DISPLAY").is_ok() {
+        let ewmh = run_cmd("xprop", &["-root", "-notype", "_NET_SUPPORTING_WM_CHECK"])
+            .and_then(|s| s.split_whitespace().last().map(|id| id.to_string()))
+            .filter(|id| id.starts_with("0x"))
+            .and_then(|id| run_cmd("xprop", &["-id", &id, "-notype", "_NET_WM_NAME"]))
+            .and_then(|s| s.split('=').nth(1).map(|v| v.trim().trim_matches('"').to_string()))
+            .filter(|name| !name.is_empty());
+        if ewmh.is_some() {
+            return ewmh;
+        }
+
+        let wmctrl = run_cmd("wmctrl", &["-m"]).and_then(|s| {
             s.lines()
                 .find(|l| l.starts_with("Name:"))
                 .map(|l| l.split(':').nth(1).unwrap_or("").trim().to_string())
-        }))
+        }).filter(|name| !name.is_empty() && name != "N/A");
+        if wmctrl.is_some() {
+            return wmctrl;
+        }
+    }
+
+    // Process scan for WMs that don't implement EWMH (dwm, plain Wayland compositors)
+    let names: Vec<&str> = KNOWN_WMS.iter().map(|&(comm, _)| comm).collect();
+    if let Some(comm) = find_running_process(&names) {
+        if let Some(&(_, pretty)) = KNOWN_WMS.iter().find(|&&(c, _)| c == comm) {
+            return Some(pretty.to_string());
+        }
+    }
+
+    // Last resort: session env vars
+    env::var("XDG_SESSION_DESKTOP").ok()
+        .or_else(|| env::var("DESKTOP_SESSION").ok())
+        .filter(|s| !s.is_empty())
+}
+
+/// Scans /proc/<pid>/comm and returns the first name from `names` that is running.
+fn find_running_process(names: &[&str]) -> Option<String> {
+    let entries = fs::read_dir("/proc").ok()?;
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let pid = file_name.to_string_lossy();
+        if !pid.chars().all(|c| c.is_ascii_digit()) { continue; }
+        if let Ok(comm) = fs::read_to_string(entry.path().join("comm")) {
+            let comm = comm.trim();
+            if names.contains(&comm) {
+                return Some(comm.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// PID 1's `comm` names and their display names. Plain "init" is resolved separately.
+const KNOWN_INITS: &[(&str, &str)] = &[
+    ("systemd", "systemd"),
+    ("openrc-init", "OpenRC"),
+    ("runit", "runit"),
+    ("runit-init", "runit"),
+    ("s6-svscan", "s6"),
+    ("dinit", "dinit"),
+    ("shepherd", "GNU Shepherd"),
+];
+
+/// Init system from PID 1's name, plus its version when `allow_spawn` is on.
+/// Directory checks are only a fallback when /proc/1 can't be read: they
+/// false-positive (e.g. /etc/runit left on a systemd box).
+fn get_init(allow_spawn: bool) -> Option<String> {
+    let name = match fs::read_to_string("/proc/1/comm") {
+        Ok(comm) => init_name(comm.trim())?,
+        Err(_) => {
+            log_debug("INIT", "/proc/1/comm unreadable, falling back to path checks");
+            if Path::new("/run/systemd/system").exists() { "systemd" }
+            else if Path::new("/sbin/openrc").exists() { "OpenRC" }
+            else if Path::new("/etc/runit").exists() { "runit" }
+            else { return None; }
+        }
+    };
+
+    let version = if !allow_spawn { None } else {
+        match name {
+            // "systemd 255 (255.4-1-arch)"
+            "systemd" => run_cmd("systemctl", &["--version"])
+                .and_then(|out| out.lines().next()?.split_whitespace().nth(1).map(|v| v.to_string())),
+            "OpenRC" => run_cmd("openrc", &["--version"]).and_then(|out| extract_version(&out)),
+            "dinit" => run_cmd("dinit", &["--version"]).and_then(|out| extract_version(&out)),
+            _ => None,
+        }
+    };
+    Some(match version {
+        Some(v) => format!("{} {}", name, v),
+        None => name.to_string(),
+    })
 }
 
-fn get_init() -> Option<String> {
-    if Path::new("/run/systemd/system").exists() {
-        Some("systemd".to_string())
-    } else if Path::new("/sbin/openrc").exists() {
-        Some("OpenRC".to_string())
-    } else if Path::new("/etc/runit").exists() {
-        Some("runit".to_string())
+/// Display name for PID 1's `comm`. A bare "init" is SysVinit's binary (which
+/// OpenRC normally runs under), BusyBox's applet, or a symlink to another init.
+fn init_name(comm: &str) -> Option<&'static str> {
+    if let Some(&(_, name)) = KNOWN_INITS.iter().find(|(c, _)| *c == comm) {
+        return Some(name);
+    }
+    if comm != "init" {
+        // Containers run the application itself as PID 1
+        log_debug("INIT", &format!("Unknown PID 1 '{}'", comm));
+        return None;
+    }
+    let target = fs::read_link("/proc/1/exe").or_else(|_| fs::canonicalize("/sbin/init")).ok();
+    let target = target.as_ref().and_then(|t| t.file_name()).and_then(|f| f.to_str()).unwrap_or("");
+    if let Some(&(_, name)) = KNOWN_INITS.iter().find(|(c, _)| *c == target) {
+        Some(name)
+    } else if target == "busybox" {
+        Some("BusyBox")
+    } else if Path::new("/sbin/openrc").exists() || Path::new("/usr/sbin/openrc").exists() {
+        Some("OpenRC")
     } else {
-        None
+        Some("SysVinit")
     }
 }
 
 fn get_terminal() -> Option<String> {
+    get_terminal_name().map(terminal_with_version)
+}
+
+fn terminal_with_version(name: String) -> String {
+    match terminal_version(&name) {
+        Some(version) => format!("{} {}", name, version),
+        None => name,
+    }
+}
+
+/// Version from the variables terminals export into their children. Pure env
+/// lookups: kitty and alacritty export none, so they stay bare.
+fn terminal_version(name: &str) -> Option<String> {
+    let lower = name.to_lowercase();
+    let var = |key: &str| env::var(key).ok().filter(|v| !v.is_empty());
+
+    if lower.contains("konsole") {
+        // KONSOLE_VERSION=230804 -> 23.08.4
+        let v = var("KONSOLE_VERSION")?;
+        if v.len() == 6 && v.chars().all(|c| c.is_ascii_digit()) {
+            return Some(format!("{}.{}.{}", &v[0..2], &v[2..4], v[4..6].trim_start_matches('0').max("0")));
+        }
+        return Some(v);
+    }
+    if lower.contains("xterm") {
+        // XTERM_VERSION=XTerm(390)
+        if let Some(v) = var("XTERM_VERSION") {
+            return Some(v.trim_start_matches("XTerm(").trim_end_matches(')').to_string());
+        }
+    }
+    if let (Some(program), Some(version)) = (var("TERM_PROGRAM"), var("TERM_PROGRAM_VERSION")) {
+        let program = program.to_lowercase();
+        if lower.contains(&program) || program.contains(&lower) {
+            return Some(version);
+        }
+    }
+    // VTE-based terminals (GNOME Terminal, Tilix, xfce4-terminal, ...) only expose the library version
+    const VTE_TERMINALS: &[&str] = &[
+        "gnome-terminal", "kgx", "ptyxis", "tilix", "terminator", "xfce4-terminal",
+        "mate-terminal", "guake", "lxterminal", "sakura", "io.elementary.t",
+    ];
+    if !VTE_TERMINALS.iter().any(|t| lower.starts_with(t)) {
+        return None;
+    }
+    if let Some(v) = var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()) {
+        return Some(format!("(VTE {}.{}.{})", v / 10000, (v / 100) % 100, v % 100));
+    }
+    None
+}
+
+fn get_terminal_name() -> Option<String> {
     if let Ok(status) = fs::read_to_string("/proc/self/status") {
         for line in status.lines() {
             if line.starts_with("PPid:") {
                 if let Some(ppid_str) = line.split_whitespace().nth(1) {
-                    let parent_comm_path = format!("/proc/{}/comm", ppid_str);
-                    if let Ok(parent_comm) = fs::read_to_string(&parent_comm_path) {
-                        let parent = parent_comm.trim();
+                    if let Some(parent_name) = process_name(ppid_str) {
+                        let parent = parent_name.as_str();
                         
                         if parent != "sh" && parent != "bash" && parent != "fish" && 
                            parent != "zsh" && parent != "rustfetch" && parent != "dash" {
@@ -2314,9 +6202,8 @@ fn get_terminal() -> Option<String> {
                             for pline in parent_status.lines() {
                                 if pline.starts_with("PPid:") {
                                     if let Some(gppid_str) = pline.split_whitespace().nth(1) {
-                                        let gparent_comm_path = format!("/proc/{}/comm", gppid_str);
-                                        if let Ok(gparent_comm) = fs::read_to_string(&gparent_comm_path) {
-                                            let gparent = gparent_comm.trim();
+                                        if let Some(gparent_name) = process_name(gppid_str) {
+                                            let gparent = gparent_name.as_str();
                                             if !gparent.is_empty() && gparent != "systemd" && 
                                                gparent != "init" && !gparent.starts_with("login") {
                                                 return Some(gparent.to_string());
@@ -2335,6 +6222,114 @@ fn get_terminal() -> Option<String> {
     std::env::var("TERM").ok()
 }
 
+/// Kernel comm length limit (TASK_COMM_LEN minus the NUL); longer names are cut.
+const COMM_MAX_LEN: usize = 15;
+
+/// Name of process `pid`, from `comm` but recovered from `cmdline` when truncated.
+fn process_name(pid: &str) -> Option<String> {
+    let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+    Some(full_process_name(comm.trim_end_matches('\n'), &cmdline))
+}
+
+/// `comm` is argv[0]'s basename cut to 15 bytes ("gnome-terminal-"); when it is,
+/// take the full basename from the NUL-separated cmdline instead. Helper
+/// processes that stand in for a terminal are reported under the terminal's name.
+fn full_process_name(comm: &str, cmdline: &[u8]) -> String {
+    let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+    let argv0 = String::from_utf8_lossy(argv0);
+    let base = argv0.rsplit('/').next().unwrap_or_default();
+    let name = if comm.len() >= COMM_MAX_LEN && base.len() > comm.len() && base.starts_with(comm) { base } else { comm };
+    match name {
+        "gnome-terminal-" | "gnome-terminal-server" => "gnome-terminal",
+        other => other,
+    }.to_string()
+}
+
+/// Font configured in the terminal we're running in. Each terminal keeps it in
+/// its own config format; unknown terminals (or default fonts) give None.
+fn get_terminal_font(terminal: &str, allow_spawn: bool) -> Option<String> {
+    let terminal = terminal.to_lowercase();
+    let config_dir = xdg_config_dir()?;
+    let read = |path: String| fs::read_to_string(path).ok();
+
+    let font = if terminal.starts_with("alacritty") {
+        read(format!("{}/alacritty/alacritty.toml", config_dir))
+            .and_then(|c| alacritty_font_family(&c))
+    } else if terminal.starts_with("kitty") {
+        // font_family JetBrains Mono  |  font_family family="JetBrains Mono"
+        read(format!("{}/kitty/kitty.conf", config_dir)).and_then(|c| {
+            let value = c.lines().map(str::trim).find_map(|l| l.strip_prefix("font_family"))?.trim();
+            match value.split_once("family=") {
+                Some((_, rest)) => rest.trim_start_matches('"').split('"').next().map(str::to_string),
+                None => Some(value.to_string()),
+            }
+        })
+    } else if terminal.starts_with("foot") {
+        // font=JetBrains Mono:size=11
+        read(format!("{}/foot/foot.ini", config_dir))
+            .and_then(|c| ini_value(&c, "font"))
+            .map(|v| v.split(':').next().unwrap_or("").to_string())
+    } else if terminal.starts_with("konsole") {
+        // konsolerc names the profile; the profile stores Font=Hack,10,-1,...
+        let profile = read(format!("{}/konsolerc", config_dir)).and_then(|c| ini_value(&c, "DefaultProfile"))?;
+        let data_dir = env::var("XDG_DATA_HOME").ok().filter(|s| !s.is_empty())
+            .or_else(|| env::var("HOME").ok().map(|h| format!("{}/.local/share", h)))?;
+        read(format!("{}/konsole/{}", data_dir, profile))
+            .and_then(|c| ini_value(&c, "Font"))
+            .map(|v| v.split(',').next().unwrap_or("").to_string())
+    } else if terminal.starts_with("xfce4-terminal") {
+        read(format!("{}/xfce4/terminal/terminalrc", config_dir)).and_then(|c| ini_value(&c, "FontName"))
+    } else if terminal.starts_with("gnome-terminal") && allow_spawn {
+        gnome_terminal_font()
+    } else {
+        None
+    };
+    font.filter(|f| !f.is_empty())
+}
+
+/// `family` under `[font.normal]`, or the inline `normal = { family = ... }` form.
+fn alacritty_font_family(content: &str) -> Option<String> {
+    let mut section = "";
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line.trim_matches(|c| c == '[' || c == ']').trim();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let (key, value) = (key.trim(), value.trim());
+        if section == "font.normal" && key == "family" {
+            return Some(value.trim_matches('"').to_string());
+        }
+        if section == "font" && key == "normal" {
+            let (_, rest) = value.split_once("family")?;
+            return rest.trim_start_matches([' ', '=']).trim_start_matches('"').split('"').next().map(str::to_string);
+        }
+    }
+    None
+}
+
+/// First `key=value` in an INI-style file, ignoring sections.
+fn ini_value(content: &str, key: &str) -> Option<String> {
+    content.lines()
+        .filter_map(|l| l.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| v.trim().to_string())
+}
+
+/// Default gnome-terminal profile's font, or the system monospace font when the
+/// profile says to use it.
+fn gnome_terminal_font() -> Option<String> {
+    let uuid = run_cmd("gsettings", &["get", "org.gnome.Terminal.ProfilesList", "default"])?;
+    let schema = format!("org.gnome.Terminal.Legacy.Profile:/org/gnome/terminal/legacy/profiles:/:{}/", uuid.trim_matches('\''));
+    let font = if run_cmd("gsettings", &["get", &schema, "use-system-font"]).as_deref() == Some("false") {
+        run_cmd("gsettings", &["get", &schema, "font"])?
+    } else {
+        run_cmd("gsettings", &["get", "org.gnome.desktop.interface", "monospace-font-name"])?
+    };
+    Some(font.trim_matches('\'').to_string())
+}
+
 fn get_cpu_info_combined() -> CpuInfo {
     let mut info = CpuInfo {
         name: None,
@@ -2342,13 +6337,25 @@ fn get_cpu_info_combined() -> CpuInfo {
         cores: None,
         cache: None,
         freq: None,
+        vendor: None,
+        uarch: None,
     };
     
     if let Ok(cpuinfo) = fs::read_to_string("/proc/cpuinfo") {
         let mut physical_cores = HashMap::new();
         let mut current_physical_id = 0;
+        let (mut vendor_id, mut family, mut model) = (None, None, None);
         
         for line in cpuinfo.lines() {
+            // Only the first processor's identification matters
+            let value = || line.split(':').nth(1).map(str::trim);
+            if line.starts_with("vendor_id") && vendor_id.is_none() {
+                vendor_id = value().map(str::to_string);
+            } else if line.starts_with("cpu family") && family.is_none() {
+                family = value().and_then(|v| v.parse::<u32>().ok());
+            } else if line.starts_with("model") && !line.starts_with("model name") && model.is_none() {
+                model = value().and_then(|v| v.parse::<u32>().ok());
+            }
             if line.starts_with("processor") {
                 info.threads += 1;
             } else if line.starts_with("model name") && info.name.is_none() {
@@ -2381,6 +6388,15 @@ fn get_cpu_info_combined() -> CpuInfo {
         
         let total_cores: usize = physical_cores.values().sum();
         info.cores = if total_cores > 0 { Some(total_cores) } else { None };
+
+        info.vendor = vendor_id.as_deref().map(|v| match v {
+            "GenuineIntel" => "Intel".to_string(),
+            "AuthenticAMD" => "AMD".to_string(),
+            other => other.to_string(),
+        });
+        if let (Some(vendor), Some(family), Some(model)) = (vendor_id.as_deref(), family, model) {
+            info.uarch = cpu_uarch(vendor, family, model).map(str::to_string);
+        }
     }
     
     info.freq = fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/scaling_cur_freq")
@@ -2391,6 +6407,127 @@ fn get_cpu_info_combined() -> CpuInfo {
     info
 }
 
+/// Microarchitecture of recent x86 chips by (vendor_id, family, model). Only
+/// models that map to a single codename are listed; the rest give None.
+fn cpu_uarch(vendor_id: &str, family: u32, model: u32) -> Option<&'static str> {
+    match (vendor_id, family) {
+        ("GenuineIntel", 6) => Some(match model {
+            0x3C | 0x3F | 0x45 | 0x46 => "Haswell",
+            0x3D | 0x47 | 0x4F | 0x56 => "Broadwell",
+            0x4E | 0x5E => "Skylake",
+            0x6A | 0x6C | 0x7D | 0x7E => "Ice Lake",
+            0x8C | 0x8D => "Tiger Lake",
+            0x8F => "Sapphire Rapids",
+            0x96 => "Elkhart Lake",
+            0x97 | 0x9A => "Alder Lake",
+            0x9C => "Jasper Lake",
+            0xA5 | 0xA6 => "Comet Lake",
+            0xA7 => "Rocket Lake",
+            0xAA | 0xAC => "Meteor Lake",
+            0xAD | 0xAE => "Granite Rapids",
+            0xAF => "Sierra Forest",
+            0xB7 | 0xBA | 0xBF => "Raptor Lake",
+            0xBD => "Lunar Lake",
+            0xBE => "Alder Lake-N",
+            0xC5 | 0xC6 => "Arrow Lake",
+            0xCF => "Emerald Rapids",
+            _ => return None,
+        }),
+        ("AuthenticAMD", 0x17) => Some(match model {
+            0x01 | 0x11 | 0x20 => "Zen",
+            0x08 | 0x18 => "Zen+",
+            0x31 | 0x60 | 0x68 | 0x71 | 0x90 | 0xA0 => "Zen 2",
+            _ => return None,
+        }),
+        ("AuthenticAMD", 0x19) => Some(match model {
+            0x00..=0x0F | 0x20..=0x2F | 0x50..=0x5F => "Zen 3",
+            0x40..=0x4F => "Zen 3+",
+            0x10..=0x1F | 0x60..=0x7F | 0xA0..=0xAF => "Zen 4",
+            _ => return None,
+        }),
+        ("AuthenticAMD", 0x1A) => Some(match model {
+            0x00..=0x2F | 0x40..=0x4F | 0x60..=0x7F => "Zen 5",
+            _ => return None,
+        }),
+        _ => None,
+    }
+}
+
+/// Instruction set extensions worth showing, as (cpuinfo flag, display name).
+/// x86 lists them under `flags`, ARM under `Features`.
+const CPU_FEATURES_OF_INTEREST: &[(&str, &str)] = &[
+    ("sse4_2", "sse4_2"),
+    ("avx2", "avx2"),
+    ("avx512f", "avx512"),
+    ("aes", "aes"),
+    ("sha_ni", "sha_ni"),
+    ("asimd", "neon"),
+    ("sve", "sve"),
+    ("sve2", "sve2"),
+    ("sha2", "sha2"),
+];
+
+/// cpufreq governor plus the energy-performance preference (intel_pstate /
+/// amd-pstate), e.g. "powersave (balance_performance)". Read per policy, so
+/// cores set differently show "mixed (performance, powersave)".
+fn get_cpu_governor() -> Option<String> {
+    let mut policies: Vec<_> = fs::read_dir("/sys/devices/system/cpu/cpufreq").ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("policy")))
+        .collect();
+    policies.sort();
+
+    let distinct = |file: &str| {
+        let mut values: Vec<String> = policies.iter()
+            .filter_map(|p| read_file_trim(&p.join(file).to_string_lossy()))
+            .collect();
+        values.sort();
+        values.dedup();
+        values
+    };
+    let governors = distinct("scaling_governor");
+    let governor = match governors.len() {
+        0 => return None,
+        1 => governors[0].clone(),
+        _ => format!("mixed ({})", governors.join(", ")),
+    };
+    // Only meaningful when every policy agrees; "default" means the firmware's choice
+    match distinct("energy_performance_preference").as_slice() {
+        [epp] if governors.len() == 1 && epp != "default" => Some(format!("{} ({})", governor, epp)),
+        _ => Some(governor),
+    }
+}
+
+/// Curated subset of the first processor's flags; they're the same on every core.
+fn get_cpu_features() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    let flags: Vec<&str> = cpuinfo.lines()
+        .find(|l| l.starts_with("flags") || l.starts_with("Features"))?
+        .split_once(':')?.1
+        .split_whitespace()
+        .collect();
+    let found: Vec<&str> = CPU_FEATURES_OF_INTEREST.iter()
+        .filter(|(flag, _)| flags.contains(flag))
+        .map(|&(_, name)| name)
+        .collect();
+    if found.is_empty() { None } else { Some(found.join(" ")) }
+}
+
+/// A hwmon `tempN_input` file in millidegrees Celsius, or None when it is
+/// unreadable or outside the plausible range (disconnected sensors report 0
+/// or huge values).
+fn read_hwmon_temp(path: &Path) -> Option<i32> {
+    fs::read_to_string(path).ok()
+        .and_then(|t| t.trim().parse::<i32>().ok())
+        .filter(|t| (MIN_TEMP_MILLIDEGREES..=MAX_TEMP_MILLIDEGREES).contains(t))
+}
+
+/// Display form shared by every temperature line: whole degrees Celsius.
+fn format_temp(millidegrees: i32) -> String {
+    format!("{}°C", millidegrees / 1000)
+}
+
 fn get_cpu_temp() -> Option<String> {
     let hwmon_path = Path::new("/sys/class/hwmon");
     let entries = fs::read_dir(hwmon_path).ok()?;
@@ -2406,15 +6543,8 @@ fn get_cpu_temp() -> Option<String> {
                name.contains("cpu") || name.contains("zenpower") {
                 
                 for i in 1..=10 {
-                    let temp_file = path.join(format!("temp{}_input", i));
-                    if let Ok(temp_str) = fs::read_to_string(&temp_file) {
-                        if let Ok(temp_millidegrees) = temp_str.trim().parse::<i32>() {
-                            if temp_millidegrees >= MIN_TEMP_MILLIDEGREES && 
-                               temp_millidegrees <= MAX_TEMP_MILLIDEGREES {
-                                let temp_c = temp_millidegrees / 1000;
-                                return Some(format!("{}°C", temp_c));
-                            }
-                        }
+                    if let Some(millidegrees) = read_hwmon_temp(&path.join(format!("temp{}_input", i))) {
+                        return Some(format_temp(millidegrees));
                     }
                 }
             }
@@ -2424,15 +6554,132 @@ fn get_cpu_temp() -> Option<String> {
     None
 }
 
-/// Single `lspci -v` call. Parses GPU names AND per-GPU VRAM in one pass.
-fn get_gpu_combined() -> (Option<Vec<String>>, Option<Vec<String>>) {
-    let output = match run_cmd("lspci", &["-v"]) {
-        Some(o) => o,
-        None    => return (None, None),
-    };
+/// Every `fanN_input` under /sys/class/hwmon as (label, RPM). The label is `fanN_label`
+/// when the driver names the header, else "<driver> fanN". None without fan sensors.
+fn get_fan_speeds() -> Option<Vec<(String, u32)>> {
+    let mut hwmons: Vec<_> = fs::read_dir("/sys/class/hwmon").ok()?.flatten().map(|e| e.path()).collect();
+    hwmons.sort();
+
+    let mut fans = Vec::new();
+    for path in hwmons {
+        let name = fs::read_to_string(path.join("name")).map(|n| n.trim().to_string()).unwrap_or_default();
+        for i in 1..=10 {
+            let rpm = match fs::read_to_string(path.join(format!("fan{}_input", i))).ok()
+                .and_then(|v| v.trim().parse::<u32>().ok()) {
+                Some(rpm) => rpm,
+                None => continue,
+            };
+            let label = fs::read_to_string(path.join(format!("fan{}_label", i))).ok()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .unwrap_or_else(|| format!("{} fan{}", name, i));
+            fans.push((label, rpm));
+        }
+    }
 
+    if fans.is_empty() { None } else { Some(fans) }
+}
+
+/// Board / chipset temperature from Super I/O, PCH or ACPI thermal-zone hwmon drivers.
+/// Prefers a channel labelled as system/motherboard; VMs usually have none of these.
+fn get_mobo_temp() -> Option<String> {
+    const BOARD_DRIVERS: &[&str] = &["nct6", "it87", "it86", "w83", "f718", "asus", "gigabyte_wmi", "pch_", "acpitz"];
+    const BOARD_LABELS: &[&str] = &["systin", "system", "motherboard", "mb", "chipset", "pch"];
+
+    let mut fallback: Option<(usize, i32)> = None;
+    for entry in fs::read_dir("/sys/class/hwmon").ok()?.flatten() {
+        let path = entry.path();
+        let name = match fs::read_to_string(path.join("name")) {
+            Ok(n) => n.trim().to_lowercase(),
+            Err(_) => continue,
+        };
+        // Lower index = more trustworthy driver; acpitz is the last resort
+        let rank = match BOARD_DRIVERS.iter().position(|d| name.starts_with(d)) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        for i in 1..=10 {
+            let millidegrees = match read_hwmon_temp(&path.join(format!("temp{}_input", i))) {
+                Some(t) => t,
+                None => continue,
+            };
+            let label = fs::read_to_string(path.join(format!("temp{}_label", i)))
+                .map(|l| l.trim().to_lowercase())
+                .unwrap_or_default();
+            if BOARD_LABELS.iter().any(|l| label.contains(l)) {
+                return Some(format_temp(millidegrees));
+            }
+            if fallback.is_none_or(|(r, _)| rank < r) {
+                fallback = Some((rank, millidegrees));
+            }
+        }
+    }
+
+    fallback.map(|(_, t)| format_temp(t))
+}
+
+/// (GPU names, VRAM apertures, PCI addresses) from a single `lspci -v` call.
+/// Addresses ("0000:01:00.0") are index-aligned with the names, empty when unknown,
+/// for pairing with sysfs devices. Falls back to /sys/class/drm when lspci is
+/// missing or finds nothing; that path has names and addresses but no VRAM.
+fn get_gpu_combined() -> (Option<Vec<String>>, Option<Vec<String>>, Vec<String>) {
+    let (gpus, vrams, slots) = run_cmd("lspci", &["-v"])
+        .map(|output| parse_lspci_gpus(&output))
+        .unwrap_or_default();
+
+    if gpus.is_empty() {
+        log_debug("GPU", "lspci found no GPU, falling back to /sys/class/drm");
+        return match get_drm_gpus() {
+            Some((gpus, slots)) => (Some(gpus), None, slots),
+            None => (None, None, Vec::new()),
+        };
+    }
+
+    let vrams: Vec<String> = vrams.into_iter().filter(|s| !s.is_empty()).collect();
+    (
+        Some(gpus),
+        if vrams.is_empty() { None } else { Some(vrams) },
+        slots,
+    )
+}
+
+/// Full PCI address with the domain, as sysfs names devices: lspci's "01:00.0"
+/// and nvidia-smi's "00000000:01:00.0" both become "0000:01:00.0".
+fn normalize_pci_address(address: &str) -> String {
+    let address = address.trim().to_lowercase();
+    match address.split(':').count() {
+        2 => format!("0000:{}", address),
+        3 => {
+            let (domain, rest) = address.split_once(':').unwrap_or_default();
+            match u32::from_str_radix(domain, 16) {
+                Ok(domain) => format!("{:04x}:{}", domain, rest),
+                Err(_) => address.clone(),
+            }
+        }
+        _ => address,
+    }
+}
+
+/// PCI address of the device behind a sysfs `device` link (hwmon or drm card).
+fn sysfs_device_address(device_link: &Path) -> Option<String> {
+    fs::canonicalize(device_link).ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+}
+
+/// Vendor names that mark a display controller line as a real GPU.
+const GPU_VENDOR_KEYWORDS: &[&str] = &[
+    "intel", "amd", "radeon", "nvidia", "geforce", "matrox", "aspeed",
+    "vmware", "virtio", "red hat", "qxl", "cirrus", "innotek", "virtualbox",
+    "qualcomm", "adreno", "broadcom", "mali", "apple", "moore threads", "silicon motion",
+];
+
+/// Parses `lspci -v` into GPU names, their largest memory BAR (the VRAM aperture)
+/// and their PCI addresses. All three lists stay index-aligned; vram entries may be empty.
+fn parse_lspci_gpus(output: &str) -> (Vec<String>, Vec<String>, Vec<String>) {
     let mut gpus:  Vec<String> = Vec::with_capacity(2);
     let mut vrams: Vec<String> = Vec::with_capacity(2);
+    let mut slots: Vec<String> = Vec::with_capacity(2);
     let mut cur_vram: Option<String> = None;
     let mut in_gpu = false;
 
@@ -2445,10 +6692,12 @@ fn get_gpu_combined() -> (Option<Vec<String>>, Option<Vec<String>>) {
             if in_gpu { vrams.push(cur_vram.take().unwrap_or_default()); }
             in_gpu = false;
 
-            if lower.contains("bridge") || lower.contains("audio") || lower.contains("usb") { continue; }
-            if !((lower.contains("vga") || lower.contains("3d") ||
-                  (lower.contains("display") && !lower.contains("audio"))) &&
-                 lower.contains("controller")) { continue; }
+            // "00:02.0 VGA compatible controller: Intel ..." — match on the class only,
+            // so words like "audio" or "usb" in the description can't hide a GPU
+            let class = lower.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+            if !(class.starts_with("vga compatible controller:") ||
+                 class.starts_with("3d controller:") ||
+                 class.starts_with("display controller:")) { continue; }
 
             if let Some(pos) = line.find("controller:") {
                 let mut desc = line[pos + 11..].trim().to_string();
@@ -2459,8 +6708,13 @@ fn get_gpu_combined() -> (Option<Vec<String>>, Option<Vec<String>>) {
                            .replace("NVIDIA Corporation", "NVIDIA")
                            .replace("Corporation", "");
                 let desc = desc.trim().to_string();
-                if desc.len() > 10 && !desc.to_lowercase().contains("bridge") && !desc.starts_with("Device ") {
+                let desc_lower = desc.to_lowercase();
+                // "ati" only as a word ("ATI Technologies"), never inside e.g. "Corporation"
+                let is_gpu = GPU_VENDOR_KEYWORDS.iter().any(|k| desc_lower.contains(k))
+                    || desc_lower.split(|c: char| !c.is_ascii_alphanumeric()).any(|w| w == "ati");
+                if is_gpu && !desc.starts_with("Device ") {
                     gpus.push(desc);
+                    slots.push(normalize_pci_address(line.split(' ').next().unwrap_or_default()));
                     in_gpu = true;
                     cur_vram = None;
                 }
@@ -2481,145 +6735,390 @@ fn get_gpu_combined() -> (Option<Vec<String>>, Option<Vec<String>>) {
             }
         }
     }
-    if in_gpu { vrams.push(cur_vram.unwrap_or_default()); }
+    if in_gpu { vrams.push(cur_vram.unwrap_or_default()); }
+
+    (gpus, vrams, slots)
+}
+
+/// GPUs from /sys/class/drm/cardN: PCI vendor/device IDs mapped to names for
+/// common iGPUs, or the kernel driver name for SoC GPUs without PCI IDs.
+/// Also returns each card's device address, index-aligned with the names.
+fn get_drm_gpus() -> Option<(Vec<String>, Vec<String>)> {
+    let mut cards: Vec<_> = fs::read_dir("/sys/class/drm").ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|n| n.strip_prefix("card").is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())))
+        .collect();
+    cards.sort();
+
+    let mut gpus = Vec::with_capacity(cards.len());
+    let mut slots = Vec::with_capacity(cards.len());
+    for card in cards {
+        let dev = format!("/sys/class/drm/{}/device", card);
+        let id = |f: &str| read_file_trim(&format!("{}/{}", dev, f))
+            .and_then(|v| u16::from_str_radix(v.trim_start_matches("0x"), 16).ok());
+
+        let name = match (id("vendor"), id("device")) {
+            (Some(vendor), Some(device)) => drm_gpu_name(vendor, device),
+            _ => match read_file_trim(&format!("{}/uevent", dev))
+                .and_then(|u| u.lines().find_map(|l| l.strip_prefix("DRIVER=").map(str::to_string))) {
+                Some(driver) => driver,
+                None => continue,
+            },
+        };
+        gpus.push(name);
+        slots.push(sysfs_device_address(Path::new(&dev)).unwrap_or_default());
+    }
+
+    if gpus.is_empty() { None } else { Some((gpus, slots)) }
+}
+
+fn drm_gpu_name(vendor: u16, device: u16) -> String {
+    let vendor_name = match vendor {
+        0x8086 => "Intel",
+        0x1002 => "AMD",
+        0x10de => "NVIDIA",
+        0x1af4 => "Red Hat VirtIO GPU",
+        0x1b36 => "Red Hat QXL",
+        0x15ad => "VMware SVGA II",
+        0x80ee => "VirtualBox Graphics Adapter",
+        0x1234 => "QEMU Standard VGA",
+        0x102b => "Matrox",
+        0x1a03 => "ASPEED Graphics",
+        _ => return format!("GPU [{:04x}:{:04x}]", vendor, device),
+    };
+    let model = match (vendor, device) {
+        (0x8086, 0x3e9b) => "UHD Graphics 630",
+        (0x8086, 0x3ea0) | (0x8086, 0x5917) => "UHD Graphics 620",
+        (0x8086, 0x9a49) | (0x8086, 0x46a6) | (0x8086, 0xa7a0) => "Iris Xe Graphics",
+        (0x8086, 0x4680) | (0x8086, 0x4692) => "UHD Graphics 770",
+        (0x8086, 0x7d55) => "Arc Graphics (Meteor Lake)",
+        (0x1002, 0x15d8) | (0x1002, 0x15dd) => "Radeon Vega (Raven)",
+        (0x1002, 0x1636) | (0x1002, 0x1638) => "Radeon Vega (Renoir/Cezanne)",
+        (0x1002, 0x1681) => "Radeon 680M",
+        (0x1002, 0x15bf) => "Radeon 780M",
+        (0x1002, 0x164e) => "Radeon Graphics (Raphael)",
+        _ if matches!(vendor, 0x8086 | 0x1002 | 0x10de) => return format!("{} GPU [{:04x}]", vendor_name, device),
+        _ => return vendor_name.to_string(),
+    };
+    format!("{} {}", vendor_name, model)
+}
+
+/// On hybrid (Optimus/PRIME) systems, the index in `gpus` of the GPU doing the
+/// work: an explicit offload request in the environment, else the only card
+/// runtime PM reports as awake, else the one the firmware booted the display on.
+/// Cards are matched to `gpus` by PCI address (`slots`, index-aligned with `gpus`).
+fn get_active_gpu(gpus: &[String], slots: &[String]) -> Option<usize> {
+    if gpus.len() < 2 {
+        return None;
+    }
+    let var = |key: &str| env::var(key).ok().filter(|v| !v.is_empty());
+
+    // (vendor, device, PCI slot, boot_vga, runtime active)
+    let mut cards = Vec::new();
+    for entry in fs::read_dir("/sys/class/drm").ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.strip_prefix("card").is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())) {
+            continue;
+        }
+        let dev = entry.path().join("device");
+        let read = |f: &str| read_file_trim(&dev.join(f).to_string_lossy());
+        let id = |f: &str| read(f).and_then(|v| u16::from_str_radix(v.trim_start_matches("0x"), 16).ok());
+        let Some(vendor) = id("vendor") else { continue };
+        let slot = sysfs_device_address(&dev).unwrap_or_default();
+        cards.push((vendor, id("device").unwrap_or(0), slot,
+            read("boot_vga").as_deref() == Some("1"),
+            read("power/runtime_status").as_deref() == Some("active")));
+    }
+    cards.sort_by(|a, b| a.2.cmp(&b.2));
+    let index_of = |card: &(u16, u16, String, bool, bool)| slots.iter().position(|s| *s == card.2);
+
+    if var("__NV_PRIME_RENDER_OFFLOAD").as_deref() == Some("1")
+        || var("__GLX_VENDOR_LIBRARY_NAME").as_deref() == Some("nvidia") {
+        return cards.iter().find(|c| c.0 == 0x10de).and_then(index_of);
+    }
+
+    // DRI_PRIME=1 (any non-boot GPU), =0, =vendor:device or =pci-0000_01_00_0
+    if let Some(prime) = var("DRI_PRIME") {
+        let chosen = match prime.as_str() {
+            "0" => cards.iter().find(|c| c.3),
+            "1" => cards.iter().find(|c| !c.3),
+            p if p.starts_with("pci-") => {
+                let slot = p[4..].replacen('_', ":", 2).replacen('_', ".", 1);
+                cards.iter().find(|c| c.2 == slot)
+            }
+            p => p.split_once(':')
+                .and_then(|(v, d)| Some((u16::from_str_radix(v, 16).ok()?, u16::from_str_radix(d, 16).ok()?)))
+                .and_then(|(v, d)| cards.iter().find(|c| c.0 == v && c.1 == d)),
+        };
+        if let Some(card) = chosen {
+            return index_of(card);
+        }
+    }
+
+    let awake: Vec<_> = cards.iter().filter(|c| c.4).collect();
+    if awake.len() == 1 {
+        return index_of(awake[0]);
+    }
+    cards.iter().find(|c| c.3).and_then(index_of)
+}
+
+/// On Wayland with several GPUs, the index in `gpus` of the one the compositor
+/// renders on: the first device pinned via `$WLR_DRM_DEVICES`/`$KWIN_DRM_DEVICES`,
+/// else the only card with an enabled connector (the scanout device). Cards are
+/// matched to `gpus` by PCI address (`slots`, index-aligned with `gpus`).
+fn get_compositor_gpu(gpus: &[String], slots: &[String]) -> Option<usize> {
+    if gpus.len() < 2 || env::var_os("WAYLAND_DISPLAY").is_none() {
+        return None;
+    }
+    let index_of_card = |card: &str| {
+        let address = sysfs_device_address(Path::new(&format!("/sys/class/drm/{}/device", card)))?;
+        slots.iter().position(|s| *s == address)
+    };
+
+    for key in ["WLR_DRM_DEVICES", "KWIN_DRM_DEVICES"] {
+        let Ok(devices) = env::var(key) else { continue };
+        let Some(first) = devices.split(':').next().filter(|d| !d.is_empty()) else { continue };
+        // by-path symlinks resolve to /dev/dri/cardN
+        let card = fs::canonicalize(first).ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()));
+        if let Some(i) = card.and_then(|c| index_of_card(&c)) {
+            return Some(i);
+        }
+    }
 
-    let vrams: Vec<String> = vrams.into_iter().filter(|s| !s.is_empty()).collect();
-    (
-        if gpus.is_empty()  { None } else { Some(gpus) },
-        if vrams.is_empty() { None } else { Some(vrams) },
-    )
+    // connectors are named cardN-<type>-<n>, e.g. card1-eDP-1
+    let mut scanout: Vec<String> = Vec::new();
+    for entry in fs::read_dir("/sys/class/drm").ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some((card, _)) = name.split_once('-') else { continue };
+        if read_file_trim(&entry.path().join("enabled").to_string_lossy()).as_deref() == Some("enabled")
+            && !scanout.iter().any(|c| c == card) {
+            scanout.push(card.to_string());
+        }
+    }
+    match scanout.as_slice() {
+        [card] => index_of_card(card),
+        _ => None,
+    }
 }
 
-fn get_gpu_temp_with_gpus(gpus: Option<&Vec<String>>) -> Option<Vec<Option<String>>> {
+/// Per-GPU temperature, indexed like `gpus`. Each amdgpu / i915 hwmon sensor goes to the GPU
+/// at the PCI address its `device` link resolves to, nvidia-smi readings by their bus id.
+fn get_gpu_temp_with_gpus(gpus: Option<&Vec<String>>, slots: &[String]) -> Option<Vec<Option<String>>> {
     let gpus = gpus?;
     if gpus.is_empty() {
         return None;
     }
     
-    let gpu_count = gpus.len();
-    let mut gpu_temps: Vec<Option<String>> = vec![None; gpu_count];
-    
-    let has_intel = gpus.iter().any(|g| g.to_lowercase().contains("intel"));
-    let has_nvidia = gpus.iter().any(|g| g.to_lowercase().contains("nvidia"));
-    let has_amd = gpus.iter().any(|g| g.to_lowercase().contains("amd"));
-    
-    let hwmon_path = Path::new("/sys/class/hwmon");
+    let mut gpu_temps = read_gpu_hwmon_temps(Path::new("/sys/class/hwmon"), gpus, slots);
     
-    if let Ok(entries) = fs::read_dir(hwmon_path) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            
-            if let Ok(name) = fs::read_to_string(path.join("name")) {
-                let name = name.trim().to_lowercase();
-                
-                if (name.contains("i915") || name.contains("pch")) && has_intel {
-                    if let Ok(temp_str) = fs::read_to_string(path.join("temp1_input")) {
-                        if let Ok(temp_millidegrees) = temp_str.trim().parse::<i32>() {
-                            if temp_millidegrees >= MIN_TEMP_MILLIDEGREES && 
-                               temp_millidegrees <= MAX_TEMP_MILLIDEGREES {
-                                let idx = gpus.iter().position(|g| g.to_lowercase().contains("intel")).unwrap_or(0);
-                                gpu_temps[idx] = Some(format!("{}°C", temp_millidegrees / 1000));
-                            }
-                        }
-                    }
-                }
-                else if name.contains("amdgpu") && has_amd {
-                    if let Ok(temp_str) = fs::read_to_string(path.join("temp1_input")) {
-                        if let Ok(temp_millidegrees) = temp_str.trim().parse::<i32>() {
-                            if temp_millidegrees >= MIN_TEMP_MILLIDEGREES && 
-                               temp_millidegrees <= MAX_TEMP_MILLIDEGREES {
-                                let idx = gpus.iter().position(|g| g.to_lowercase().contains("amd")).unwrap_or(0);
-                                gpu_temps[idx] = Some(format!("{}°C", temp_millidegrees / 1000));
-                            }
-                        }
+    if gpus.iter().any(|g| g.to_lowercase().contains("nvidia")) {
+        if let Some(output) = run_cmd("nvidia-smi", &["--query-gpu=pci.bus_id,temperature.gpu", "--format=csv,noheader,nounits"]) {
+            for line in output.lines() {
+                let Some((bus_id, temp)) = line.split_once(',') else { continue };
+                let Some(slot) = slots.iter().position(|s| *s == normalize_pci_address(bus_id)) else { continue };
+                if let Ok(temp) = temp.trim().parse::<i32>() {
+                    if temp > 0 && temp < 150 {
+                        // nvidia-smi reports whole degrees
+                        gpu_temps[slot] = Some(format_temp(temp * 1000));
                     }
                 }
             }
         }
     }
     
-    if has_nvidia {
-        if let Some(output) = run_cmd("nvidia-smi", &["--query-gpu=temperature.gpu", "--format=csv,noheader,nounits"]) {
+    if gpu_temps.iter().any(|t| t.is_some()) {
+        Some(gpu_temps)
+    } else {
+        None
+    }
+}
+
+/// amdgpu / i915 hwmon readings under `hwmon_dir`, placed at the index of the GPU whose
+/// PCI address matches the sensor's device. pch has no GPU device behind it, so it is
+/// only used for the Intel GPU when there is exactly one and it has no reading of its own.
+fn read_gpu_hwmon_temps(hwmon_dir: &Path, gpus: &[String], slots: &[String]) -> Vec<Option<String>> {
+    let mut gpu_temps: Vec<Option<String>> = vec![None; gpus.len()];
+    let mut pch = None;
+    let Ok(entries) = fs::read_dir(hwmon_dir) else { return gpu_temps };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(name) = fs::read_to_string(path.join("name")) else { continue };
+        let name = name.trim().to_lowercase();
+        let Some(millidegrees) = read_hwmon_temp(&path.join("temp1_input")) else { continue };
+        if name.contains("pch") {
+            pch = Some(millidegrees);
+        } else if name.contains("i915") || name.contains("amdgpu") {
+            let slot = sysfs_device_address(&path.join("device"))
+                .and_then(|address| slots.iter().position(|s| *s == address));
+            if let Some(slot) = slot.filter(|&i| i < gpu_temps.len()) {
+                gpu_temps[slot] = Some(format_temp(millidegrees));
+            }
+        }
+    }
+
+    let intel: Vec<usize> = gpus.iter().enumerate()
+        .filter(|(_, g)| g.to_lowercase().contains("intel"))
+        .map(|(i, _)| i)
+        .collect();
+    if let (Some(millidegrees), [i]) = (pch, intel.as_slice()) {
+        gpu_temps[*i].get_or_insert_with(|| format_temp(millidegrees));
+    }
+    gpu_temps
+}
+
+/// Per-GPU VRAM (used, total) in GiB, indexed like `gpus`.
+/// AMD comes from amdgpu's sysfs counters; NVIDIA needs an nvidia-smi spawn (skipped in fast mode).
+/// Both are placed by PCI address, since card numbering doesn't follow lspci's order.
+fn get_gpu_vram_usage(gpus: Option<&Vec<String>>, slots: &[String], fast_mode: bool) -> Option<Vec<Option<(f64, f64)>>> {
+    let gpus = gpus?;
+    if gpus.is_empty() {
+        return None;
+    }
+    let mut usage = read_amdgpu_vram(Path::new("/sys/class/drm"), gpus, slots);
+
+    if gpus.iter().any(|g| g.to_lowercase().contains("nvidia")) && !fast_mode {
+        if let Some(output) = run_cmd("nvidia-smi", &["--query-gpu=pci.bus_id,memory.used,memory.total", "--format=csv,noheader,nounits"]) {
             for line in output.lines() {
-                if let Ok(temp) = line.trim().parse::<i32>() {
-                    if temp > 0 && temp < 150 {
-                        if let Some(idx) = gpus.iter().position(|g| g.to_lowercase().contains("nvidia")) {
-                            gpu_temps[idx] = Some(format!("{}°C", temp));
-                        }
-                        break;
+                let mut fields = line.split(',');
+                let Some(slot) = fields.next().and_then(|id| slots.iter().position(|s| *s == normalize_pci_address(id))) else { continue };
+                let mut numbers = fields.map(|f| f.trim().parse::<f64>().ok());
+                if let (Some(Some(used)), Some(Some(total))) = (numbers.next(), numbers.next()) {
+                    if total > 0.0 {
+                        // nvidia-smi reports MiB
+                        usage[slot] = Some((used / 1024.0, total / 1024.0));
                     }
                 }
             }
         }
     }
-    
-    if gpu_temps.iter().any(|t| t.is_some()) {
-        Some(gpu_temps)
+
+    if usage.iter().any(|u| u.is_some()) {
+        Some(usage)
     } else {
         None
     }
 }
 
+/// amdgpu's `mem_info_vram_*` counters for each `cardN` under `drm_dir`, placed at the
+/// index of the GPU whose PCI address matches the card's device.
+fn read_amdgpu_vram(drm_dir: &Path, gpus: &[String], slots: &[String]) -> Vec<Option<(f64, f64)>> {
+    const BYTES_TO_GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    let mut usage: Vec<Option<(f64, f64)>> = vec![None; gpus.len()];
+    let Ok(entries) = fs::read_dir(drm_dir) else { return usage };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+        let device = entry.path().join("device");
+        if fs::read_to_string(device.join("vendor")).map(|v| v.trim() != "0x1002").unwrap_or(true) {
+            continue;
+        }
+        let Some(slot) = sysfs_device_address(&device).and_then(|a| slots.iter().position(|s| *s == a)) else { continue };
+        let read_bytes = |f: &str| fs::read_to_string(device.join(f)).ok()
+            .and_then(|s| s.trim().parse::<u64>().ok());
+        if let (Some(used), Some(total)) = (read_bytes("mem_info_vram_used"), read_bytes("mem_info_vram_total")) {
+            if total > 0 && slot < usage.len() {
+                usage[slot] = Some((used as f64 / BYTES_TO_GIB, total as f64 / BYTES_TO_GIB));
+            }
+        }
+    }
+    usage
+}
+
+/// RAM figures in KiB, as /proc/meminfo reports them. `used` is MemTotal - MemAvailable; the rest are for `--memory-detail`.
+#[derive(Clone, Copy, Default)]
+struct MemoryInfo {
+    used: f64,
+    total: f64,
+    buffers: f64,
+    cached: f64,
+    shmem: f64,
+}
+
 /// Single read of /proc/meminfo. Returns (memory, swap).
-fn get_memory_and_swap() -> (Option<(f64, f64)>, Option<(f64, f64)>) {
+fn get_memory_and_swap() -> (Option<MemoryInfo>, Option<(f64, f64)>) {
     let meminfo = match fs::read_to_string("/proc/meminfo") {
         Ok(s) => s,
         Err(_) => return (None, None),
     };
     let (mut mt, mut ma, mut st, mut sf) = (0.0_f64, 0.0_f64, 0.0_f64, 0.0_f64);
-    let (mut a, mut b, mut c, mut d) = (false, false, false, false);
+    let (mut buffers, mut cached, mut shmem) = (0.0_f64, 0.0_f64, 0.0_f64);
+    let mut found = 0;
     for line in meminfo.lines() {
-        if a && b && c && d { break; } // all four found, stop scanning
-        if !a && line.starts_with("MemTotal:") {
-            if let Some(v) = line.split_whitespace().nth(1).and_then(|s| s.parse::<f64>().ok()) { mt = v / KB_TO_GIB; a = true; }
-        } else if !b && line.starts_with("MemAvailable:") {
-            if let Some(v) = line.split_whitespace().nth(1).and_then(|s| s.parse::<f64>().ok()) { ma = v / KB_TO_GIB; b = true; }
-        } else if !c && line.starts_with("SwapTotal:") {
-            if let Some(v) = line.split_whitespace().nth(1).and_then(|s| s.parse::<f64>().ok()) { st = v / KB_TO_GIB; c = true; }
-        } else if !d && line.starts_with("SwapFree:") {
-            if let Some(v) = line.split_whitespace().nth(1).and_then(|s| s.parse::<f64>().ok()) { sf = v / KB_TO_GIB; d = true; }
-        }
-    }
-    let mem  = if mt  > 0.0 { Some((mt  - ma, mt))  } else { None };
+        if found == 7 { break; } // everything found, stop scanning
+        let mut it = line.split_whitespace();
+        let slot = match it.next() {
+            Some("MemTotal:") => &mut mt,
+            Some("MemAvailable:") => &mut ma,
+            Some("Buffers:") => &mut buffers,
+            Some("Cached:") => &mut cached,
+            Some("Shmem:") => &mut shmem,
+            Some("SwapTotal:") => &mut st,
+            Some("SwapFree:") => &mut sf,
+            _ => continue,
+        };
+        if let Some(v) = it.next().and_then(|s| s.parse::<f64>().ok()) {
+            *slot = v;
+            found += 1;
+        }
+    }
+    let mem  = if mt  > 0.0 { Some(MemoryInfo { used: mt - ma, total: mt, buffers, cached, shmem }) } else { None };
     let swap = if st > 0.0 { Some((st - sf, st)) } else { None };
     (mem, swap)
 }
 
-/// Returns (display, resolution). At most one subprocess on x11 (xrandr) or wayland (wlr-randr).
-fn get_display_and_resolution() -> (Option<String>, Option<String>) {
+/// Detects zram swap devices via /proc/swaps and sums their mm_stat compression ratio.
+fn get_zram_swap() -> Option<ZramSwap> {
+    let swaps = fs::read_to_string("/proc/swaps").ok()?;
+    let devices: Vec<String> = swaps.lines().skip(1)
+        .filter_map(|l| l.split_whitespace().next())
+        .filter_map(|dev| dev.rsplit('/').next())
+        .filter(|name| name.starts_with("zram"))
+        .map(|name| name.to_string())
+        .collect();
+    if devices.is_empty() {
+        return None;
+    }
+
+    // mm_stat: orig_data_size compr_data_size mem_used_total ...
+    let (mut orig, mut compr) = (0u64, 0u64);
+    for dev in &devices {
+        if let Some(stat) = read_file_trim(&format!("/sys/block/{}/mm_stat", dev)) {
+            let fields: Vec<u64> = stat.split_whitespace().take(2).filter_map(|f| f.parse().ok()).collect();
+            if fields.len() == 2 {
+                orig += fields[0];
+                compr += fields[1];
+            }
+        }
+    }
+    let ratio = if compr > 0 { Some(orig as f64 / compr as f64) } else { None };
+    log_debug("SWAP", &format!("zram swap on {}, ratio {:?}", devices.join(", "), ratio));
+    Some(ZramSwap { devices, ratio })
+}
+
+/// Display server plus every connected monitor. xrandr (X11) or wlr-randr
+/// (wlroots compositors) give the current modes; DRM sysfs covers GNOME/KDE
+/// Wayland and missing tools with each connector's preferred mode.
+fn get_display_and_resolution() -> (Option<String>, Option<Vec<Monitor>>) {
     if let Ok(stype) = std::env::var("XDG_SESSION_TYPE") {
         if stype == "wayland" {
             let disp = match std::env::var("WAYLAND_DISPLAY") {
                 Ok(wd) => format!("Wayland ({})", wd),
                 Err(_) => "Wayland".to_string(),
             };
-            let res = run_cmd("wlr-randr", &[]).and_then(|out|
-                out.lines().find(|l| l.contains(" px, ") && l.contains(" Hz")).map(|l| l.trim().to_string())
-            );
-            return (Some(disp), res);
+            let monitors = run_cmd("wlr-randr", &[]).map(|out| parse_wlr_randr(&out))
+                .filter(|m| !m.is_empty())
+                .or_else(drm_monitors);
+            return (Some(disp), monitors);
         }
         if stype == "x11" {
-            // Single xrandr call serves both display and resolution
-            if let Some(out) = run_cmd("xrandr", &["--current"]) {
-                let mut res: Option<String> = None;
-                for line in out.lines() {
-                    if line.contains(" connected") && (line.contains(" primary") || !line.contains(" disconnected")) {
-                        for (i, p) in line.split_whitespace().enumerate() {
-                            if i > 0 && p.contains('x') && p.as_bytes().first().map_or(false, |b| b.is_ascii_digit()) {
-                                res = Some(p.to_string());
-                                break;
-                            }
-                        }
-                        break;
-                    }
-                }
-                let disp = match &res {
-                    Some(r) => format!("{} (X11)", r),
-                    None    => "X11".to_string(),
-                };
-                return (Some(disp), res);
-            }
-            return (Some("X11".to_string()), None);
+            let monitors = run_cmd("xrandr", &["--current"]).map(|out| parse_xrandr(&out))
+                .filter(|m| !m.is_empty())
+                .or_else(drm_monitors);
+            return (Some("X11".to_string()), monitors);
         }
     }
     // Fallback: env vars only, no resolution available
@@ -2628,6 +7127,99 @@ fn get_display_and_resolution() -> (Option<String>, Option<String>) {
     (None, None)
 }
 
+/// "WIDTHxHEIGHT" at the start of `text`, e.g. the "2560x1440" in "2560x1440+0+0".
+fn parse_mode(text: &str) -> Option<(u32, u32)> {
+    let (w, rest) = text.split_once('x')?;
+    let h: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some((w.parse().ok()?, h.parse().ok()?))
+}
+
+/// `xrandr --current`: "DP-1 connected primary 2560x1440+0+0 ..." per active
+/// output (connected ones without a geometry are switched off), followed by
+/// its mode lines where `*` marks the active refresh rate: "2560x1440  59.95 + 143.91*".
+fn parse_xrandr(out: &str) -> Vec<Monitor> {
+    let mut monitors: Vec<Monitor> = Vec::new();
+    let mut in_active_output = false;
+    for line in out.lines() {
+        if !line.starts_with(' ') {
+            in_active_output = false;
+            if !line.contains(" connected") {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let name = match words.next() {
+                Some(n) => n.to_string(),
+                None => continue,
+            };
+            if let Some((width, height)) = words.find_map(|w| if w.contains('+') { parse_mode(w) } else { None }) {
+                monitors.push(Monitor { name, width, height, refresh: None, primary: line.contains(" primary ") });
+                in_active_output = true;
+            }
+        } else if in_active_output {
+            let current = line.split_whitespace().skip(1).find(|rate| rate.contains('*'));
+            if let (Some(rate), Some(monitor)) = (current, monitors.last_mut()) {
+                monitor.refresh = monitor.refresh.or_else(|| rate.trim_end_matches(['*', '+']).parse().ok());
+            }
+        }
+    }
+    monitors
+}
+
+/// `wlr-randr`: an unindented header per output, then "Enabled:" and an
+/// indented mode list where the active one says "current".
+fn parse_wlr_randr(out: &str) -> Vec<Monitor> {
+    let mut monitors = Vec::new();
+    let mut name: Option<String> = None;
+    for line in out.lines() {
+        if !line.starts_with(' ') {
+            name = line.split_whitespace().next().map(str::to_string);
+        } else if line.trim() == "Enabled: no" {
+            name = None;
+        } else if line.contains(" px,") && line.contains("current") {
+            // "3840x2160 px, 59.996999 Hz (preferred, current)"
+            let refresh = line.split(',').nth(1)
+                .and_then(|r| r.split(" Hz").next())
+                .and_then(|r| r.trim().parse().ok());
+            if let (Some(n), Some((width, height))) = (name.take(), parse_mode(line.trim())) {
+                monitors.push(Monitor { name: n, width, height, refresh, primary: false });
+            }
+        }
+    }
+    monitors
+}
+
+/// Connected DRM connectors with their preferred (first listed) mode. No
+/// spawns, but it can't tell which mode is active or which output is primary.
+fn drm_monitors() -> Option<Vec<Monitor>> {
+    let mut connectors: Vec<_> = fs::read_dir("/sys/class/drm").ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| read_file_trim(&p.join("status").to_string_lossy()).as_deref() == Some("connected"))
+        .collect();
+    connectors.sort();
+    let monitors: Vec<Monitor> = connectors.iter().filter_map(|path| {
+        let modes = fs::read_to_string(path.join("modes")).ok()?;
+        let (width, height) = parse_mode(modes.lines().next()?)?;
+        // "card0-DP-1" -> "DP-1"
+        let file = path.file_name()?.to_str()?;
+        let name = file.split_once('-').map_or(file, |(_, n)| n).to_string();
+        Some(Monitor { name, width, height, refresh: None, primary: false })
+    }).collect();
+    if monitors.is_empty() { None } else { Some(monitors) }
+}
+
+/// PSI "some avg10" for `resource` (memory, cpu, io): the share of the last ten
+/// seconds in which at least one task stalled on it. None on kernels built
+/// without CONFIG_PSI or booted with psi=0.
+fn get_pressure(resource: &str) -> Option<f64> {
+    let content = fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?;
+    content.lines()
+        .find(|l| l.starts_with("some "))?
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix("avg10="))?
+        .parse().ok()
+}
+
 fn get_entropy() -> Option<String> {
     let avail = read_file_trim("/proc/sys/kernel/random/entropy_avail")?;
     let pool = read_file_trim("/proc/sys/kernel/random/poolsize").unwrap_or_else(|| "4096".to_string());
@@ -2663,39 +7255,226 @@ fn get_users_count() -> Option<usize> {
     Some(1)
 }
 
-fn get_failed_units() -> Option<usize> {
-    run_cmd("systemctl", &["list-units", "--failed", "--no-legend", "--no-pager"])
-        .map(|s| s.lines().filter(|l| !l.trim().is_empty()).count())
+/// Unit names from `systemctl --failed`; `--plain` drops the leading status bullet.
+fn get_failed_units() -> Option<Vec<String>> {
+    run_cmd("systemctl", &["list-units", "--failed", "--no-legend", "--no-pager", "--plain"])
+        .map(|s| s.lines().filter_map(|l| l.split_whitespace().next()).map(|u| u.to_string()).collect())
+}
+
+/// Container runtime we're running under, or None on the host. Marker files first,
+/// then PID 1's `container=` variable and cgroup path, and only then (when
+/// `allow_spawn`) systemd-detect-virt.
+fn get_container(allow_spawn: bool) -> Option<String> {
+    if Path::new("/run/.containerenv").exists() {
+        return Some("Podman".to_string());
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some("Docker".to_string());
+    }
+
+    // Set by most runtimes for PID 1; usually only readable as root
+    if let Ok(environ) = fs::read("/proc/1/environ") {
+        let value = environ.split(|&b| b == 0)
+            .filter_map(|var| var.strip_prefix(b"container="))
+            .map(|v| String::from_utf8_lossy(v).into_owned())
+            .next();
+        if let Some(v) = value.filter(|v| !v.is_empty()) {
+            return Some(container_display_name(&v));
+        }
+    }
+
+    // systemd inside a container records the manager it was told about
+    if let Some(v) = read_file_trim("/run/systemd/container").filter(|v| !v.is_empty()) {
+        return Some(container_display_name(&v));
+    }
+
+    // cgroup v1 (and some v2 setups) name the runtime in PID 1's cgroup path
+    if let Ok(cgroup) = fs::read_to_string("/proc/1/cgroup") {
+        const CGROUP_MARKERS: &[(&str, &str)] = &[
+            ("libpod", "podman"),
+            ("/docker", "docker"),
+            ("docker-", "docker"),
+            ("kubepods", "Kubernetes"),
+            ("/lxc", "lxc"),
+            ("lxc.payload", "lxc"),
+        ];
+        if let Some(&(_, id)) = CGROUP_MARKERS.iter().find(|(marker, _)| cgroup.contains(marker)) {
+            return Some(container_display_name(id));
+        }
+    }
+
+    if !allow_spawn {
+        return None;
+    }
+    run_cmd("systemd-detect-virt", &["-c"])
+        .filter(|v| !v.is_empty() && v != "none")
+        .map(|v| container_display_name(&v))
+}
+
+/// WSL major version from the environment: Microsoft's kernel release string
+/// ("...-microsoft-standard-WSL2" on WSL2, "...-Microsoft" on WSL1), else `$WSL_DISTRO_NAME`.
+fn detect_wsl() -> Option<u8> {
+    fs::read_to_string("/proc/version").ok()
+        .and_then(|v| wsl_version_from_proc(&v))
+        .or_else(|| env::var_os("WSL_DISTRO_NAME").map(|_| 2))
+}
+
+/// WSL2 kernels carry "microsoft-standard" (older ones "WSL2"); WSL1 only fakes a "Microsoft" release.
+fn wsl_version_from_proc(version: &str) -> Option<u8> {
+    let lower = version.to_lowercase();
+    if !lower.contains("microsoft") {
+        return None;
+    }
+    Some(if lower.contains("wsl2") || lower.contains("microsoft-standard") { 2 } else { 1 })
+}
+
+/// "Ubuntu (WSL2)": the distro as registered with Windows, else os-release NAME.
+fn wsl_description(version: u8) -> String {
+    let distro = env::var("WSL_DISTRO_NAME").ok()
+        .filter(|d| !d.is_empty())
+        .or_else(|| get_os_release().and_then(|f| f.get("NAME").cloned()));
+    match distro {
+        Some(d) => format!("{} (WSL{})", d, version),
+        None => format!("WSL{}", version),
+    }
+}
+
+fn container_display_name(id: &str) -> String {
+    match id {
+        "docker" => "Docker",
+        "podman" => "Podman",
+        "lxc" | "lxc-libvirt" => "LXC",
+        "systemd-nspawn" => "systemd-nspawn",
+        "openvz" => "OpenVZ",
+        "wsl" => "WSL",
+        "proot" => "proot",
+        "rkt" => "rkt",
+        other => return other.to_string(),
+    }.to_string()
 }
 
-fn get_partitions_impl() -> Option<Vec<(String, String, f64, f64)>> {
-    // Find device + fstype for "/" from /proc/mounts (zero spawns)
+/// A mounted filesystem; sizes in GiB.
+#[derive(Clone)]
+struct Partition {
+    device: String,
+    mount: String,
+    fstype: String,
+    read_only: bool,
+    used: f64,
+    total: f64,
+}
+
+/// The root filesystem, plus the Windows C: drive (/mnt/c) under WSL.
+fn get_partitions_impl(wsl: bool) -> Option<Vec<Partition>> {
+    // Find device, fstype and options for "/" from /proc/mounts (zero spawns)
     let mounts = fs::read_to_string("/proc/mounts").ok()?;
     let mut dev = "root";
     let mut fst = "unknown";
+    let mut read_only = false;
     for line in mounts.lines() {
-        let mut it = line.splitn(4, ' ');
+        let mut it = line.split(' ');
         let d = it.next().unwrap_or("");
         let mp = it.next().unwrap_or("");
         let f  = it.next().unwrap_or("");
-        if mp == "/" { dev = d; fst = f; break; }
+        let opts = it.next().unwrap_or("");
+        if mp == "/" {
+            dev = d;
+            fst = f;
+            read_only = opts.split(',').any(|o| o == "ro");
+            break;
+        }
     }
     let dev_short = dev.rsplit('/').next().unwrap_or(dev);
 
-    // statfs syscall — no external binary needed
+    // statfs undercounts free space on btrfs/zfs (compression, snapshots, shared pools)
+    let special = match fst {
+        "btrfs" => btrfs_usage(dev),
+        "zfs" => zfs_usage(dev),
+        _ => None,
+    };
+    let (used, total) = match special {
+        Some(usage) => usage,
+        None => statfs_usage("/")?,
+    };
+    let mut parts = vec![Partition {
+        device: dev_short.to_string(),
+        mount: "/".to_string(),
+        fstype: fst.to_string(),
+        read_only,
+        used,
+        total,
+    }];
+
+    // drvfs / 9p mount of the Windows system drive
+    if wsl {
+        let windows = mounts.lines()
+            .map(|l| l.split(' ').collect::<Vec<_>>())
+            .find(|cols| cols.len() > 3 && cols[1] == "/mnt/c");
+        if let Some(cols) = windows {
+            if let Some((used, total)) = statfs_usage("/mnt/c") {
+                parts.push(Partition {
+                    device: "C:".to_string(),
+                    mount: "/mnt/c".to_string(),
+                    fstype: cols[2].to_string(),
+                    read_only: cols[3].split(',').any(|o| o == "ro"),
+                    used,
+                    total,
+                });
+            }
+        }
+    }
+    Some(parts)
+}
+
+/// (used, total) GiB of the filesystem holding `path` via statfs(2) — no external binary needed.
+fn statfs_usage(path: &str) -> Option<(f64, f64)> {
     #[repr(C)]
     struct Statfs { f_type: i64, f_bsize: i64, f_blocks: u64, f_bfree: u64, f_bavail: u64,
                     f_files: u64, f_ffree: u64, f_fsid: [i64; 2], f_flag: i64, f_namelen: i64, _pad: [i64; 4] }
     extern "C" { fn statfs(path: *const u8, buf: *mut Statfs) -> i32; }
     let mut s = Statfs { f_type:0, f_bsize:0, f_blocks:0, f_bfree:0, f_bavail:0,
                          f_files:0, f_ffree:0, f_fsid:[0;2], f_flag:0, f_namelen:0, _pad:[0;4] };
-    if unsafe { statfs(b"/\0".as_ptr(), &mut s) } != 0 { return None; }
+    let c_path = format!("{}\0", path);
+    if unsafe { statfs(c_path.as_ptr(), &mut s) } != 0 { return None; }
 
     let bs    = s.f_bsize as f64;
-    let total = s.f_blocks as f64 * bs / (1024.0 * 1024.0 * 1024.0);
-    let avail = s.f_bavail as f64 * bs / (1024.0 * 1024.0 * 1024.0);
+    let total = s.f_blocks as f64 * bs / BYTES_PER_GIB;
+    let avail = s.f_bavail as f64 * bs / BYTES_PER_GIB;
     if total <= 0.0 { return None; }
-    Some(vec![(format!("{} - {}", dev_short, fst), "/".to_string(), total - avail, total)])
+    Some((total - avail, total))
+}
+
+/// Btrfs allocation from sysfs: space used by data, metadata and system chunks
+/// (on-disk, so RAID copies count) against the size of every member device.
+fn btrfs_usage(dev: &str) -> Option<(f64, f64)> {
+    // /dev/mapper/* names are symlinks to dm-N, which is what sysfs lists
+    let name = fs::canonicalize(dev).ok()?.file_name()?.to_string_lossy().into_owned();
+    let fs_dir = fs::read_dir("/sys/fs/btrfs").ok()?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.join("devices").join(&name).exists())?;
+    let read_num = |path: std::path::PathBuf| read_file_trim(&path.to_string_lossy()).and_then(|v| v.parse::<u64>().ok());
+
+    let mut used = 0u64;
+    for kind in ["data", "metadata", "system"] {
+        let dir = fs_dir.join("allocation").join(kind);
+        // disk_used (raw bytes) needs 5.x kernels; bytes_used is the logical figure
+        used += read_num(dir.join("disk_used")).or_else(|| read_num(dir.join("bytes_used")))?;
+    }
+    let total: u64 = fs::read_dir(fs_dir.join("devices")).ok()?
+        .flatten()
+        .filter_map(|d| read_num(d.path().join("size")))
+        .sum::<u64>() * 512;
+    if total == 0 { return None; }
+    Some((used as f64 / BYTES_PER_GIB, total as f64 / BYTES_PER_GIB))
+}
+
+/// ZFS dataset usage from `zfs list`; the mount source is the dataset name.
+fn zfs_usage(dataset: &str) -> Option<(f64, f64)> {
+    let out = run_cmd("zfs", &["list", "-Hp", "-o", "used,available", dataset])?;
+    let mut fields = out.split_whitespace().map(|v| v.parse::<u64>().ok());
+    let (used, avail) = (fields.next()??, fields.next()??);
+    Some((used as f64 / BYTES_PER_GIB, (used + avail) as f64 / BYTES_PER_GIB))
 }
 
 fn run_cmd(cmd: &str, args: &[&str]) -> Option<String> {
@@ -2734,6 +7513,42 @@ fn run_cmd(cmd: &str, args: &[&str]) -> Option<String> {
     }
 }
 
+/// Like `run_cmd`, but kills the command after `timeout` and hands back the exit
+/// code with stdout whatever it was, for tools that report through their exit status.
+fn run_cmd_timeout(cmd: &str, args: &[&str], timeout: std::time::Duration) -> Option<(Option<i32>, String)> {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    log_debug("COMMAND", &format!("Executing with {}s timeout: {} {}", timeout.as_secs(), cmd, args.join(" ")));
+    let mut child = match Command::new(cmd).args(args).stdout(Stdio::piped()).stderr(Stdio::null()).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            log_debug("COMMAND", &format!("Failed to execute {}: {}", cmd, e));
+            return None;
+        }
+    };
+    // drain stdout on another thread so a chatty command can't block on a full pipe
+    let mut stdout = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut out = String::new();
+        let _ = stdout.read_to_string(&mut out);
+        out
+    });
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Some((status.code(), reader.join().ok()?)),
+            Ok(None) if std::time::Instant::now() < deadline => thread::sleep(std::time::Duration::from_millis(50)),
+            _ => {
+                log_warn("COMMAND", &format!("{} {} timed out after {}s, killing it", cmd, args.join(" "), timeout.as_secs()));
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+}
+
 fn read_file_trim(path: &str) -> Option<String> {
     match fs::read_to_string(path) {
         Ok(content) => {
@@ -2748,33 +7563,355 @@ fn read_file_trim(path: &str) -> Option<String> {
     }
 }
 
-fn get_model() -> Option<String> {
-    let vendor = read_file_trim("/sys/class/dmi/id/sys_vendor").unwrap_or_default();
-    let product = read_file_trim("/sys/class/dmi/id/product_name").unwrap_or_default();
-    if vendor.is_empty() && product.is_empty() { return None; }
-    Some(format!("{} {}", vendor, product).trim().to_string())
-}
+fn get_model() -> Option<String> {
+    let vendor = read_file_trim("/sys/class/dmi/id/sys_vendor").unwrap_or_default();
+    let product = read_file_trim("/sys/class/dmi/id/product_name").unwrap_or_default();
+    if vendor.is_empty() && product.is_empty() { return None; }
+    Some(format!("{} {}", vendor, product).trim().to_string())
+}
+
+/// Form factor from the SMBIOS chassis type. "Other"/"Unknown" and codes we
+/// don't recognise give None rather than a meaningless label.
+fn get_chassis() -> Option<String> {
+    let code: u8 = read_file_trim("/sys/class/dmi/id/chassis_type")?.parse().ok()?;
+    let name = match code & 0x7f {
+        3 | 4 | 5 | 6 | 7 | 15 | 16 | 24 => "Desktop",
+        8 | 9 | 10 | 14 => "Laptop",
+        11 => "Handheld",
+        12 => "Docking Station",
+        13 => "All-in-One",
+        17 | 23 | 25 | 28 | 29 => "Server",
+        18..=22 | 26 | 27 => "Expansion Chassis",
+        30 => "Tablet",
+        31 => "Convertible",
+        32 => "Detachable",
+        33 => "IoT Gateway",
+        34 => "Embedded PC",
+        35 => "Mini PC",
+        36 => "Stick PC",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Hypervisor we're a guest of, or None on bare metal. DMI strings name most
+/// hypervisors; the cpuinfo `hypervisor` flag catches the rest, and only then
+/// is `systemd-detect-virt` asked for the name (when `allow_spawn`).
+fn get_virtualization(allow_spawn: bool) -> Option<String> {
+    let dmi = format!("{} {}",
+        read_file_trim("/sys/class/dmi/id/sys_vendor").unwrap_or_default(),
+        read_file_trim("/sys/class/dmi/id/product_name").unwrap_or_default()).to_lowercase();
+
+    const DMI_SIGNATURES: &[(&str, &str)] = &[
+        ("kvm", "KVM"),
+        ("qemu", "QEMU"),
+        ("vmware", "VMware"),
+        ("virtualbox", "VirtualBox"),
+        ("innotek", "VirtualBox"),
+        ("microsoft corporation virtual machine", "Hyper-V"),
+        ("xen", "Xen"),
+        ("parallels", "Parallels"),
+        ("bochs", "Bochs"),
+        ("amazon ec2", "Amazon EC2"),
+        ("google compute engine", "Google Compute Engine"),
+        ("openstack", "OpenStack"),
+    ];
+    if let Some(&(_, name)) = DMI_SIGNATURES.iter().find(|(sig, _)| dmi.contains(sig)) {
+        return Some(name.to_string());
+    }
+
+    let flagged = fs::read_to_string("/proc/cpuinfo")
+        .map(|c| c.lines()
+            .find(|l| l.starts_with("flags"))
+            .is_some_and(|l| l.split_whitespace().any(|f| f == "hypervisor")))
+        .unwrap_or(false);
+
+    if !flagged {
+        return None;
+    }
+    let detected = if allow_spawn { run_cmd("systemd-detect-virt", &["-v"]) } else { None };
+    if let Some(id) = detected.filter(|v| !v.is_empty() && v != "none") {
+        return Some(match id.as_str() {
+            "kvm" => "KVM".to_string(),
+            "qemu" => "QEMU".to_string(),
+            "vmware" => "VMware".to_string(),
+            "oracle" => "VirtualBox".to_string(),
+            "microsoft" => "Hyper-V".to_string(),
+            "xen" => "Xen".to_string(),
+            "parallels" => "Parallels".to_string(),
+            "amazon" => "Amazon EC2".to_string(),
+            "bochs" => "Bochs".to_string(),
+            _ => id,
+        });
+    }
+
+    Some("Unknown hypervisor".to_string())
+}
+
+fn get_motherboard() -> Option<String> {
+    read_file_trim("/sys/class/dmi/id/board_name")
+}
+
+fn get_bios() -> Option<String> {
+    read_file_trim("/sys/class/dmi/id/bios_version")
+}
+
+#[derive(Clone, Default)]
+struct ProcessStates {
+    total: usize,
+    running: usize,
+    sleeping: usize,
+    zombie: usize,
+    stopped: usize,
+}
+
+/// Tallies the state field of every `/proc/<pid>/stat`. Processes that exit
+/// mid-scan are simply skipped.
+fn get_process_states() -> Option<ProcessStates> {
+    let mut states = ProcessStates::default();
+    for entry in fs::read_dir("/proc").ok()?.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+        if !name.to_str().is_some_and(|s| s.chars().all(|c| c.is_ascii_digit())) { continue; }
+        let stat = match fs::read_to_string(entry.path().join("stat")) {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        // comm may contain spaces or parens, so the state is the first field after the last ')'
+        let state = match stat.rfind(')').and_then(|p| stat[p + 1..].trim_start().chars().next()) {
+            Some(c) => c,
+            None => continue,
+        };
+        states.total += 1;
+        match state {
+            'R' => states.running += 1,
+            'S' | 'D' | 'I' => states.sleeping += 1,
+            'Z' => states.zombie += 1,
+            'T' | 't' => states.stopped += 1,
+            _ => {}
+        }
+    }
+    if states.total == 0 { None } else { Some(states) }
+}
+
+/// Whether /proc is mounted and readable: memory, processes, uptime and most
+/// CPU details come from it on Linux.
+fn proc_accessible() -> bool {
+    fs::read_dir("/proc").is_ok() && fs::metadata("/proc/meminfo").is_ok() && fs::read_to_string("/proc/self/stat").is_ok()
+}
+
+fn get_processes() -> Option<usize> {
+    fs::read_dir("/proc").ok()?.filter_map(|e| e.ok()).filter(|e| {
+        e.file_name().to_str().map(|s| s.chars().all(|c| c.is_ascii_digit())).unwrap_or(false)
+    }).count().into()
+}
+
+fn get_locale() -> Option<String> {
+    env::var("LANG").ok()
+}
+
+/// Categories that can each override `$LANG`.
+const LOCALE_CATEGORIES: &[&str] = &[
+    "LC_CTYPE", "LC_NUMERIC", "LC_TIME", "LC_COLLATE", "LC_MONETARY", "LC_MESSAGES",
+    "LC_PAPER", "LC_NAME", "LC_ADDRESS", "LC_TELEPHONE", "LC_MEASUREMENT", "LC_IDENTIFICATION",
+];
+
+/// `--locale-detail`: the effective locale with whatever overrides `$LANG`.
+/// LC_ALL beats everything, then each LC_* for its category, then LANG (POSIX "C" when unset).
+fn get_locale_detail() -> Option<String> {
+    let var = |key: &str| env::var(key).ok().filter(|v| !v.is_empty());
+    let lang = var("LANG");
+    if let Some(all) = var("LC_ALL") {
+        return Some(match lang {
+            Some(lang) if lang != all => format!("{} (LC_ALL, overrides LANG={})", all, lang),
+            _ => format!("{} (LC_ALL)", all),
+        });
+    }
+    let base = lang.unwrap_or_else(|| "C".to_string());
+    let overrides: Vec<String> = LOCALE_CATEGORIES.iter()
+        .filter_map(|&key| var(key).filter(|v| *v != base).map(|v| format!("{}={}", key, v)))
+        .collect();
+    if overrides.is_empty() {
+        Some(base)
+    } else {
+        Some(format!("{} ({})", base, overrides.join(", ")))
+    }
+}
+
+/// `--updates`: pending updates from every manager that answers in time.
+/// checkupdates exits 2 and dnf exits 0 when there is nothing to do; dnf exits 100 with a list.
+fn get_pending_updates() -> Option<usize> {
+    let timeout = std::time::Duration::from_secs(UPDATES_TIMEOUT_SECS);
+    let mut total = None;
+    let mut add = |n: usize| *total.get_or_insert(0) += n;
+
+    match run_cmd_timeout("checkupdates", &[], timeout) {
+        Some((Some(0), out)) => add(out.lines().filter(|l| !l.trim().is_empty()).count()),
+        Some((Some(2), _)) => add(0),
+        _ => {}
+    }
+    if let Some((Some(0), out)) = run_cmd_timeout("apt", &["list", "--upgradable"], timeout) {
+        add(out.lines().filter(|l| l.contains("[upgradable from")).count());
+    }
+    match run_cmd_timeout("dnf", &["check-update", "-q"], timeout) {
+        Some((Some(0), _)) => add(0),
+        // "name.arch  version  repo"; an "Obsoleting Packages" section may follow
+        Some((Some(100), out)) => add(out.lines()
+            .take_while(|l| !l.starts_with("Obsoleting"))
+            .filter(|l| l.split_whitespace().count() == 3)
+            .count()),
+        _ => {}
+    }
+    total
+}
+
+/// Split a command line on whitespace, keeping single- or double-quoted runs together.
+fn split_command(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_arg = false;
+    for c in command.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => { quote = Some(c); in_arg = true; }
+            None if c.is_whitespace() => {
+                if in_arg { args.push(std::mem::take(&mut current)); in_arg = false; }
+            }
+            None => { current.push(c); in_arg = true; }
+        }
+    }
+    if in_arg { args.push(current); }
+    args
+}
+
+/// First line of a `[[custom]]` command's output; failures, empty output and
+/// commands still running after `CUSTOM_TIMEOUT_SECS` hide the line.
+fn run_custom_module(custom: &CustomModule) -> Option<String> {
+    let timeout = std::time::Duration::from_secs(CUSTOM_TIMEOUT_SECS);
+    let out = if custom.shell {
+        run_cmd_timeout("sh", &["-c", &custom.command], timeout)
+    } else {
+        let args = split_command(&custom.command);
+        let (program, rest) = args.split_first()?;
+        let rest: Vec<&str> = rest.iter().map(|a| a.as_str()).collect();
+        run_cmd_timeout(program, &rest, timeout)
+    };
+    let out = out.filter(|(code, _)| *code == Some(0)).map(|(_, stdout)| stdout);
+    match out.as_deref().and_then(|o| o.lines().next()).map(str::trim) {
+        Some(line) if !line.is_empty() => Some(line.to_string()),
+        _ => {
+            log_warn("CUSTOM", &format!("'{}' produced no output, hiding the line", custom.label));
+            None
+        }
+    }
+}
+
+/// Sound server: PipeWire, PulseAudio or JACK by their sockets / running
+/// daemons, else plain ALSA when the kernel has sound cards. No spawns.
+fn get_audio_server() -> Option<String> {
+    let runtime_dir = env::var("XDG_RUNTIME_DIR").ok().filter(|s| !s.is_empty()).unwrap_or_else(|| {
+        extern "C" {
+            fn geteuid() -> u32;
+        }
+        format!("/run/user/{}", unsafe { geteuid() })
+    });
+
+    // pipewire-pulse also serves pulse/native, so PipeWire has to be checked first
+    if Path::new(&format!("{}/pipewire-0", runtime_dir)).exists() {
+        return Some("PipeWire".to_string());
+    }
+
+    // Sockets can be missing for system-wide daemons or other users; scan /proc
+    let mut running = Vec::new();
+    if let Ok(entries) = fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let pid = entry.file_name();
+            if !pid.to_str().is_some_and(|s| s.chars().all(|c| c.is_ascii_digit())) {
+                continue;
+            }
+            if let Ok(comm) = fs::read_to_string(entry.path().join("comm")) {
+                running.push(comm.trim().to_string());
+            }
+        }
+    }
+    let is_running = |names: &[&str]| running.iter().any(|c| names.contains(&c.as_str()));
+
+    if is_running(&["pipewire", "wireplumber"]) {
+        Some("PipeWire".to_string())
+    } else if Path::new(&format!("{}/pulse/native", runtime_dir)).exists() || is_running(&["pulseaudio"]) {
+        Some("PulseAudio".to_string())
+    } else if is_running(&["jackd", "jackdbus"]) {
+        Some("JACK".to_string())
+    } else if fs::read_to_string("/proc/asound/cards").is_ok_and(|c| !c.contains("no soundcards")) {
+        Some("ALSA".to_string())
+    } else {
+        None
+    }
+}
+
+const PUBLIC_IP_HOST: &str = "icanhazip.com";
 
-fn get_motherboard() -> Option<String> {
-    read_file_trim("/sys/class/dmi/id/board_name")
+/// Public IPv4 and IPv6 addresses, queried in parallel. A family the network
+/// doesn't route (v4-only or v6-only) just comes back None.
+fn get_public_ips() -> (Option<String>, Option<String>) {
+    thread::scope(|s| {
+        let v6 = s.spawn(|| get_public_ip(true));
+        let v4 = get_public_ip(false);
+        (v4, v6.join().ok().flatten())
+    })
 }
 
-fn get_bios() -> Option<String> {
-    read_file_trim("/sys/class/dmi/id/bios_version")
+/// Our address as seen by an IP-echo service. Plain HTTP over a raw socket so
+/// curl isn't required; curl is still tried if that fails (e.g. behind a proxy).
+fn get_public_ip(ipv6: bool) -> Option<String> {
+    let family: fn(&std::net::SocketAddr) -> bool = if ipv6 { |a| a.is_ipv6() } else { |a| a.is_ipv4() };
+    http_get(PUBLIC_IP_HOST, "/", family, std::time::Duration::from_secs(1))
+        .and_then(|body| parse_public_ip(&body, ipv6))
+        .or_else(|| {
+            log_debug("PUBLIC_IP", "Raw HTTP request failed, falling back to curl");
+            run_cmd("curl", &["-s", if ipv6 { "-6" } else { "-4" }, "--connect-timeout", "1", "https://icanhazip.com"])
+                .and_then(|body| parse_public_ip(&body, ipv6))
+        })
 }
 
-fn get_processes() -> Option<usize> {
-    fs::read_dir("/proc").ok()?.filter_map(|e| e.ok()).filter(|e| {
-        e.file_name().to_str().map(|s| s.chars().all(|c| c.is_ascii_digit())).unwrap_or(false)
-    }).count().into()
+/// The response body if it is exactly one address of the requested family; a captive
+/// portal or proxy error page returns 200 with HTML that must not be shown or cached.
+fn parse_public_ip(body: &str, ipv6: bool) -> Option<String> {
+    let ip = body.trim();
+    let valid = if ipv6 { ip.parse::<std::net::Ipv6Addr>().is_ok() } else { ip.parse::<std::net::Ipv4Addr>().is_ok() };
+    if !valid {
+        log_warn("PUBLIC_IP", &format!("Ignoring a response that is not an IPv{} address", if ipv6 { 6 } else { 4 }));
+        return None;
+    }
+    Some(ip.to_string())
 }
 
-fn get_locale() -> Option<String> {
-    env::var("LANG").ok()
-}
+/// Minimal HTTP/1.0 GET returning the body of a 200 response, connecting to the
+/// first resolved address accepted by `family`. `timeout` applies to the connect
+/// and to each read/write; name resolution is not bounded.
+fn http_get(host: &str, path: &str, family: fn(&std::net::SocketAddr) -> bool, timeout: std::time::Duration) -> Option<String> {
+    use std::io::Read;
+    use std::net::{TcpStream, ToSocketAddrs};
 
-fn get_public_ip() -> Option<String> {
-    run_cmd("curl", &["-s", "--connect-timeout", "1", "https://icanhazip.com"])
+    let addr = (host, 80).to_socket_addrs().ok()?.find(family)?;
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: {}/{}\r\nConnection: close\r\n\r\n",
+        path, host, PROGRAM_NAME, VERSION);
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let status = head.lines().next()?.split_whitespace().nth(1)?;
+    if status != "200" {
+        log_debug("HTTP", &format!("GET http://{}{} returned {}", host, path, status));
+        return None;
+    }
+    Some(body.to_string())
 }
 
 struct ThemeInfo {
@@ -2840,126 +7977,816 @@ fn parse_human_size(s: &str) -> Option<f64> {
     } else {
         (s, "")
     };
-    
-    if let Ok(num) = num_str.parse::<f64>() {
-        match unit {
-            "G" => Some(num),
-            "M" => Some(num / 1024.0),
-            "T" => Some(num * 1024.0),
-            _ => Some(num / (1024.0 * 1024.0 * 1024.0)),
+    
+    if let Ok(num) = num_str.parse::<f64>() {
+        match unit {
+            "G" => Some(num),
+            "M" => Some(num / 1024.0),
+            "T" => Some(num * 1024.0),
+            _ => Some(num / (1024.0 * 1024.0 * 1024.0)),
+        }
+    } else {
+        None
+    }
+}
+
+#[derive(Clone)]
+struct Battery {
+    capacity: u8,
+    status: String,
+    /// Watts, positive while charging and negative while discharging (`--battery-power`)
+    power: Option<f64>,
+}
+
+fn get_battery(with_power: bool) -> Option<Battery> {
+    let entries = fs::read_dir("/sys/class/power_supply").ok()?;
+    
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_name = path.file_name()?.to_string_lossy();
+        
+        if file_name.starts_with("BAT") {
+            let capacity = read_file_trim(&path.join("capacity").to_string_lossy().to_string())
+                .and_then(|s| s.parse::<u8>().ok())
+                .unwrap_or(0);
+            
+            let status = read_file_trim(&path.join("status").to_string_lossy().to_string())
+                .unwrap_or_else(|| "Unknown".to_string());
+
+            let power = if with_power { get_battery_power(&path, &status) } else { None };
+            
+            return Some(Battery { capacity, status, power });
+        }
+    }
+    
+    None
+}
+
+/// "AC connected" when any mains adapter (type Mains, or named AC*/ADP*) is online,
+/// "Battery" when there are adapters but none is. None without an adapter to ask.
+fn get_power_source() -> Option<String> {
+    let mut found = false;
+    for entry in fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let read = |f: &str| read_file_trim(&path.join(f).to_string_lossy());
+        let is_mains = read("type").as_deref() == Some("Mains") || name.starts_with("AC") || name.starts_with("ADP");
+        if !is_mains {
+            continue;
+        }
+        found = true;
+        if read("online").as_deref() == Some("1") {
+            return Some("AC connected".to_string());
+        }
+    }
+    if found { Some("Battery".to_string()) } else { None }
+}
+
+/// `power_now` (µW), or `voltage_now` (µV) × `current_now` (µA) on batteries that
+/// only report current. Drivers disagree on the sign, so it comes from `status`.
+fn get_battery_power(dir: &Path, status: &str) -> Option<f64> {
+    let read = |f: &str| read_file_trim(&dir.join(f).to_string_lossy()).and_then(|v| v.parse::<f64>().ok());
+    let watts = match read("power_now") {
+        Some(uw) => uw.abs() / 1e6,
+        None => (read("voltage_now")? * read("current_now")?).abs() / 1e12,
+    };
+    if watts == 0.0 {
+        return None;
+    }
+    Some(if status == "Discharging" { -watts } else { watts })
+}
+
+/// Default IPv4 gateway: the `00000000` destination row of /proc/net/route.
+/// The kernel prints the network-order address as a host-order integer, so its
+/// native-endian bytes are the address bytes on any architecture.
+fn get_gateway() -> Option<String> {
+    let routes = fs::read_to_string("/proc/net/route").ok()?;
+    routes.lines().skip(1).find_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < 3 || cols[1] != "00000000" {
+            return None;
+        }
+        let gw = u32::from_str_radix(cols[2], 16).ok().filter(|&gw| gw != 0)?;
+        Some(std::net::Ipv4Addr::from(gw.to_ne_bytes()).to_string())
+    })
+}
+
+/// Nameservers from resolv.conf. When that only points at systemd-resolved's
+/// stub (127.0.0.53), the upstream servers come from resolved's own copy.
+fn get_dns() -> Option<String> {
+    let nameservers = |path: &str| -> Vec<String> {
+        fs::read_to_string(path).unwrap_or_default().lines()
+            .filter_map(|l| l.trim().strip_prefix("nameserver"))
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    };
+    let mut servers = nameservers("/etc/resolv.conf");
+    if servers.iter().all(|s| s == "127.0.0.53") {
+        let upstream = nameservers("/run/systemd/resolve/resolv.conf");
+        if !upstream.is_empty() { servers = upstream; }
+    }
+    if servers.is_empty() { None } else { Some(servers.join(", ")) }
+}
+
+/// Up tunnel interfaces, e.g. "wg0 (WireGuard)". WireGuard announces itself in
+/// `uevent`; TUN/TAP devices (OpenVPN, Tailscale, ...) have a `tun_flags` file.
+fn get_vpn() -> Option<String> {
+    const IFF_UP: u32 = 0x1;
+    const IFF_TAP: u32 = 0x2;
+    let hex = |s: String| u32::from_str_radix(s.trim_start_matches("0x"), 16).ok();
+
+    let mut tunnels = Vec::new();
+    for entry in fs::read_dir("/sys/class/net").ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let dir = entry.path();
+        // operstate is "unknown" for most tunnels, so go by the admin-up flag
+        let up = read_file_trim(&dir.join("flags").to_string_lossy()).and_then(hex).is_some_and(|f| f & IFF_UP != 0);
+        if !up {
+            continue;
+        }
+        let kind = if fs::read_to_string(dir.join("uevent")).is_ok_and(|u| u.contains("DEVTYPE=wireguard")) {
+            "WireGuard"
+        } else if let Some(flags) = read_file_trim(&dir.join("tun_flags").to_string_lossy()).and_then(hex) {
+            if name.starts_with("tailscale") { "Tailscale" }
+            else if flags & IFF_TAP != 0 { "TAP" }
+            else { "TUN" }
+        } else {
+            continue;
+        };
+        tunnels.push(format!("{} ({})", name, kind));
+    }
+    tunnels.sort();
+    if tunnels.is_empty() { None } else { Some(tunnels.join(", ")) }
+}
+
+/// Name prefixes of bridge/tunnel/container interfaces hidden unless `--all-net`.
+const VIRTUAL_IFACE_PREFIXES: &[&str] = &["veth", "docker", "br-", "virbr", "tun", "tap"];
+
+/// `--net-iface` whitelist if given, otherwise everything but loopback and
+/// (without `--all-net`) virtual interfaces.
+fn show_interface(name: &str, config: &Config) -> bool {
+    if !config.net_ifaces.is_empty() {
+        return config.net_ifaces.iter().any(|i| i == name);
+    }
+    name != "lo" && (config.all_net || !VIRTUAL_IFACE_PREFIXES.iter().any(|p| name.starts_with(p)))
+}
+
+fn get_network_final_with_ip(net_start: Option<String>, delta: f64, config: &Config, ip_out: Option<String>) -> Option<Vec<NetworkInfo>> {
+    let dev1 = net_start?;
+    let dev2 = fs::read_to_string("/proc/net/dev").ok()?;
+    
+    let mut stats1 = HashMap::new();
+    for line in dev1.lines().skip(2) {
+        let p: Vec<&str> = line.split_whitespace().collect();
+        if p.len() > 9 { stats1.insert(p[0].trim_end_matches(':').to_string(), (p[1].parse::<u64>().unwrap_or(0), p[9].parse::<u64>().unwrap_or(0))); }
+    }
+
+    let mut ip_map = HashMap::new();
+    if let Some(output) = ip_out {
+        for line in output.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 4 { continue; }
+            let iface = parts[1].to_string();
+            let family = parts[2];
+            let addr = parts[3].split('/').next().unwrap_or(parts[3]).to_string();
+            let entry = ip_map.entry(iface).or_insert((None, None));
+            if family == "inet" { entry.0 = Some(addr); }
+            else if family == "inet6" && !addr.starts_with("fe80") && addr != "::1" { entry.1 = Some(addr); }
+        }
+    }
+
+    let mut networks = Vec::with_capacity(4);
+    for line in dev2.lines().skip(2) {
+        let p: Vec<&str> = line.split_whitespace().collect();
+        if p.len() < 10 { continue; }
+        let interface = p[0].trim_end_matches(':').to_string();
+        if !show_interface(&interface, config) { continue; }
+        let (ipv4, ipv6) = ip_map.remove(&interface).unwrap_or((None, None));
+        let state = read_file_trim(&format!("/sys/class/net/{}/operstate", interface)).unwrap_or_else(|| "unknown".to_string()).to_uppercase();
+        let rx2 = p[1].parse::<u64>().ok();
+        let tx2 = p[9].parse::<u64>().ok();
+        
+        let mut rx_rate = None;
+        let mut tx_rate = None;
+        if let (Some(r2), Some(t2), Some(&(r1, t1))) = (rx2, tx2, stats1.get(&interface)) {
+            rx_rate = Some((r2.saturating_sub(r1) as f64 / (1024.0 * 1024.0)) / delta);
+            tx_rate = Some((t2.saturating_sub(t1) as f64 / (1024.0 * 1024.0)) / delta);
+        }
+
+        let mut p_stat = None;
+        let mut j_stat = None;
+        let mut l_stat = None;
+        if config.show_network_ping && state == "UP" && ipv4.is_some() {
+            let (count, timeout) = (config.ping_count.to_string(), config.ping_timeout.to_string());
+            if let Some(out) = run_cmd("ping", &["-c", &count, "-i", "0.2", "-W", &timeout, &config.ping_host]) {
+                for l in out.lines() {
+                    if l.contains("packet loss") {
+                        if let Some(pos) = l.find('%') {
+                            let start = l[..pos].rfind(' ').unwrap_or(0);
+                            l_stat = l[start..pos].trim().parse::<f64>().ok();
+                        }
+                    } else if l.contains("rtt min/avg/max/mdev") {
+                        let stats: Vec<&str> = l.split('=').nth(1).unwrap_or("").trim().split('/').collect();
+                        if stats.len() >= 4 {
+                            p_stat = stats[1].parse::<f64>().ok();
+                            j_stat = stats[3].split(' ').next().and_then(|s| s.parse::<f64>().ok());
+                        }
+                    }
+                }
+            }
+        }
+
+        networks.push(NetworkInfo {
+            interface, ipv4, ipv6, mac: None, state, rx_bytes: rx2, tx_bytes: tx2,
+            rx_rate_mbs: rx_rate, tx_rate_mbs: tx_rate, ping: p_stat, jitter: j_stat, packet_loss: l_stat,
+        });
+    }
+
+    networks.sort_by(|a, b| {
+        let a_up = a.state == "UP";
+        let b_up = b.state == "UP";
+        if a_up != b_up { b_up.cmp(&a_up) } else { a.interface.cmp(&b.interface) }
+    });
+
+    if networks.is_empty() { None } else { Some(networks) }
+}
+
+// ============================================================================
+// IMAGE LOGOS (KITTY / SIXEL)
+// ============================================================================
+
+const IMAGE_MAX_COLS: usize = 32;
+const IMAGE_MAX_ROWS: usize = 16;
+
+enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+}
+
+struct ImageLogo {
+    escape: String,
+    cols: usize,
+    rows: usize,
+}
+
+struct RgbaImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 4]>,
+}
+
+fn detect_graphics_protocol() -> Option<GraphicsProtocol> {
+    let term = env::var("TERM").unwrap_or_default().to_lowercase();
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+
+    if env::var("KITTY_WINDOW_ID").is_ok() || term.contains("kitty") || term.contains("ghostty")
+        || term_program == "WezTerm" || term_program == "ghostty" {
+        return Some(GraphicsProtocol::Kitty);
+    }
+    if term.contains("sixel") || term.starts_with("foot") || term.contains("mlterm")
+        || term == "yaft-256color" || term_program == "iTerm.app" || term_program == "mintty" {
+        return Some(GraphicsProtocol::Sixel);
+    }
+    None
+}
+
+/// Decodes `path`, scales it to fit the logo column and encodes it for the terminal.
+/// Returns None (caller falls back to ASCII art) when anything along the way fails.
+fn load_image_logo(path: &str) -> Option<ImageLogo> {
+    let protocol = match detect_graphics_protocol() {
+        Some(p) => p,
+        None => {
+            log_info("IMAGE", "Terminal has no known graphics support, using ASCII logo");
+            return None;
+        }
+    };
+
+    let data = match fs::read(path) {
+        Ok(d) => d,
+        Err(e) => {
+            log_warn("IMAGE", &format!("Could not read image {}: {}", path, e));
+            return None;
+        }
+    };
+    let image = match decode_png(&data) {
+        Some(img) => img,
+        None => {
+            log_warn("IMAGE", &format!("Failed to decode {} (only non-interlaced PNG is supported)", path));
+            return None;
+        }
+    };
+
+    // Cell size in pixels; most terminals report it via TIOCGWINSZ, otherwise assume 10x20
+    let (cell_w, cell_h) = match get_winsize() {
+        Some(ws) if ws.cols > 0 && ws.rows > 0 && ws.xpixel > 0 && ws.ypixel > 0 => {
+            ((ws.xpixel / ws.cols).max(1) as usize, (ws.ypixel / ws.rows).max(1) as usize)
+        }
+        _ => (10, 20),
+    };
+
+    let max_w = (IMAGE_MAX_COLS * cell_w) as f64;
+    let max_h = (IMAGE_MAX_ROWS * cell_h) as f64;
+    let scale = (max_w / image.width as f64).min(max_h / image.height as f64).min(1.0);
+    let target_w = ((image.width as f64 * scale) as usize).max(1);
+    let target_h = ((image.height as f64 * scale) as usize).max(1);
+    let scaled = scale_image(&image, target_w, target_h);
+
+    let cols = target_w.div_ceil(cell_w);
+    let rows = target_h.div_ceil(cell_h);
+    let escape = match protocol {
+        GraphicsProtocol::Kitty => encode_kitty(&scaled),
+        GraphicsProtocol::Sixel => encode_sixel(&scaled),
+    };
+    log_debug("IMAGE", &format!("Image logo {}x{} px -> {}x{} px ({} cols x {} rows)",
+        image.width, image.height, target_w, target_h, cols, rows));
+
+    Some(ImageLogo { escape, cols, rows })
+}
+
+/// Box-filter downscale (or nearest-neighbour upscale) to `width` x `height`.
+fn scale_image(src: &RgbaImage, width: usize, height: usize) -> RgbaImage {
+    let mut pixels = Vec::with_capacity(width * height);
+    for ty in 0..height {
+        let y0 = ty * src.height / height;
+        let y1 = ((ty + 1) * src.height / height).max(y0 + 1).min(src.height);
+        for tx in 0..width {
+            let x0 = tx * src.width / width;
+            let x1 = ((tx + 1) * src.width / width).max(x0 + 1).min(src.width);
+            let mut sum = [0u32; 4];
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let p = src.pixels[y * src.width + x];
+                    for c in 0..4 { sum[c] += p[c] as u32; }
+                }
+            }
+            let n = ((y1 - y0) * (x1 - x0)) as u32;
+            pixels.push([(sum[0] / n) as u8, (sum[1] / n) as u8, (sum[2] / n) as u8, (sum[3] / n) as u8]);
+        }
+    }
+    RgbaImage { width, height, pixels }
+}
+
+/// Kitty graphics protocol: raw RGBA, base64 in 4096-byte chunks.
+fn encode_kitty(img: &RgbaImage) -> String {
+    let raw: Vec<u8> = img.pixels.iter().flat_map(|p| p.iter().copied()).collect();
+    let payload = base64_encode(&raw);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(4096).collect();
+
+    let mut out = String::with_capacity(payload.len() + chunks.len() * 32);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        let chunk = std::str::from_utf8(chunk).unwrap_or("");
+        if i == 0 {
+            out.push_str(&format!("\x1b_Gf=32,s={},v={},a=T,C=1,m={};{}\x1b\\", img.width, img.height, more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// Sixel with a fixed 6x6x6 colour cube; pixels under 50% alpha are left transparent.
+fn encode_sixel(img: &RgbaImage) -> String {
+    let level = |v: u8| (v as usize * 5 + 127) / 255;
+    let index_of = |p: [u8; 4]| -> Option<usize> {
+        if p[3] < 128 { None } else { Some(level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])) }
+    };
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", img.width, img.height);
+    for i in 0..216 {
+        let pct = |l: usize| l * 100 / 5;
+        out.push_str(&format!("#{};2;{};{};{}", i, pct(i / 36), pct((i / 6) % 6), pct(i % 6)));
+    }
+
+    for band in (0..img.height).step_by(6) {
+        let band_end = (band + 6).min(img.height);
+        let mut used = [false; 216];
+        for y in band..band_end {
+            for x in 0..img.width {
+                if let Some(c) = index_of(img.pixels[y * img.width + x]) { used[c] = true; }
+            }
+        }
+
+        for (color, _) in used.iter().enumerate().filter(|(_, &u)| u) {
+            out.push_str(&format!("#{}", color));
+            let mut run_char = '\0';
+            let mut run_len = 0;
+            for x in 0..img.width {
+                let mut bits = 0u8;
+                for y in band..band_end {
+                    if index_of(img.pixels[y * img.width + x]) == Some(color) {
+                        bits |= 1 << (y - band);
+                    }
+                }
+                let ch = (63 + bits) as char;
+                if ch == run_char {
+                    run_len += 1;
+                } else {
+                    push_sixel_run(&mut out, run_char, run_len);
+                    run_char = ch;
+                    run_len = 1;
+                }
+            }
+            push_sixel_run(&mut out, run_char, run_len);
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_sixel_run(out: &mut String, ch: char, len: usize) {
+    match len {
+        0 => {}
+        1..=3 => { for _ in 0..len { out.push(ch); } }
+        _ => out.push_str(&format!("!{}{}", len, ch)),
+    }
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        out.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 { ALPHABET[(n >> 6) as usize & 63] as char } else { '=' });
+        out.push(if chunk.len() > 2 { ALPHABET[n as usize & 63] as char } else { '=' });
+    }
+    out
+}
+
+/// Minimal PNG decoder: all colour types, bit depths 1-16, no interlacing.
+fn decode_png(data: &[u8]) -> Option<RgbaImage> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    if data.len() < 8 || data[..8] != SIGNATURE {
+        return None;
+    }
+
+    let (mut width, mut height, mut depth, mut color_type, mut interlace) = (0usize, 0usize, 0u8, 0u8, 0u8);
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let mut idat = Vec::new();
+
+    let mut pos = 8;
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let body = data.get(pos + 8..pos + 8 + len)?;
+        match kind {
+            b"IHDR" if len >= 13 => {
+                width = u32::from_be_bytes([body[0], body[1], body[2], body[3]]) as usize;
+                height = u32::from_be_bytes([body[4], body[5], body[6], body[7]]) as usize;
+                depth = body[8];
+                color_type = body[9];
+                interlace = body[12];
+            }
+            b"PLTE" => {
+                palette = body.chunks(3).filter(|c| c.len() == 3).map(|c| [c[0], c[1], c[2], 255]).collect();
+            }
+            b"tRNS" if color_type == 3 => {
+                for (entry, &alpha) in palette.iter_mut().zip(body.iter()) { entry[3] = alpha; }
+            }
+            b"IDAT" => idat.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len; // length + type + body + crc
+    }
+
+    if width == 0 || height == 0 || interlace != 0 || width * height > 16_000_000 {
+        return None;
+    }
+    let channels = match color_type { 0 => 1, 2 => 3, 3 => 1, 4 => 2, 6 => 4, _ => return None };
+    if !matches!(depth, 1 | 2 | 4 | 8 | 16) || (color_type == 3 && palette.is_empty()) {
+        return None;
+    }
+
+    let raw = inflate_zlib(&idat)?;
+    let bits_per_pixel = channels * depth as usize;
+    let stride = (width * bits_per_pixel).div_ceil(8);
+    let bpp = bits_per_pixel.div_ceil(8).max(1);
+    if raw.len() < height * (stride + 1) {
+        return None;
+    }
+
+    // Undo per-scanline filters
+    let mut rows: Vec<u8> = vec![0; height * stride];
+    for y in 0..height {
+        let filter = raw[y * (stride + 1)];
+        let line = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        for x in 0..stride {
+            let a = if x >= bpp { rows[y * stride + x - bpp] as i32 } else { 0 };
+            let b = if y > 0 { rows[(y - 1) * stride + x] as i32 } else { 0 };
+            let c = if x >= bpp && y > 0 { rows[(y - 1) * stride + x - bpp] as i32 } else { 0 };
+            let predictor = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => (a + b) / 2,
+                4 => {
+                    let p = a + b - c;
+                    let (pa, pb, pc) = ((p - a).abs(), (p - b).abs(), (p - c).abs());
+                    if pa <= pb && pa <= pc { a } else if pb <= pc { b } else { c }
+                }
+                _ => return None,
+            };
+            rows[y * stride + x] = (line[x] as i32 + predictor) as u8;
+        }
+    }
+
+    // Pull the `idx`th sample of a row, scaled to 8 bits (palette indices stay raw)
+    let max = ((1u32 << depth.min(8)) - 1) as u8;
+    let sample = |row: &[u8], idx: usize, scale: bool| -> u8 {
+        match depth {
+            8 => row[idx],
+            16 => row[idx * 2],
+            _ => {
+                let bit = idx * depth as usize;
+                let v = (row[bit / 8] >> (8 - depth as usize - bit % 8)) & max;
+                if scale { (v as u32 * 255 / max as u32) as u8 } else { v }
+            }
+        }
+    };
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let row = &rows[y * stride..(y + 1) * stride];
+        for x in 0..width {
+            let s = x * channels;
+            let px = match color_type {
+                0 => { let g = sample(row, s, true); [g, g, g, 255] }
+                2 => [sample(row, s, true), sample(row, s + 1, true), sample(row, s + 2, true), 255],
+                3 => *palette.get(sample(row, s, false) as usize).unwrap_or(&[0, 0, 0, 0]),
+                4 => { let g = sample(row, s, true); [g, g, g, sample(row, s + 1, true)] }
+                _ => [sample(row, s, true), sample(row, s + 1, true), sample(row, s + 2, true), sample(row, s + 3, true)],
+            };
+            pixels.push(px);
         }
-    } else {
-        None
     }
+
+    Some(RgbaImage { width, height, pixels })
 }
 
-fn get_battery() -> Option<(u8, String)> {
-    let entries = fs::read_dir("/sys/class/power_supply").ok()?;
-    
-    for entry in entries.flatten() {
-        let path = entry.path();
-        let file_name = path.file_name()?.to_string_lossy();
-        
-        if file_name.starts_with("BAT") {
-            let capacity = read_file_trim(&path.join("capacity").to_string_lossy().to_string())
-                .and_then(|s| s.parse::<u8>().ok())
-                .unwrap_or(0);
-            
-            let status = read_file_trim(&path.join("status").to_string_lossy().to_string())
-                .unwrap_or_else(|| "Unknown".to_string());
-            
-            return Some((capacity, status));
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, n: u32) -> Option<u32> {
+        while self.bit_count < n {
+            let byte = *self.data.get(self.pos)? as u32;
+            self.pos += 1;
+            self.bit_buf |= byte << self.bit_count;
+            self.bit_count += 8;
         }
+        let val = self.bit_buf & ((1u32 << n) - 1);
+        self.bit_buf >>= n;
+        self.bit_count -= n;
+        Some(val)
     }
-    
-    None
 }
 
-fn get_network_final_with_ip(net_start: Option<String>, delta: f64, should_ping: bool, ip_out: Option<String>) -> Option<Vec<NetworkInfo>> {
-    let dev1 = net_start?;
-    let dev2 = fs::read_to_string("/proc/net/dev").ok()?;
-    
-    let mut stats1 = HashMap::new();
-    for line in dev1.lines().skip(2) {
-        let p: Vec<&str> = line.split_whitespace().collect();
-        if p.len() > 9 { stats1.insert(p[0].trim_end_matches(':').to_string(), (p[1].parse::<u64>().unwrap_or(0), p[9].parse::<u64>().unwrap_or(0))); }
-    }
+/// Canonical Huffman table in the counts/symbols form used by zlib's puff.c.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
 
-    let mut ip_map = HashMap::new();
-    if let Some(output) = ip_out {
-        for line in output.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 4 { continue; }
-            let iface = parts[1].to_string();
-            let family = parts[2];
-            let addr = parts[3].split('/').next().unwrap_or(parts[3]).to_string();
-            let entry = ip_map.entry(iface).or_insert((None, None));
-            if family == "inet" { entry.0 = Some(addr); }
-            else if family == "inet6" && !addr.starts_with("fe80") && addr != "::1" { entry.1 = Some(addr); }
+impl Huffman {
+    fn new(lengths: &[u8]) -> Huffman {
+        let mut counts = [0u16; 16];
+        for &len in lengths { counts[len as usize] += 1; }
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        for len in 1..16 { offsets[len] = offsets[len - 1] + counts[len - 1]; }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (sym, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = sym as u16;
+                offsets[len as usize] += 1;
+            }
         }
+        Huffman { counts, symbols }
     }
 
-    let mut networks = Vec::with_capacity(4);
-    for line in dev2.lines().skip(2) {
-        let p: Vec<&str> = line.split_whitespace().collect();
-        if p.len() < 10 { continue; }
-        let interface = p[0].trim_end_matches(':').to_string();
-        if interface == "lo" { continue; }
-        let (ipv4, ipv6) = ip_map.remove(&interface).unwrap_or((None, None));
-        let state = read_file_trim(&format!("/sys/class/net/{}/operstate", interface)).unwrap_or_else(|| "unknown".to_string()).to_uppercase();
-        let rx2 = p[1].parse::<u64>().ok();
-        let tx2 = p[9].parse::<u64>().ok();
-        
-        let mut rx_rate = None;
-        let mut tx_rate = None;
-        if let (Some(r2), Some(t2), Some(&(r1, t1))) = (rx2, tx2, stats1.get(&interface)) {
-            rx_rate = Some((r2.saturating_sub(r1) as f64 / (1024.0 * 1024.0)) / delta);
-            tx_rate = Some((t2.saturating_sub(t1) as f64 / (1024.0 * 1024.0)) / delta);
+    fn decode(&self, br: &mut BitReader) -> Option<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= br.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return self.symbols.get((index + code - first) as usize).copied();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
         }
+        None
+    }
+}
 
-        let mut p_stat = None;
-        let mut j_stat = None;
-        let mut l_stat = None;
-        if should_ping && state == "UP" && ipv4.is_some() {
-            if let Some(out) = run_cmd("ping", &["-c", "2", "-i", "0.2", "-W", "1", "1.1.1.1"]) {
-                for l in out.lines() {
-                    if l.contains("packet loss") {
-                        if let Some(pos) = l.find('%') {
-                            let start = l[..pos].rfind(' ').unwrap_or(0);
-                            l_stat = l[start..pos].trim().parse::<f64>().ok();
+/// zlib stream -> raw bytes (RFC 1950/1951). The Adler-32 trailer is not verified.
+fn inflate_zlib(data: &[u8]) -> Option<Vec<u8>> {
+    const LEN_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+                                 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+    const LEN_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+                                 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+    const DIST_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+                                  257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+                                  8193, 12289, 16385, 24577];
+    const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+                                  7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+    const CODE_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+    if data.len() < 2 || data[0] & 0x0f != 8 {
+        return None;
+    }
+    let mut br = BitReader { data: &data[2..], pos: 0, bit_buf: 0, bit_count: 0 };
+    let mut out: Vec<u8> = Vec::with_capacity(data.len() * 4);
+
+    loop {
+        let last = br.bits(1)? == 1;
+        match br.bits(2)? {
+            0 => {
+                // Stored block: realign to a byte boundary
+                br.bit_buf = 0;
+                br.bit_count = 0;
+                let header = br.data.get(br.pos..br.pos + 4)?;
+                let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+                br.pos += 4;
+                out.extend_from_slice(br.data.get(br.pos..br.pos + len)?);
+                br.pos += len;
+            }
+            kind @ (1 | 2) => {
+                let (lit, dist) = if kind == 1 {
+                    let mut lengths = [0u8; 288];
+                    for (i, l) in lengths.iter_mut().enumerate() {
+                        *l = match i { 0..=143 => 8, 144..=255 => 9, 256..=279 => 7, _ => 8 };
+                    }
+                    (Huffman::new(&lengths), Huffman::new(&[5u8; 30]))
+                } else {
+                    let hlit = br.bits(5)? as usize + 257;
+                    let hdist = br.bits(5)? as usize + 1;
+                    let hclen = br.bits(4)? as usize + 4;
+                    let mut code_lengths = [0u8; 19];
+                    for &idx in CODE_ORDER.iter().take(hclen) {
+                        code_lengths[idx] = br.bits(3)? as u8;
+                    }
+                    let code_huff = Huffman::new(&code_lengths);
+
+                    let mut lengths = Vec::with_capacity(hlit + hdist);
+                    while lengths.len() < hlit + hdist {
+                        let sym = code_huff.decode(&mut br)?;
+                        let (value, repeat) = match sym {
+                            0..=15 => (sym as u8, 1),
+                            16 => (*lengths.last()?, 3 + br.bits(2)? as usize),
+                            17 => (0, 3 + br.bits(3)? as usize),
+                            18 => (0, 11 + br.bits(7)? as usize),
+                            _ => return None,
+                        };
+                        lengths.extend(std::iter::repeat_n(value, repeat));
+                    }
+                    if lengths.len() > hlit + hdist {
+                        return None;
+                    }
+                    (Huffman::new(&lengths[..hlit]), Huffman::new(&lengths[hlit..]))
+                };
+
+                loop {
+                    let sym = lit.decode(&mut br)? as usize;
+                    if sym < 256 {
+                        out.push(sym as u8);
+                    } else if sym == 256 {
+                        break;
+                    } else {
+                        let li = sym - 257;
+                        let len = *LEN_BASE.get(li)? as usize + br.bits(*LEN_EXTRA.get(li)? as u32)? as usize;
+                        let di = dist.decode(&mut br)? as usize;
+                        let distance = *DIST_BASE.get(di)? as usize + br.bits(*DIST_EXTRA.get(di)? as u32)? as usize;
+                        if distance > out.len() {
+                            return None;
                         }
-                    } else if l.contains("rtt min/avg/max/mdev") {
-                        let stats: Vec<&str> = l.split('=').nth(1).unwrap_or("").trim().split('/').collect();
-                        if stats.len() >= 4 {
-                            p_stat = stats[1].parse::<f64>().ok();
-                            j_stat = stats[3].split(' ').next().and_then(|s| s.parse::<f64>().ok());
+                        let start = out.len() - distance;
+                        for k in 0..len {
+                            out.push(out[start + k]);
                         }
                     }
                 }
             }
+            _ => return None,
+        }
+        if last {
+            break;
         }
-
-        networks.push(NetworkInfo {
-            interface, ipv4, ipv6, mac: None, state, rx_bytes: rx2, tx_bytes: tx2,
-            rx_rate_mbs: rx_rate, tx_rate_mbs: tx_rate, ping: p_stat, jitter: j_stat, packet_loss: l_stat,
-        });
     }
-
-    networks.sort_by(|a, b| {
-        let a_up = a.state == "UP";
-        let b_up = b.state == "UP";
-        if a_up != b_up { b_up.cmp(&a_up) } else { a.interface.cmp(&b.interface) }
-    });
-
-    if networks.is_empty() { None } else { Some(networks) }
+    Some(out)
 }
 
 // ============================================================================
 // ASCII LOGOS
 // ============================================================================
 
-fn get_logo(os: &str) -> Vec<String> {
+/// Where `render_output()` puts the logo relative to the info block.
+#[derive(Clone, Copy, PartialEq)]
+enum LogoPosition {
+    Left,
+    Right,
+    Top,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum LogoSize {
+    Normal,
+    Small,
+    None,
+}
+
+/// Logos taller than this are halved when no small variant exists.
+const SMALL_LOGO_MAX_LINES: usize = 8;
+
+/// Names accepted by `--ascii-distro`, as matched in `get_logo()` (some share art).
+const LOGO_NAMES: &[&str] = &[
+    "alma", "alpine", "android", "arch", "arco", "artix", "bazzite", "cachy", "centos",
+    "debian", "elementary", "endeavour", "fedora", "freebsd", "garuda", "gentoo", "kali",
+    "linux", "manjaro", "mint", "nixos", "pop", "rhel", "rocky", "solus", "ubuntu",
+    "void", "windows", "zorin",
+];
+
+/// os-release `ID` values mapped to the name `get_logo()` knows them by.
+const LOGO_IDS: &[(&str, &str)] = &[
+    ("cachyos", "cachy"),
+    ("bazzite", "bazzite"),
+    ("arch", "arch"),
+    ("artix", "artix"),
+    ("arcolinux", "arco"),
+    ("ubuntu", "ubuntu"),
+    ("kubuntu", "ubuntu"),
+    ("xubuntu", "ubuntu"),
+    ("lubuntu", "ubuntu"),
+    ("debian", "debian"),
+    ("raspbian", "debian"),
+    ("fedora", "fedora"),
+    ("manjaro", "manjaro"),
+    ("manjaro-arm", "manjaro"),
+    ("linuxmint", "mint"),
+    ("pop", "pop"),
+    ("gentoo", "gentoo"),
+    ("nixos", "nixos"),
+    ("void", "void"),
+    ("alpine", "alpine"),
+    ("endeavouros", "endeavour"),
+    ("zorin", "zorin"),
+    ("kali", "kali"),
+    ("garuda", "garuda"),
+    ("elementary", "elementary"),
+    ("solus", "solus"),
+    ("rhel", "rhel"),
+    ("centos", "centos"),
+    ("rocky", "rocky"),
+    ("almalinux", "alma"),
+    ("freebsd", "freebsd"),
+];
+
+/// Picks the logo from os-release `ID`, then each `ID_LIKE` parent (so derivatives
+/// get their family's logo), and only then falls back to matching the pretty name.
+fn select_logo(info: &Info, size: LogoSize) -> Vec<String> {
+    let ids = info.os_id.iter()
+        .chain(info.os_id_like.iter())
+        .flat_map(|s| s.split_whitespace());
+    for id in ids {
+        let id = id.to_lowercase();
+        if let Some(&(_, name)) = LOGO_IDS.iter().find(|(known, _)| *known == id) {
+            log_debug("LOGO", &format!("Logo selected by os-release ID '{}'", id));
+            return get_logo(name, size);
+        }
+    }
+    get_logo(info.os.as_deref().unwrap_or("unknown"), size)
+}
+
+fn get_logo(os: &str, size: LogoSize) -> Vec<String> {
     let ol = os.to_lowercase();
+
+    match size {
+        LogoSize::None => return Vec::new(),
+        LogoSize::Small => {
+            if let Some(lines) = get_small_logo(&ol) {
+                return lines.iter().map(|&s| s.to_string()).collect();
+            }
+            // No compact art: keep every other line of the full logo
+            let full = get_logo(os, LogoSize::Normal);
+            if full.len() <= SMALL_LOGO_MAX_LINES {
+                return full;
+            }
+            return full.into_iter().step_by(2).collect();
+        }
+        LogoSize::Normal => {}
+    }
     
     let lines: &[&str] = if ol.contains("cachy") {
         &[
@@ -3415,3 +9242,528 @@ fn get_logo(os: &str) -> Vec<String> {
     
     lines.iter().map(|&s| s.to_string()).collect()
 }
+
+/// Compact 6-8 line variants for `--logo-size small`.
+fn get_small_logo(ol: &str) -> Option<&'static [&'static str]> {
+    let lines: &'static [&'static str] = if ol.contains("arch") || ol.contains("artix") || ol.contains("arco") {
+        &[
+            r#"      /\      "#,
+            r#"     /  \     "#,
+            r#"    /\   \    "#,
+            r#"   /      \   "#,
+            r#"  /   ,,   \  "#,
+            r#" /   |  |  -\ "#,
+            r#"/_-''    ''-_\"#,
+        ]
+    } else if ol.contains("ubuntu") {
+        &[
+            r#"         _  "#,
+            r#"     ---(_) "#,
+            r#" _/  ---  \ "#,
+            r#"(_) |   |   "#,
+            r#"  \  --- _/ "#,
+            r#"     ---(_) "#,
+        ]
+    } else if ol.contains("debian") || ol.contains("raspberry") || ol.contains("raspbian") {
+        &[
+            r#"  _____  "#,
+            r#" /  __ \ "#,
+            r#"|  /    |"#,
+            r#"|  \___- "#,
+            r#"-_       "#,
+            r#"  --_    "#,
+        ]
+    } else if ol.contains("fedora") {
+        &[
+            r#"      _____   "#,
+            r#"     /   __)\ "#,
+            r#"     |  /  \ \"#,
+            r#"  ___|  |__/ /"#,
+            r#" / (_    _)_/ "#,
+            r#"/ /  |  |     "#,
+            r#"\ \__/  |     "#,
+            r#" \(_____/     "#,
+        ]
+    } else if ol.contains("manjaro") {
+        &[
+            r#"||||||||| ||||"#,
+            r#"||||||||| ||||"#,
+            r#"||||      ||||"#,
+            r#"|||| |||| ||||"#,
+            r#"|||| |||| ||||"#,
+            r#"|||| |||| ||||"#,
+            r#"|||| |||| ||||"#,
+        ]
+    } else if ol.contains("mint") {
+        &[
+            r#" ___________ "#,
+            r#"|_          \"#,
+            r#"  | | _____ |"#,
+            r#"  | | | | | |"#,
+            r#"  | | | | | |"#,
+            r#"  | \_____/ |"#,
+            r#"  \_________/"#,
+        ]
+    } else if ol.contains("gentoo") {
+        &[
+            r#" _-----_   "#,
+            r#"(       \  "#,
+            r#"\    0   \ "#,
+            r#" \        )"#,
+            r#" /      _/ "#,
+            r#"(     _-   "#,
+            r#"\____-     "#,
+        ]
+    } else if ol.contains("nixos") || ol.contains("nix") {
+        &[
+            r#"  \\  \\ //   "#,
+            r#" ==\\__\\/ // "#,
+            r#"   //   \\//  "#,
+            r#"==//     //== "#,
+            r#" //\\___//    "#,
+            r#"// /\\  \\==  "#,
+            r#"  // \\  \\   "#,
+        ]
+    } else if ol.contains("void") {
+        &[
+            r#"    _______   "#,
+            r#" _ \______ -  "#,
+            r#"| \  ___  \ | "#,
+            r#"| | /   \ | | "#,
+            r#"| | \___/ | | "#,
+            r#"| \______ \_| "#,
+            r#" -_______\    "#,
+        ]
+    } else if ol.contains("alpine") {
+        &[
+            r#"   /\ /\     "#,
+            r#"  // \  \    "#,
+            r#" //   \  \   "#,
+            r#"///    \  \  "#,
+            r#"//      \  \ "#,
+            r#"         \   "#,
+        ]
+    } else {
+        return None;
+    };
+    Some(lines)
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty scratch directory for tests that need a fake sysfs tree.
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir = env::temp_dir().join(format!("rustfetch-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn gpu_temps_pair_two_amdgpu_sensors_by_pci_address() {
+        let root = scratch_dir("gpu-temps");
+        let hwmon = root.join("hwmon");
+        // hwmon0 belongs to the second card, hwmon1 to the first
+        for (sensor, address, millidegrees) in [("hwmon0", "0000:0c:00.0", "61000"), ("hwmon1", "0000:03:00.0", "48000")] {
+            fs::create_dir_all(root.join("pci").join(address)).unwrap();
+            fs::create_dir_all(hwmon.join(sensor)).unwrap();
+            fs::write(hwmon.join(sensor).join("name"), "amdgpu\n").unwrap();
+            fs::write(hwmon.join(sensor).join("temp1_input"), millidegrees).unwrap();
+            std::os::unix::fs::symlink(root.join("pci").join(address), hwmon.join(sensor).join("device")).unwrap();
+        }
+        let gpus = vec!["AMD Navi 21 [Radeon RX 6800]".to_string(), "AMD Raphael".to_string()];
+        let slots = vec!["0000:03:00.0".to_string(), "0000:0c:00.0".to_string()];
+        assert_eq!(read_gpu_hwmon_temps(&hwmon, &gpus, &slots),
+            vec![Some("48°C".to_string()), Some("61°C".to_string())]);
+
+        // a sensor that fails to read leaves only its own GPU without a temperature
+        fs::write(hwmon.join("hwmon1").join("temp1_input"), "garbage").unwrap();
+        assert_eq!(read_gpu_hwmon_temps(&hwmon, &gpus, &slots), vec![None, Some("61°C".to_string())]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn pci_addresses_gain_a_four_digit_domain() {
+        assert_eq!(normalize_pci_address("01:00.0"), "0000:01:00.0");
+        assert_eq!(normalize_pci_address("00000000:0C:00.0"), "0000:0c:00.0");
+        assert_eq!(normalize_pci_address("0000:03:00.0"), "0000:03:00.0");
+    }
+
+    #[test]
+    fn cache_ttl_zero_means_never_expire() {
+        assert_eq!(parse_cache_ttl("0"), Ok(0));
+        assert_eq!(parse_cache_ttl(" 120 "), Ok(120));
+    }
+
+    #[test]
+    fn cache_ttl_rejects_negative_and_non_numeric_values() {
+        assert!(parse_cache_ttl("-5").unwrap_err().contains("negative"));
+        assert!(parse_cache_ttl("soon").unwrap_err().contains("whole number"));
+        assert!(parse_cache_ttl("").is_err());
+    }
+
+
+    #[test]
+    fn truncate_ansi_keeps_strings_at_the_limit_whole() {
+        assert_eq!(truncate_ansi("abcde", 5), "abcde");
+        assert_eq!(truncate_ansi("\x1b[31mabcde\x1b[0m", 5), "\x1b[31mabcde\x1b[0m");
+    }
+
+    #[test]
+    fn truncate_ansi_cuts_below_the_limit() {
+        assert_eq!(truncate_ansi("abcdef", 4), "abcd");
+        // cut mid-color: the open SGR is closed once
+        assert_eq!(truncate_ansi("\x1b[1;32mabcdef", 3), "\x1b[1;32mabc\x1b[0m");
+        // the color was already reset before the cut: no extra reset
+        assert_eq!(truncate_ansi("\x1b[32mab\x1b[0mcdef", 3), "\x1b[32mab\x1b[0mc");
+    }
+
+    #[test]
+    fn truncate_ansi_counts_wide_chars_as_two_columns() {
+        assert_eq!(visible_len("日本語"), 6);
+        assert_eq!(truncate_ansi("日本語", 6), "日本語");
+        // a wide char never straddles the limit
+        assert_eq!(truncate_ansi("日本語", 5), "日本");
+        assert_eq!(truncate_ansi("\x1b[33m日本語", 4), "\x1b[33m日本\x1b[0m");
+    }
+
+
+    #[test]
+    fn os_release_handles_quotes_escapes_and_comments() {
+        let content = concat!(
+            "# Steam Deck style, with a comment\n",
+            "\n",
+            "NAME=\"SteamOS\"\n",
+            "PRETTY_NAME=\"SteamOS \\\"Holo\\\" 3.5\"\n",
+            "ID=steamos\n",
+            "ID_LIKE=arch\n",
+            "   \n",
+            "VERSION_ID='3.5.7'\n",
+            "BUILD_ID=20231122.1\n",
+            "HOME_URL=\"https://example.com/?a=b&c=d\"\n",
+            "VARIANT=Holo # trailing comment\n",
+        );
+        let fields = parse_os_release(content);
+        assert_eq!(fields.get("PRETTY_NAME").map(String::as_str), Some("SteamOS \"Holo\" 3.5"));
+        assert_eq!(fields.get("ID").map(String::as_str), Some("steamos"));
+        assert_eq!(fields.get("VERSION_ID").map(String::as_str), Some("3.5.7"));
+        assert_eq!(fields.get("BUILD_ID").map(String::as_str), Some("20231122.1"));
+        assert_eq!(fields.get("HOME_URL").map(String::as_str), Some("https://example.com/?a=b&c=d"));
+        assert_eq!(fields.get("VARIANT").map(String::as_str), Some("Holo"));
+        assert_eq!(fields.len(), 8);
+    }
+
+
+    #[test]
+    fn logo_follows_os_release_id_then_id_like() {
+        let logo = |id: Option<&str>, id_like: Option<&str>, pretty: &str| select_logo(&Info {
+            os: Some(pretty.to_string()),
+            os_id: id.map(str::to_string),
+            os_id_like: id_like.map(str::to_string),
+            ..Info::default()
+        }, LogoSize::Normal);
+
+        // the pretty name alone would not say which family this is
+        assert_eq!(logo(Some("pop"), Some("ubuntu debian"), "Pop!_OS 22.04 LTS"), get_logo("pop", LogoSize::Normal));
+        assert_eq!(logo(Some("neon"), Some("ubuntu debian"), "KDE neon 6.0"), get_logo("ubuntu", LogoSize::Normal));
+        assert_eq!(logo(Some("steamos"), Some("arch"), "SteamOS"), get_logo("arch", LogoSize::Normal));
+        assert_eq!(logo(Some("rocky"), Some("rhel centos fedora"), "Rocky Linux 9.3"), get_logo("rocky", LogoSize::Normal));
+        // unknown IDs fall back to the pretty name
+        assert_eq!(logo(Some("mystery"), None, "Fedora Linux 40"), get_logo("fedora", LogoSize::Normal));
+    }
+
+
+    #[test]
+    fn timestamps_apply_a_fixed_utc_offset() {
+        // POSIX TZ: "UTC-2" is two hours east of UTC, no DST
+        env::set_var("TZ", "UTC-2");
+        assert_eq!(format_local_timestamp(1_700_000_000, false), "2023-11-15 00:13:20");
+        assert_eq!(format_local_timestamp(1_700_000_000, true), "2023-11-14 22:13:20");
+        assert_eq!(format_rfc3339(1_700_000_000, false), "2023-11-15T00:13:20+02:00");
+        assert_eq!(format_rfc3339(1_700_000_000, true), "2023-11-14T22:13:20Z");
+    }
+
+
+    #[test]
+    fn lspci_keeps_short_igpu_names_and_skips_non_gpus() {
+        let output = concat!(
+            "00:00.0 Host bridge: Intel Corporation Device 9b61 (rev 0c)\n",
+            "\tSubsystem: Lenovo Device 5092\n",
+            "\n",
+            "00:02.0 VGA compatible controller: Intel Corporation UHD 620 (rev 02)\n",
+            "\tSubsystem: Lenovo Device 5092\n",
+            "\tMemory at a0000000 (64-bit, non-prefetchable) [size=16M]\n",
+            "\tMemory at 90000000 (64-bit, prefetchable) [size=256M]\n",
+            "\n",
+            "00:1f.3 Audio device: Intel Corporation Comet Lake PCH-LP cAVS\n",
+            "\n",
+            "01:00.0 Display controller: Hauppauge Communications Framegrabber\n",
+            "\n",
+            "02:00.0 VGA compatible controller: ATI Technologies Inc Rage XL (rev 27)\n",
+        );
+        let (gpus, vrams, slots) = parse_lspci_gpus(output);
+        assert_eq!(gpus, vec!["Intel UHD 620".to_string(), "ATI Technologies Inc Rage XL".to_string()]);
+        assert_eq!(vrams, vec!["256M".to_string(), String::new()]);
+        assert_eq!(slots, vec!["0000:00:02.0".to_string(), "0000:02:00.0".to_string()]);
+    }
+
+
+    #[test]
+    fn render_snapshot_keeps_info_aligned_past_a_short_logo() {
+        let config = Config {
+            use_color: false,
+            use_glyphs: false,
+            width: Some(80),
+            ascii_distro: Some("alpine".to_string()),
+            logo_size: LogoSize::Small,
+            ..Config::default()
+        };
+        let info = Info {
+            user: Some("ada".to_string()),
+            hostname: Some("engine".to_string()),
+            os: Some("Alpine Linux v3.20".to_string()),
+            kernel: Some("6.6.32-0-lts".to_string()),
+            uptime_seconds: Some(273_127),
+            shell: Some("ash".to_string()),
+            cpu: Some("AMD Ryzen 7 7840U (16) @ 5.13GHz".to_string()),
+            ..Info::default()
+        };
+        let mut out = Vec::new();
+        render_to(&mut out, &info, &config);
+        let expected = concat!(
+            r"   /\ /\      ada@engine", "\n",
+            r"  // \  \     ──────────", "\n",
+            r" //   \  \    OS: Alpine Linux v3.20", "\n",
+            r"///    \  \   Kernel: 6.6.32-0-lts", "\n",
+            r"//      \  \  Uptime: 3d 3h 52m", "\n",
+            r"         \    Shell: ash", "\n",
+            // the logo ran out: the info column keeps its indent
+            r"              CPU: AMD Ryzen 7 7840U (16) @ 5.13GHz", "\n",
+        );
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+
+    #[test]
+    fn hwmon_temps_keep_the_old_range_and_format() {
+        let dir = scratch_dir("hwmon-temp");
+        let read = |value: &str| {
+            fs::write(dir.join("temp1_input"), value).unwrap();
+            read_hwmon_temp(&dir.join("temp1_input"))
+        };
+        assert_eq!(read("45000\n"), Some(45000));
+        assert_eq!(read("1000"), Some(1000));
+        assert_eq!(read("150000"), Some(150_000));
+        // unplugged sensors report 0 or nonsense; both are out of range
+        assert_eq!(read("0"), None);
+        assert_eq!(read("999"), None);
+        assert_eq!(read("150001"), None);
+        assert_eq!(read("-273000"), None);
+        assert_eq!(read("n/a"), None);
+        assert_eq!(read_hwmon_temp(&dir.join("temp9_input")), None);
+
+        // whole degrees, truncated like the inline `/ 1000` it replaced
+        assert_eq!(format_temp(45000), "45°C");
+        assert_eq!(format_temp(45999), "45°C");
+        assert_eq!(format_temp(1000), "1°C");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+
+    #[test]
+    fn json_strings_escape_every_control_character() {
+        let raw = "tab\there\u{1}cr\rbell\u{7}\"q\"\\".to_string();
+        let json = raw.to_json();
+        assert_eq!(json, r#""tab\there\u0001cr\rbell\u0007\"q\"\\""#);
+        // strict JSON allows no raw control characters inside a string
+        assert!(!json.chars().any(|c| (c as u32) < 0x20));
+        match JsonValue::parse(&json) {
+            Some(JsonValue::Str(back)) => assert_eq!(back, raw),
+            _ => panic!("escaped string did not parse back"),
+        }
+    }
+
+
+    #[test]
+    fn xrandr_reports_the_active_refresh_rate() {
+        let output = concat!(
+            "Screen 0: minimum 320 x 200, current 4480 x 1440, maximum 16384 x 16384\n",
+            "DP-1 connected primary 2560x1440+0+0 (normal left inverted right x axis y axis) 597mm x 336mm\n",
+            "   2560x1440     59.95 +  143.91*  119.88  \n",
+            "   1920x1080     60.00    50.00    59.94  \n",
+            "HDMI-1 connected 1920x1080+2560+0 (normal left inverted right x axis y axis) 527mm x 296mm\n",
+            "   1920x1080     60.00*+  50.00  \n",
+            "DP-2 connected (normal left inverted right x axis y axis)\n",
+            "   3840x2160     60.00 +\n",
+            "DP-3 disconnected (normal left inverted right x axis y axis)\n",
+        );
+        let monitors = parse_xrandr(output);
+        let summary: Vec<_> = monitors.iter().map(|m| (m.name.as_str(), m.width, m.height, m.refresh, m.primary)).collect();
+        assert_eq!(summary, vec![
+            ("DP-1", 2560, 1440, Some(143.91), true),
+            ("HDMI-1", 1920, 1080, Some(60.0), false),
+        ]);
+        assert_eq!(monitors[0].mode(), "2560x1440@144Hz");
+    }
+
+
+    #[test]
+    fn header_shows_the_hostname_alone_without_a_user() {
+        assert_eq!(header_title(None, Some("buildbox")), Some("buildbox".to_string()));
+        assert_eq!(header_title(Some("ada"), Some("buildbox")), Some("ada@buildbox".to_string()));
+        assert_eq!(header_title(None, None), None);
+
+        let config = Config { use_color: false, width: Some(80), logo_size: LogoSize::None, ..Config::default() };
+        let info = Info { hostname: Some("buildbox".to_string()), ..Info::default() };
+        let mut out = Vec::new();
+        render_to(&mut out, &info, &config);
+        assert!(String::from_utf8(out).unwrap().starts_with("buildbox\n────────\n"));
+    }
+
+
+    #[test]
+    fn wsl_version_comes_from_the_proc_version_signature() {
+        let wsl2 = "Linux version 5.15.153.1-microsoft-standard-WSL2 (root@941d701f84f1) (gcc (GCC) 11.2.0) #1 SMP";
+        let wsl2_old = "Linux version 4.19.104-microsoft-standard (oe-user@oe-host) #1 SMP Wed Feb 19 06:37:35 UTC 2020";
+        let wsl1 = "Linux version 4.4.0-19041-Microsoft (Microsoft@Microsoft.com) (gcc version 5.4.0 (GCC) ) #1237-Microsoft";
+        let native = "Linux version 6.9.3-arch1-1 (linux@archlinux) (gcc (GCC) 14.1.1 20240522) #1 SMP PREEMPT_DYNAMIC";
+        assert_eq!(wsl_version_from_proc(wsl2), Some(2));
+        assert_eq!(wsl_version_from_proc(wsl2_old), Some(2));
+        assert_eq!(wsl_version_from_proc(wsl1), Some(1));
+        assert_eq!(wsl_version_from_proc(native), None);
+    }
+
+
+    #[test]
+    fn truncated_comm_is_recovered_from_cmdline() {
+        // comm keeps 15 bytes of argv[0]'s basename
+        assert_eq!(full_process_name("wezterm-gui-bet", b"/opt/wez/wezterm-gui-beta\0start\0"), "wezterm-gui-beta");
+        assert_eq!(full_process_name("gnome-terminal-", b"/usr/libexec/gnome-terminal-server\0"), "gnome-terminal");
+        // without a usable cmdline the known truncation is still normalized
+        assert_eq!(full_process_name("gnome-terminal-", b""), "gnome-terminal");
+        // short names and unrelated cmdlines are left alone
+        assert_eq!(full_process_name("kitty", b"/usr/bin/kitty\0"), "kitty");
+        assert_eq!(full_process_name("python3-wrapper", b"/usr/bin/python3\0-m\0foo\0"), "python3-wrapper");
+    }
+
+
+    #[test]
+    fn public_ip_responses_must_be_an_address_of_the_family() {
+        assert_eq!(parse_public_ip("203.0.113.7\n", false), Some("203.0.113.7".to_string()));
+        assert_eq!(parse_public_ip("2001:db8::1\n", true), Some("2001:db8::1".to_string()));
+        assert_eq!(parse_public_ip("2001:db8::1", false), None);
+        assert_eq!(parse_public_ip("<html><body>Sign in to Hotel WiFi</body></html>", false), None);
+        assert_eq!(parse_public_ip("", true), None);
+    }
+
+
+    #[test]
+    fn compact_groups_have_no_separator_lines() {
+        let config = Config {
+            use_color: false, width: Some(80), logo_size: LogoSize::None,
+            group_sections: true, compact: true,
+            ..Config::default()
+        };
+        let info = Info {
+            os: Some("Alpine Linux v3.20".to_string()),
+            cpu: Some("AMD Ryzen 7 7840U".to_string()),
+            ..Info::default()
+        };
+        let mut out = Vec::new();
+        render_to(&mut out, &info, &config);
+        assert_eq!(String::from_utf8(out).unwrap(), "OS: Alpine Linux v3.20\nCPU: AMD Ryzen 7 7840U\n");
+    }
+
+
+    #[test]
+    fn render_fits_a_terminal_narrower_than_logo_plus_sixty() {
+        let config = Config {
+            use_color: false,
+            use_glyphs: false,
+            width: Some(40),
+            ascii_distro: Some("alpine".to_string()),
+            logo_size: LogoSize::Small,
+            ..Config::default()
+        };
+        let info = Info {
+            user: Some("ada".to_string()),
+            hostname: Some("engine".to_string()),
+            cpu: Some("AMD Ryzen 7 7840U w/ Radeon 780M Graphics (16) @ 5.13GHz".to_string()),
+            ..Info::default()
+        };
+        let mut out = Vec::new();
+        render_to(&mut out, &info, &config);
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("CPU: AMD"));
+        for line in out.lines() {
+            assert!(visible_len(line) <= 40, "{:?} overflows 40 columns", line);
+        }
+    }
+
+
+    #[test]
+    fn every_json_key_resolves_to_a_field_module() {
+        // no `..Info::default()`: a new field must be filled in here to compile
+        let some = || Some("x".to_string());
+        let info = Info {
+            user: some(), hostname: some(), os: some(), os_id: some(), os_id_like: some(),
+            os_version_id: some(), os_build_id: some(), os_short: some(), kernel: some(), arch: some(),
+            kernel_installed: some(), public_ipv4: some(), public_ipv6: some(),
+            cpu_cores: Some((4, 8)), cpu_cache: some(), cpu_vendor: some(), cpu_uarch: some(),
+            gpu_vram: Some(vec![]), gpu_vram_used: Some(vec![]),
+            monitors: Some(vec![]), entropy: some(), users: Some(1), failed_units: Some(vec![]),
+            container: some(), wsl: some(), virtualization: some(), uptime: some(), uptime_seconds: Some(1),
+            boot_time: some(), boot_time_rfc3339: some(), boot_duration: Some((1.0, 2.0)), bootloader: some(),
+            packages: Some(vec![]), updates: Some(0), shell: some(), de: some(), wm: some(), init: some(),
+            terminal: some(), terminal_font: some(), term_size: some(), terminal_bg: some(),
+            cpu: some(), cpu_temp: some(), cpu_features: some(), cpu_governor: some(),
+            gpu: Some(vec![]), gpu_temps: Some(vec![]), gpu_active: Some(0), gpu_compositor: Some(0),
+            memory: Some(MemoryInfo::default()), swap: Some((0.0, 1.0)),
+            zram: Some(ZramSwap { devices: vec![], ratio: None }),
+            memory_pressure: Some(0.0), cpu_pressure: Some(0.0), io_pressure: Some(0.0),
+            partitions: Some(vec![]), network: Some(vec![]), gateway: some(), dns: some(), vpn: some(),
+            display: some(), battery: Some(Battery { capacity: 50, status: "Full".to_string(), power: None }),
+            power_source: some(), model: some(), chassis: some(), motherboard: some(), mobo_temp: some(),
+            fans: Some(vec![]), bios: some(), theme: some(), icons: some(), font: some(),
+            processes: Some(1), process_states: Some(ProcessStates::default()), cpu_freq: some(),
+            locale: some(), audio: some(),
+            custom: vec![(0, "label".to_string(), "x".to_string())],
+            from_cache: false,
+        };
+        let Some(JsonValue::Object(fields)) = JsonValue::parse(&info.to_json()) else {
+            panic!("to_json didn't produce an object");
+        };
+        for (key, _) in fields.iter().filter(|(key, _)| key != "custom") {
+            assert!(field_module(key).is_some(), "--field {} has no module toggle", key);
+        }
+    }
+
+
+    #[test]
+    fn amdgpu_vram_pairs_cards_by_pci_address() {
+        let root = scratch_dir("amdgpu-vram");
+        let drm = root.join("drm");
+        // card0 is the discrete GPU even though lspci lists the iGPU first
+        for (card, address, used, total) in [("card0", "0000:0c:00.0", "1073741824", "17179869184"),
+                                             ("card1", "0000:03:00.0", "268435456", "536870912")] {
+            let device = root.join("pci").join(address);
+            fs::create_dir_all(&device).unwrap();
+            fs::write(device.join("vendor"), "0x1002\n").unwrap();
+            fs::write(device.join("mem_info_vram_used"), used).unwrap();
+            fs::write(device.join("mem_info_vram_total"), total).unwrap();
+            fs::create_dir_all(drm.join(card)).unwrap();
+            std::os::unix::fs::symlink(&device, drm.join(card).join("device")).unwrap();
+        }
+        fs::create_dir_all(drm.join("card0-DP-1")).unwrap();
+        let gpus = vec!["AMD Raphael".to_string(), "AMD Navi 21 [Radeon RX 6800]".to_string()];
+        let slots = vec!["0000:03:00.0".to_string(), "0000:0c:00.0".to_string()];
+        assert_eq!(read_amdgpu_vram(&drm, &gpus, &slots), vec![Some((0.25, 0.5)), Some((1.0, 16.0))]);
+        let _ = fs::remove_dir_all(&root);
+    }
+}